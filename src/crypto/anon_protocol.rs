@@ -2,12 +2,18 @@ use anyhow::{anyhow, Result};
 use std::fmt;
 use prost::Message as ProstMessage;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use ring::{aead, rand as ringrand, signature::Ed25519KeyPair};
+use ring::{aead, digest, rand as ringrand, signature::Ed25519KeyPair};
 use ring::rand::SecureRandom;
 
+// Şifreli mesajın başındaki nonce ve açık atlama sayacı
+const NONCE_LEN: usize = 12;
+const HOP_COUNT_LEN: usize = 4;
+
 // Kimliksiz mesaj türleri
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum MessageType {
+    // Varsayılan tür
+    #[default]
     Text = 0,
     Binary = 1,
     Command = 2,
@@ -25,11 +31,6 @@ impl MessageType {
             _ => None,
         }
     }
-    
-    // Varsayılan tür
-    pub fn default() -> Self {
-        MessageType::Text
-    }
 }
 
 impl fmt::Display for MessageType {
@@ -51,6 +52,8 @@ pub struct AnonMessage {
     pub temp_id: String,
     pub payload: Vec<u8>,
     pub signature: Vec<u8>,
+    // Mesajı ileten her düğüm bir artırır, imzaya dahil değildir.
+    // Şifreli pakette AEAD ek verisi olarak doğrulanır; anahtarı bilmeyen bir aracı sayacı değiştiremez
     pub hop_count: u32,
 }
 
@@ -140,7 +143,7 @@ impl TemporaryIdentity {
         let mut id_bytes = [0u8; 16];
         rng.fill(&mut id_bytes)?;
         
-        let id = hex::encode(&id_bytes);
+        let id = hex::encode(id_bytes);
         let now = SystemTime::now();
         let valid_until = now + valid_duration;
         
@@ -163,27 +166,39 @@ impl TemporaryIdentity {
     }
 }
 
+// Yayın konusundaki tüm düğümlerin paylaştığı mesaj anahtarı
+// Konuyu bilen herkes türetebilir; gizlilik sağlamaz, paketleri yalnızca konunun düğümlerine bağlar
+pub fn topic_key(topic: &str) -> [u8; 32] {
+    let mut context = digest::Context::new(&digest::SHA256);
+    context.update(b"kuantum-anon-v1");
+    context.update(topic.as_bytes());
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(context.finish().as_ref());
+    key
+}
+
 // Anonim mesaj oluşturucu
 pub struct AnonymousProtocol {
     current_identity: Option<TemporaryIdentity>,
     identity_duration: Duration,
+    // Mesajları şifreleyen ve atlama sayacını doğrulayan anahtar
+    message_key: [u8; 32],
 }
 
 impl AnonymousProtocol {
-    pub fn new(identity_duration: Duration) -> Self {
+    pub fn new(identity_duration: Duration, message_key: [u8; 32]) -> Self {
         Self {
             current_identity: None,
             identity_duration,
+            message_key,
         }
     }
     
     // Geçerli bir kimlik al veya yeni oluştur
     pub fn get_identity(&mut self) -> Result<&TemporaryIdentity> {
         // Önce geçerli kimliğin durumunu kontrol et
-        let should_create_new = match &self.current_identity {
-            Some(identity) if identity.is_valid() => false,
-            _ => true,
-        };
+        let should_create_new = !matches!(&self.current_identity, Some(identity) if identity.is_valid());
         
         // Geçerli değilse yeni oluştur
         if should_create_new {
@@ -224,7 +239,6 @@ impl AnonymousProtocol {
         message_data.extend_from_slice(&message.timestamp.to_be_bytes());
         message_data.extend_from_slice(message.temp_id.as_bytes());
         message_data.extend_from_slice(&message.payload);
-        message_data.extend_from_slice(&message.hop_count.to_be_bytes());
         
         // İmzala
         let signature = identity.sign(&message_data);
@@ -234,76 +248,117 @@ impl AnonymousProtocol {
     }
     
     // Mesajı şifreli bir paket içine koy (ChaCha20-Poly1305 ile)
+    // Paket: nonce | atlama sayacı | şifreli mesaj; sayaç ek veri olarak etikete bağlanır
     pub fn encrypt_message(&self, message: &AnonMessage) -> Result<Vec<u8>> {
         // Önce mesajı binary formata dönüştür
         let mut encoded = Vec::new();
-        message.encode(&mut encoded);
-        
-        // Şifreleme için anahtar ve nonce oluştur
+        message.encode(&mut encoded)?;
+
         let rng = ringrand::SystemRandom::new();
-        let mut key_bytes = [0u8; 32];
-        rng.fill(&mut key_bytes)?;
-        
-        let mut nonce_bytes = [0u8; 12];
+        let mut nonce_bytes = [0u8; NONCE_LEN];
         rng.fill(&mut nonce_bytes)?;
-        let nonce = aead::Nonce::assume_unique_for_key(nonce_bytes);
-        
-        // ChaCha20-Poly1305 ile şifrele
-        let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes)
-            .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
-        let key = aead::LessSafeKey::new(unbound_key);
-        
-        // Veriyi şifrele
+        let hop_count = message.hop_count.to_be_bytes();
+
         let mut in_out = encoded;
-        key.seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut in_out)
+        self.key()?
+            .seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce_bytes), aead::Aad::from(hop_count), &mut in_out)
             .map_err(|_| anyhow!("Şifreleme hatası"))?;
-        
-        // Şifrelenmiş verilere nonce'u ekle
-        let mut result = Vec::with_capacity(in_out.len() + nonce_bytes.len());
+
+        let mut result = Vec::with_capacity(NONCE_LEN + HOP_COUNT_LEN + in_out.len());
         result.extend_from_slice(&nonce_bytes);
+        result.extend_from_slice(&hop_count);
         result.extend_from_slice(&in_out);
-        
+
         Ok(result)
     }
-    
+
     // Şifreli paketi çöz
+    // Açık sayaç etiketle ve mesajın içindeki sayaçla eşleşmelidir
     pub fn decrypt_message(&self, encrypted: &[u8]) -> Result<AnonMessage> {
-        if encrypted.len() < 12 {
+        if encrypted.len() < NONCE_LEN + HOP_COUNT_LEN {
             return Err(anyhow!("Geçersiz şifrelenmiş mesaj"));
         }
-        
-        // Nonce'u ve şifrelenmiş veriyi ayır
-        let nonce_bytes = &encrypted[..12];
-        let ciphertext = &encrypted[12..];
-        
-        // Şifre çözme için anahtar oluştur (gerçekte bu doğru değil, 
-        // alıcının anahtarı bilmesi gerekir, ama bu örnek için basitleştirilmiştir)
-        let rng = ringrand::SystemRandom::new();
-        let mut key_bytes = [0u8; 32];
-        rng.fill(&mut key_bytes)?;
-        
-        let mut nonce_arr = [0u8; 12];
-        nonce_arr.copy_from_slice(nonce_bytes);
-        let nonce = aead::Nonce::assume_unique_for_key(nonce_arr);
-        
-        // ChaCha20-Poly1305 anahtarı oluştur
-        let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes)
-            .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
-        let key = aead::LessSafeKey::new(unbound_key);
-        
-        // Veriyi çöz
-        let mut in_out = ciphertext.to_vec();
-        key.open_in_place(nonce, aead::Aad::empty(), &mut in_out)
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        nonce_bytes.copy_from_slice(&encrypted[..NONCE_LEN]);
+        let mut hop_count = [0u8; HOP_COUNT_LEN];
+        hop_count.copy_from_slice(&encrypted[NONCE_LEN..NONCE_LEN + HOP_COUNT_LEN]);
+
+        let mut in_out = encrypted[NONCE_LEN + HOP_COUNT_LEN..].to_vec();
+        let plaintext = self.key()?
+            .open_in_place(aead::Nonce::assume_unique_for_key(nonce_bytes), aead::Aad::from(hop_count), &mut in_out)
             .map_err(|_| anyhow!("Şifre çözme hatası"))?;
-        
-        // Tag boyutunu çıkar
-        let tag_len = aead::CHACHA20_POLY1305.tag_len();
-        in_out.truncate(in_out.len() - tag_len);
-        
-        // Çözülmüş veriyi AnonMessage'a dönüştür
-        let message = AnonMessage::decode(&*in_out)
+
+        let message = AnonMessage::decode(&*plaintext)
             .map_err(|e| anyhow!("Mesaj çözme hatası: {}", e))?;
-        
+        if message.hop_count != u32::from_be_bytes(hop_count) {
+            return Err(anyhow!("Atlama sayacı mesajla eşleşmiyor"));
+        }
+
         Ok(message)
     }
-} 
\ No newline at end of file
+
+    // İletilecek mesajın sayacını artır ve yeni nonce ile yeniden şifrele
+    pub fn forward_message(&self, message: &AnonMessage) -> Result<Vec<u8>> {
+        let mut forwarded = message.clone();
+        forwarded.hop_count = forwarded.hop_count.checked_add(1)
+            .ok_or_else(|| anyhow!("Atlama sayacı taştı"))?;
+
+        self.encrypt_message(&forwarded)
+    }
+
+    fn key(&self) -> Result<aead::LessSafeKey> {
+        let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &self.message_key)
+            .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
+
+        Ok(aead::LessSafeKey::new(unbound_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol() -> AnonymousProtocol {
+        AnonymousProtocol::new(Duration::from_secs(60), topic_key("test"))
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let mut protocol = protocol();
+        let message = protocol.create_message(MessageType::Text, b"merhaba", 0).unwrap();
+        let encrypted = protocol.encrypt_message(&message).unwrap();
+
+        assert_eq!(protocol.decrypt_message(&encrypted).unwrap(), message);
+
+        // Başka konunun anahtarı mesajı açamaz
+        let other = AnonymousProtocol::new(Duration::from_secs(60), topic_key("diger"));
+        assert!(other.decrypt_message(&encrypted).is_err());
+    }
+
+    #[test]
+    fn tampered_hop_count_is_rejected() {
+        let mut protocol = protocol();
+        let message = protocol.create_message(MessageType::Text, b"veri", 3).unwrap();
+        let mut encrypted = protocol.encrypt_message(&message).unwrap();
+
+        // Açık sayacı sıfırlamak etiketi bozar
+        encrypted[NONCE_LEN..NONCE_LEN + HOP_COUNT_LEN].copy_from_slice(&0u32.to_be_bytes());
+        assert!(protocol.decrypt_message(&encrypted).is_err());
+    }
+
+    #[test]
+    fn forwarding_increments_authenticated_hop_count() {
+        let mut protocol = protocol();
+        let message = protocol.create_message(MessageType::Text, b"veri", 0).unwrap();
+        let mut encrypted = protocol.encrypt_message(&message).unwrap();
+
+        for expected in 0..3 {
+            let received = protocol.decrypt_message(&encrypted).unwrap();
+            assert_eq!(received.hop_count, expected);
+            assert_eq!(received.payload, b"veri");
+            encrypted = protocol.forward_message(&received).unwrap();
+        }
+        assert_eq!(encrypted[NONCE_LEN..NONCE_LEN + HOP_COUNT_LEN], 3u32.to_be_bytes());
+    }
+}
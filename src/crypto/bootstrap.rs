@@ -0,0 +1,277 @@
+use anyhow::{anyhow, Result};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Daha önce bağlanılabilen önyükleme düğümü, yeniden başlatmada tekrar denenir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedBootstrapPeer {
+    // `/p2p/<peer-id>` ile biten tam adres
+    pub address: String,
+    pub last_connected: u64,
+}
+
+// Bir önyükleme düğümünün bağlantı durumu
+struct BootstrapPeer {
+    address: Multiaddr,
+    connected: bool,
+    // Art arda başarısız bağlantı denemesi sayısı
+    failures: u32,
+    // Bir sonraki bağlantı denemesinin zamanı, bekleyen deneme yoksa None
+    next_dial: Option<Instant>,
+}
+
+// Önyükleme düğümlerine bağlanan, kopan bağlantıları artan beklemeyle yeniden deneyen
+// ve çalışan düğümleri diskte saklayan yönetici
+pub struct BootstrapManager {
+    path: PathBuf,
+    peers: HashMap<PeerId, BootstrapPeer>,
+    saved: Vec<SavedBootstrapPeer>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_saved: usize,
+}
+
+impl BootstrapManager {
+    // Kayıtlı önyükleme düğümlerini yükle, dosya yoksa boş başla
+    pub fn load(path: impl Into<PathBuf>, initial_backoff: Duration, max_backoff: Duration, max_saved: usize) -> Result<Self> {
+        let path = path.into();
+        let saved: Vec<SavedBootstrapPeer> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Önyükleme düğümü dosyası okunamadı ({}): {}", path.display(), e))?,
+            Err(_) => Vec::new(),
+        };
+
+        let mut manager = Self {
+            path,
+            peers: HashMap::new(),
+            saved: Vec::new(),
+            initial_backoff,
+            max_backoff,
+            max_saved,
+        };
+        for peer in &saved {
+            // Bozuk kayıtlar atlanır, bir sonraki kayıtta dosyadan da düşer
+            if let Ok((peer_id, address)) = parse_peer_addr(&peer.address) {
+                manager.add(peer_id, address);
+            }
+        }
+        manager.saved = saved;
+
+        Ok(manager)
+    }
+
+    // Önyükleme düğümlerini diske yaz
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&self.saved)?)?;
+
+        Ok(())
+    }
+
+    // Önyükleme düğümü ekle, ilk bağlantı denemesi hemen yapılır
+    pub fn add(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.peers.insert(peer_id, BootstrapPeer {
+            address,
+            connected: false,
+            failures: 0,
+            next_dial: Some(Instant::now()),
+        });
+    }
+
+    // Düğüm kimlikleri ve `/p2p` soneki olmayan adresleri
+    pub fn peers(&self) -> Vec<(PeerId, Multiaddr)> {
+        self.peers.iter().map(|(peer_id, peer)| (*peer_id, peer.address.clone())).collect()
+    }
+
+    // Zamanı gelen bağlantı denemelerinin tam adresleri
+    pub fn due_dials(&mut self) -> Vec<Multiaddr> {
+        let now = Instant::now();
+
+        self.peers.iter_mut()
+            .filter(|(_, peer)| !peer.connected && peer.next_dial.is_some_and(|at| at <= now))
+            .map(|(peer_id, peer)| {
+                peer.next_dial = None;
+                peer.address.clone().with(Protocol::P2p((*peer_id).into()))
+            })
+            .collect()
+    }
+
+    // Bağlantı kuruldu, bekleme sıfırlanır ve düğüm çalışanlar listesine yazılır
+    // Önyükleme düğümüyle ilk bağlantı kurulduysa true döner
+    pub fn on_connected(&mut self, peer_id: &PeerId) -> bool {
+        let peer = match self.peers.get_mut(peer_id) {
+            Some(peer) if !peer.connected => peer,
+            _ => return false,
+        };
+        peer.connected = true;
+        peer.failures = 0;
+        peer.next_dial = None;
+
+        let address = peer.address.clone().with(Protocol::P2p((*peer_id).into())).to_string();
+        self.saved.retain(|saved| saved.address != address);
+        self.saved.insert(0, SavedBootstrapPeer { address, last_connected: unix_now() });
+        self.saved.truncate(self.max_saved);
+
+        true
+    }
+
+    // Bağlantı koptu, bekleme süresi sonunda yeniden denenir
+    pub fn on_disconnected(&mut self, peer_id: &PeerId) -> Option<Duration> {
+        let peer = self.peers.get_mut(peer_id)?;
+        peer.connected = false;
+
+        Some(self.schedule_redial(peer_id))
+    }
+
+    // Bağlantı denemesi başarısız oldu, bekleme süresi iki katına çıkar
+    pub fn on_dial_failure(&mut self, peer_id: &PeerId) -> Option<Duration> {
+        let peer = self.peers.get_mut(peer_id)?;
+        if peer.connected {
+            return None;
+        }
+        peer.failures += 1;
+
+        Some(self.schedule_redial(peer_id))
+    }
+
+    fn schedule_redial(&mut self, peer_id: &PeerId) -> Duration {
+        let (initial, max) = (self.initial_backoff, self.max_backoff);
+        let peer = match self.peers.get_mut(peer_id) {
+            Some(peer) => peer,
+            None => return Duration::ZERO,
+        };
+
+        // Üstel bekleme, aynı anda kopan düğümler aynı anda denemesin diye rastgele pay eklenir
+        let backoff = initial.saturating_mul(1 << peer.failures.min(16)).min(max);
+        let backoff = backoff.mul_f64(thread_rng().gen_range(0.5..1.0));
+        peer.next_dial = Some(Instant::now() + backoff);
+
+        backoff
+    }
+}
+
+// `/p2p/<peer-id>` ile biten multiaddr'ı düğüm kimliği ve adrese ayır
+pub fn parse_peer_addr(value: &str) -> Result<(PeerId, Multiaddr)> {
+    let mut address: Multiaddr = value.parse()
+        .map_err(|e| anyhow!("Geçersiz adres ({}): {}", value, e))?;
+
+    match address.pop() {
+        Some(Protocol::P2p(hash)) => {
+            let peer_id = PeerId::from_multihash(hash)
+                .map_err(|_| anyhow!("Geçersiz peer kimliği: {}", value))?;
+            Ok((peer_id, address))
+        }
+        _ => Err(anyhow!("Adres /p2p/<peer-id> ile bitmeli: {}", value)),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(path: PathBuf) -> BootstrapManager {
+        BootstrapManager::load(path, Duration::from_secs(1), Duration::from_secs(8), 2).unwrap()
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("kuantum-bootstrap-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    fn peer_addr(port: u16) -> (PeerId, Multiaddr) {
+        let peer_id = PeerId::random();
+        (peer_id, format!("/ip4/127.0.0.1/tcp/{}", port).parse().unwrap())
+    }
+
+    #[test]
+    fn peer_address_must_end_with_peer_id() {
+        let peer_id = PeerId::random();
+        let (parsed, address) = parse_peer_addr(&format!("/ip4/10.0.0.1/tcp/4001/p2p/{}", peer_id)).unwrap();
+        assert_eq!(parsed, peer_id);
+        assert_eq!(address.to_string(), "/ip4/10.0.0.1/tcp/4001");
+
+        assert!(parse_peer_addr("/ip4/10.0.0.1/tcp/4001").is_err());
+        assert!(parse_peer_addr("adres değil").is_err());
+    }
+
+    #[test]
+    fn new_peer_is_dialed_once() {
+        let mut bootstrap = manager(temp_path());
+        let (peer_id, address) = peer_addr(4001);
+        bootstrap.add(peer_id, address.clone());
+
+        assert_eq!(bootstrap.due_dials(), vec![address.with(Protocol::P2p(peer_id.into()))]);
+        assert!(bootstrap.due_dials().is_empty());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_maximum() {
+        let mut bootstrap = manager(temp_path());
+        let (peer_id, address) = peer_addr(4001);
+        bootstrap.add(peer_id, address);
+
+        // Rastgele pay beklemeyi yarısına kadar kısaltabilir
+        for expected in [2, 4, 8, 8, 8] {
+            let backoff = bootstrap.on_dial_failure(&peer_id).unwrap();
+            let expected = Duration::from_secs(expected);
+            assert!(backoff >= expected / 2 && backoff <= expected, "{:?}", backoff);
+        }
+
+        // Bağlantı bekleme süresini sıfırlar, bağlıyken başarısız denemeler sayılmaz
+        assert!(bootstrap.on_connected(&peer_id));
+        assert!(!bootstrap.on_connected(&peer_id));
+        assert!(bootstrap.on_dial_failure(&peer_id).is_none());
+        let backoff = bootstrap.on_disconnected(&peer_id).unwrap();
+        assert!(backoff <= Duration::from_secs(1));
+
+        assert!(bootstrap.on_dial_failure(&PeerId::random()).is_none());
+    }
+
+    #[test]
+    fn connected_peers_are_saved_and_reloaded() {
+        let path = temp_path();
+        let mut bootstrap = manager(path.clone());
+        let peers: Vec<_> = (0..3).map(|i| peer_addr(4001 + i)).collect();
+        for (peer_id, address) in &peers {
+            bootstrap.add(*peer_id, address.clone());
+            bootstrap.on_connected(peer_id);
+        }
+        bootstrap.save().unwrap();
+
+        // En son bağlanılan iki düğüm saklanır
+        let mut reloaded: Vec<PeerId> = manager(path).peers().into_iter().map(|(peer_id, _)| peer_id).collect();
+        reloaded.sort();
+        let mut expected = vec![peers[1].0, peers[2].0];
+        expected.sort();
+        assert_eq!(reloaded, expected);
+    }
+
+    #[test]
+    fn corrupt_entries_are_skipped_on_load() {
+        let path = temp_path();
+        let (peer_id, address) = peer_addr(4001);
+        let saved = vec![
+            SavedBootstrapPeer { address: "bozuk".to_string(), last_connected: 0 },
+            SavedBootstrapPeer { address: address.with(Protocol::P2p(peer_id.into())).to_string(), last_connected: 0 },
+        ];
+        fs::write(&path, serde_json::to_string(&saved).unwrap()).unwrap();
+
+        let peers = manager(path).peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].0, peer_id);
+    }
+}
@@ -1,44 +1,223 @@
-use anyhow::Result;
-use rand::{thread_rng, Rng};
-use std::collections::HashMap;
+use anyhow::{anyhow, Result};
+use rand::{seq::SliceRandom, Rng};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::{Duration, Instant};
 use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+
+use super::hop_limit::HopToken;
+use super::onion_routing::{OnionPacket, RouteHop};
+use super::{os_rng, BoxedRng};
+
+// Crowds yolu kimliği
+pub type CrowdPathId = [u8; 16];
+
+// Crowds yol durumu ne kadar saklanır
+const CROWD_PATH_LIFETIME: Duration = Duration::from_secs(600);
+// Düğüm başına gecikmenin üst sınırı
+// Aracı düğümler daha uzun gecikme isteyen paketleri atar, paketler kuyrukta bellek tüketemez
+pub const MAX_HOP_DELAY: Duration = Duration::from_secs(60);
+// Bu puanın altındaki eşler rotalara alınmaz
+const DEFAULT_MIN_ROUTE_SCORE: f64 = -20.0;
+
+// Crowds paketinin yönü
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrowdDirection {
+    Request,
+    Reply,
+}
+
+// Jondo'lar arasında rastgele yürüyüşle taşınan paket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrowdPacket {
+    pub path_id: CrowdPathId,
+    pub destination: Vec<u8>,
+    pub hop_count: u32,
+    // hop_count'u doğrulayan özet zinciri, her atlamada ilerletilir
+    pub hop_token: HopToken,
+    pub direction: CrowdDirection,
+    // İstekte hedefe mühürlü içerik, cevapta başlatıcının anahtarıyla şifreli içerik
+    pub sealed: Vec<u8>,
+}
+
+impl CrowdPacket {
+    // Atlamalar boyunca değişmeyen döngü anahtarı
+    // Sayaç her atlamada ilerlediğinden paketin kendisi değil yol kimliği ve yön kullanılır
+    pub fn loop_key(&self) -> Vec<u8> {
+        let mut key = self.path_id.to_vec();
+        key.push(match self.direction {
+            CrowdDirection::Request => 0,
+            CrowdDirection::Reply => 1,
+        });
+        key
+    }
+}
+
+// Hedefe mühürlenen istek içeriği
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrowdRequest {
+    pub body: Vec<u8>,
+    // Hedefin cevabı şifreleyeceği anahtar
+    pub reply_key: [u8; 32],
+    // Başlatıcının kurduğu atlama sayacının çapası, yolda sayacı yeniden kuran düğüm hedefte fark edilir
+    pub hop_anchor: [u8; 32],
+}
+
+// Başlatıcının anahtarıyla şifrelenen cevap içeriği
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrowdReply {
+    pub body: Vec<u8>,
+    // Hedefin kurduğu cevap sayacının çapası
+    pub hop_anchor: [u8; 32],
+}
+
+// Paketi alan jondo'nun kararı
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrowdDecision {
+    // Rastgele bir jondo'ya ilet
+    Forward(PeerId),
+    // Doğrudan hedefe teslim et
+    Deliver,
+}
+
+// Cevabın bu düğümden sonra nereye gideceği
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrowdReturnHop {
+    // Yolu bu düğüm başlattı, cevap burada açılır
+    Initiator,
+    // Paketi bize gönderen düğüm
+    Predecessor(PeerId),
+}
+
+// Bir jondo'nun yol için tuttuğu durum
+struct CrowdPathState {
+    return_hop: CrowdReturnHop,
+    // İsteğin bu düğüme ilk gelişindeki sayaç çapası
+    hop_anchor: [u8; 32],
+    created_at: Instant,
+}
 
 // Kaotik yönlendirme sistemi
 // Mesajların rastgele yönlendirilmesi için kullanılır
 pub struct ChaoticRouter {
     forward_probability: f32,  // Mesajı yönlendirme olasılığı
     max_hops: u32,            // Maksimum atlama sayısı
+    mix_mean_delay: Option<Duration>, // Mixnet modunda düğüm başına ortalama bekleme
     current_routes: HashMap<String, Vec<PeerId>>, // Mevcut rotalar
+    crowd_paths: HashMap<CrowdPathId, CrowdPathState>, // Cevapların geri döneceği yollar
+    reputation: HashMap<PeerId, f64>, // Eş puanları, listede olmayan eşler 0 sayılır
+    min_route_score: f64,     // Rotaya alınmak için gereken en düşük puan
+    rng: BoxedRng,            // Tüm rastgele kararların kaynağı
 }
 
 impl ChaoticRouter {
     // Yeni bir kaotik yönlendirici oluştur
-    pub fn new(forward_probability: f32, max_hops: u32) -> Self {
+    // mix_mean_delay verilirse yüksek gecikmeli mixnet modu etkinleşir
+    pub fn new(forward_probability: f32, max_hops: u32, mix_mean_delay: Option<Duration>) -> Self {
+        Self::with_rng(forward_probability, max_hops, mix_mean_delay, os_rng())
+    }
+    
+    // Verilen RNG ile yönlendirici oluştur
+    // Sabit tohumlu bir RNG ile tüm yönlendirme kararları tekrar üretilebilir
+    pub fn with_rng(forward_probability: f32, max_hops: u32, mix_mean_delay: Option<Duration>, rng: BoxedRng) -> Self {
         Self {
             forward_probability,
             max_hops,
+            mix_mean_delay,
             current_routes: HashMap::new(),
+            crowd_paths: HashMap::new(),
+            reputation: HashMap::new(),
+            min_route_score: DEFAULT_MIN_ROUTE_SCORE,
+            rng,
+        }
+    }
+    
+    // Eş puanlarını güncelle, düşük puanlı eşler sonraki rotalarda kullanılmaz
+    pub fn set_reputation(&mut self, reputation: HashMap<PeerId, f64>) {
+        self.reputation = reputation;
+    }
+    
+    // Eşin puanı rotaya alınmasına yetiyor mu
+    pub fn is_eligible(&self, peer_id: &PeerId) -> bool {
+        self.reputation.get(peer_id).is_none_or(|score| *score >= self.min_route_score)
+    }
+    
+    fn eligible_peers(&self, peers: &[PeerId]) -> Vec<PeerId> {
+        peers.iter().filter(|peer_id| self.is_eligible(peer_id)).cloned().collect()
+    }
+    
+    // Mixnet modunu aç ya da kapat
+    pub fn set_mix_mean_delay(&mut self, mix_mean_delay: Option<Duration>) {
+        self.mix_mean_delay = mix_mean_delay;
+    }
+    
+    // Bir paketin alabileceği en fazla atlama
+    pub fn max_hops(&self) -> u32 {
+        self.max_hops
+    }
+    
+    // max_hops ile sınırlı yeni bir atlama sayacı oluştur
+    // Sayaç rastgele bir başlangıç değerine ilerletilir, ilk jondo sayaçtan yolun başında
+    // olduğunu çıkaramaz. Başlangıç en fazla max_hops'un yarısıdır, yürüyüşe bütçenin yarısı kalır.
+    // reserved_hops, yolun bu sayaçla yeniden yürüneceği atlama sayısıdır (cevaplar için isteğin sayacı)
+    pub fn new_hop_token(&mut self, reserved_hops: u32) -> (HopToken, u32) {
+        let mut token = HopToken::new(self.max_hops, &mut self.rng);
+        let max_offset = (self.max_hops / 2).min(self.max_hops.saturating_sub(reserved_hops));
+        let offset = self.rng.gen_range(0..=max_offset);
+        for _ in 0..offset {
+            token.advance();
+        }
+
+        (token, offset)
+    }
+    
+    // Mixnet modunun ortalama düğüm gecikmesi
+    pub fn mix_mean_delay(&self) -> Option<Duration> {
+        self.mix_mean_delay
+    }
+    
+    // Üstel dağılımdan bir düğüm gecikmesi örnekle
+    // Gecikmelerin üstel olması düğümden çıkışları Poisson sürecine çevirir
+    // Aracı düğümler MAX_HOP_DELAY'i aşan paketleri attığından dağılım bu sınırda kesilir.
+    // Örnek kesik üstel dağılımın ters dağılım fonksiyonundan alınır; sınıra yığılma olmaz,
+    // gözlemci sınırdaki gecikmeden paketin bu düğümden geçtiğini çıkaramaz
+    pub fn sample_hop_delay(&mut self) -> Duration {
+        match self.mix_mean_delay {
+            Some(mean) if !mean.is_zero() => {
+                let tail = (-MAX_HOP_DELAY.as_secs_f64() / mean.as_secs_f64()).exp();
+                let uniform: f64 = self.rng.gen_range(0.0..1.0);
+                let delay = mean.mul_f64(-(1.0 - uniform * (1.0 - tail)).ln());
+                delay.min(MAX_HOP_DELAY)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+    
+    // Rotadaki her aracı düğüm için gönderen tarafından seçilen gecikmeyi ata
+    pub fn apply_hop_delays(&mut self, route: &mut [RouteHop]) {
+        for hop in route.iter_mut() {
+            hop.delay = self.sample_hop_delay();
         }
     }
     
     // Mesajın yönlendirilip yönlendirilmeyeceğine karar ver
-    pub fn should_forward(&self) -> bool {
-        let mut rng = thread_rng();
-        rng.gen::<f32>() < self.forward_probability
+    pub fn should_forward(&mut self) -> bool {
+        self.rng.gen::<f32>() < self.forward_probability
     }
     
     // Rastgele bir rota oluştur
-    pub fn generate_random_route(&self, available_peers: &[PeerId], hop_count: u32) -> Vec<PeerId> {
+    pub fn generate_random_route(&mut self, available_peers: &[PeerId], hop_count: u32) -> Vec<PeerId> {
+        let available_peers = self.eligible_peers(available_peers);
         if available_peers.is_empty() || hop_count == 0 {
             return Vec::new();
         }
         
-        let mut rng = thread_rng();
         let actual_hops = std::cmp::min(hop_count, self.max_hops);
         let mut route = Vec::with_capacity(actual_hops as usize);
         
         for _ in 0..actual_hops {
-            if let Some(peer) = available_peers.get(rng.gen_range(0..available_peers.len())) {
+            if let Some(peer) = available_peers.get(self.rng.gen_range(0..available_peers.len())) {
                 route.push(*peer);
             }
         }
@@ -46,9 +225,38 @@ impl ChaoticRouter {
         route
     }
     
+    // Birbiriyle ortak düğümü olmayan rotalar oluştur
+    // Yeterli düğüm yoksa rotalar kısaltılır; her rotaya en az bir aracı düşmüyorsa hata döner
+    pub fn generate_disjoint_routes(&mut self, available_peers: &[PeerId], route_count: usize, hop_count: u32) -> Result<Vec<Vec<PeerId>>> {
+        if route_count == 0 {
+            return Ok(Vec::new());
+        }
+        
+        let mut peers = self.eligible_peers(available_peers);
+        peers.sort();
+        peers.dedup();
+        peers.shuffle(&mut self.rng);
+        
+        let hops_per_route = std::cmp::min(
+            std::cmp::min(hop_count, self.max_hops) as usize,
+            peers.len() / route_count,
+        );
+        if hops_per_route == 0 {
+            return Err(anyhow!(
+                "{} ayrık rota için yeterli aracı düğüm yok ({} uygun düğüm)",
+                route_count,
+                peers.len()
+            ));
+        }
+        
+        Ok((0..route_count)
+            .map(|index| peers[index * hops_per_route..(index + 1) * hops_per_route].to_vec())
+            .collect())
+    }
+    
     // Mesaj için yeni bir rota oluştur ve kaydet
     pub fn create_route(&mut self, message_id: &str, available_peers: &[PeerId]) -> Result<Vec<PeerId>> {
-        let hop_count = thread_rng().gen_range(1..=self.max_hops);
+        let hop_count = self.rng.gen_range(1..=self.max_hops);
         let route = self.generate_random_route(available_peers, hop_count);
         
         self.current_routes.insert(message_id.to_string(), route.clone());
@@ -65,4 +273,215 @@ impl ChaoticRouter {
     pub fn clear_route(&mut self, message_id: &str) {
         self.current_routes.remove(message_id);
     }
-} 
\ No newline at end of file
+    
+    // Crowds modu: paketi rastgele bir jondo'ya mı iletmeli yoksa hedefe mi teslim etmeli
+    // Bir sonraki atlamada max_hops'a ulaşacak paketler her zaman hedefe teslim edilir
+    pub fn next_crowd_hop(&mut self, hop_count: u32, available_peers: &[PeerId]) -> CrowdDecision {
+        let available_peers = self.eligible_peers(available_peers);
+        if hop_count + 1 >= self.max_hops || available_peers.is_empty() || !self.should_forward() {
+            return CrowdDecision::Deliver;
+        }
+        
+        let peer = available_peers[self.rng.gen_range(0..available_peers.len())];
+        CrowdDecision::Forward(peer)
+    }
+    
+    // Yolu kaydet, cevaplar aynı rastgele yürüyüşle geri döner
+    // Döngüde yol ikinci kez görülürse ilk önceki düğüm korunur
+    pub fn record_crowd_path(&mut self, path_id: CrowdPathId, return_hop: CrowdReturnHop, hop_anchor: [u8; 32]) {
+        self.crowd_paths.entry(path_id).or_insert_with(|| CrowdPathState {
+            return_hop,
+            hop_anchor,
+            created_at: Instant::now(),
+        });
+    }
+    
+    // Yol için cevabın iletileceği yer
+    pub fn crowd_return_hop(&self, path_id: &CrowdPathId) -> Option<CrowdReturnHop> {
+        self.crowd_paths.get(path_id).map(|state| state.return_hop)
+    }
+    
+    // Yolun ilk görüldüğündeki sayaç çapası
+    pub fn crowd_path_anchor(&self, path_id: &CrowdPathId) -> Option<[u8; 32]> {
+        self.crowd_paths.get(path_id).map(|state| state.hop_anchor)
+    }
+    
+    // Süresi dolan yol durumlarını temizle
+    pub fn prune_crowd_paths(&mut self) {
+        self.crowd_paths.retain(|_, state| state.created_at.elapsed() <= CROWD_PATH_LIFETIME);
+    }
+}
+
+// Mixnet modunda iletilmeyi bekleyen paketlerin zamanlı kuyruğu
+#[derive(Default)]
+pub struct MixQueue {
+    queue: BinaryHeap<Reverse<(Instant, u64)>>,
+    packets: HashMap<u64, (PeerId, OnionPacket)>,
+    next_sequence: u64,
+    // Kuyruktaki paketlerin toplam boyutu
+    buffered_bytes: usize,
+}
+
+impl MixQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    // Paketi belirtilen süre sonra iletilmek üzere kuyruğa ekle
+    pub fn push(&mut self, delay: Duration, next_hop: PeerId, packet: OnionPacket) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        
+        self.queue.push(Reverse((Instant::now() + delay, sequence)));
+        self.buffered_bytes += packet_size(&packet);
+        self.packets.insert(sequence, (next_hop, packet));
+    }
+    
+    // Bekleme süresi dolan paketleri kuyruktan çıkar
+    pub fn pop_due(&mut self) -> Vec<(PeerId, OnionPacket)> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        
+        while let Some(Reverse((deliver_at, sequence))) = self.queue.peek().copied() {
+            if deliver_at > now {
+                break;
+            }
+            
+            self.queue.pop();
+            if let Some(entry) = self.packets.remove(&sequence) {
+                self.buffered_bytes -= packet_size(&entry.1);
+                due.push(entry);
+            }
+        }
+        
+        due
+    }
+    
+    // Kuyruktaki paket sayısı
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+    
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+}
+
+fn packet_size(packet: &OnionPacket) -> usize {
+    packet.header.len() + packet.payload.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::seeded_rng;
+
+    #[test]
+    fn hop_delays_follow_truncated_exponential() {
+        let mean = MAX_HOP_DELAY / 2;
+        let mut router = ChaoticRouter::with_rng(0.5, 10, Some(mean), seeded_rng(7));
+
+        let delays: Vec<Duration> = (0..20_000).map(|_| router.sample_hop_delay()).collect();
+        assert!(delays.iter().all(|delay| *delay < MAX_HOP_DELAY));
+
+        // Kesik dağılımda son 5 saniyenin payı ~%2.8'dir, sınıra kırpma ~%13.5 yığılma yaratırdı
+        let near_cap = MAX_HOP_DELAY - Duration::from_secs(5);
+        let tail = delays.iter().filter(|delay| **delay >= near_cap).count() as f64 / delays.len() as f64;
+        assert!((0.015..0.045).contains(&tail), "sınır yakınındaki pay: {}", tail);
+
+        // Kesik dağılımın ortalaması 30 - 60 e^-2 / (1 - e^-2) ≈ 20.6 saniyedir
+        let average = delays.iter().sum::<Duration>().as_secs_f64() / delays.len() as f64;
+        assert!((19.8..21.4).contains(&average), "ortalama: {}", average);
+    }
+
+    #[test]
+    fn disjoint_routes_share_no_peers() {
+        let peers: Vec<PeerId> = (0..10).map(|_| PeerId::random()).collect();
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(1));
+
+        let routes = router.generate_disjoint_routes(&peers, 3, 3).unwrap();
+        assert_eq!(routes.len(), 3);
+        assert!(routes.iter().all(|route| route.len() == 3));
+
+        let mut used: Vec<PeerId> = routes.into_iter().flatten().collect();
+        used.sort();
+        used.dedup();
+        assert_eq!(used.len(), 9);
+    }
+
+    #[test]
+    fn disjoint_routes_fail_without_enough_peers() {
+        let peers: Vec<PeerId> = (0..2).map(|_| PeerId::random()).collect();
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(1));
+
+        assert_eq!(router.generate_disjoint_routes(&peers, 2, 3).unwrap().len(), 2);
+        assert!(router.generate_disjoint_routes(&peers, 3, 3).is_err());
+    }
+
+    #[test]
+    fn loop_key_is_invariant_across_hops() {
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(3));
+        let (hop_token, hop_count) = router.new_hop_token(0);
+        let mut packet = CrowdPacket {
+            path_id: [9; 16],
+            destination: Vec::new(),
+            hop_count,
+            hop_token,
+            direction: CrowdDirection::Request,
+            sealed: Vec::new(),
+        };
+        let key = packet.loop_key();
+
+        packet.hop_count += 1;
+        packet.hop_token.advance();
+        assert_eq!(packet.loop_key(), key);
+
+        packet.direction = CrowdDirection::Reply;
+        assert_ne!(packet.loop_key(), key);
+    }
+
+    #[test]
+    fn hop_tokens_start_at_random_verified_offsets() {
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(5));
+        let mut offsets = Vec::new();
+
+        for _ in 0..100 {
+            let (token, offset) = router.new_hop_token(0);
+            token.verify(offset, 10).unwrap();
+            assert!(offset <= 5);
+            offsets.push(offset);
+
+            // Cevap sayacı isteğin kullandığı atlamalardan sonra kalan bütçeye sığar
+            let (_, reply_offset) = router.new_hop_token(8);
+            assert!(reply_offset <= 2);
+        }
+
+        // Başlangıç değeri sabit olsaydı ilk jondo yolun başında olduğunu bilirdi
+        offsets.sort();
+        offsets.dedup();
+        assert_eq!(offsets, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn first_path_record_wins() {
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(3));
+        let first = PeerId::random();
+
+        router.record_crowd_path([1; 16], CrowdReturnHop::Predecessor(first), [1; 32]);
+        router.record_crowd_path([1; 16], CrowdReturnHop::Predecessor(PeerId::random()), [2; 32]);
+
+        assert_eq!(router.crowd_return_hop(&[1; 16]), Some(CrowdReturnHop::Predecessor(first)));
+        assert_eq!(router.crowd_path_anchor(&[1; 16]), Some([1; 32]));
+    }
+
+    #[test]
+    fn hop_delay_is_zero_without_mixnet() {
+        let mut router = ChaoticRouter::with_rng(0.5, 10, None, seeded_rng(7));
+
+        assert_eq!(router.sample_hop_delay(), Duration::ZERO);
+    }
+}
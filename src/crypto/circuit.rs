@@ -0,0 +1,178 @@
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::flow_control::{CircuitFlow, CongestionController, Priority, ReceiveWindow};
+use super::onion_routing::RouteHop;
+use super::reply_block::ReplyBlock;
+use super::stream_mux::{ReorderBuffer, StreamFrame, StreamId};
+
+// Devre kimliği
+pub type CircuitId = [u8; 16];
+
+// SENDME gelmeden gönderilebilecek en fazla hücre
+pub const CIRCUIT_WINDOW: u32 = 100;
+// Alıcı her bu kadar hücrede bir SENDME gönderir
+pub const SENDME_INCREMENT: u32 = 10;
+// Tıkanıklık penceresinin başlangıç, en küçük ve en büyük değerleri
+const INITIAL_CWND: u32 = 20;
+const MIN_CWND: u32 = SENDME_INCREMENT;
+const MAX_CWND: u32 = CIRCUIT_WINDOW;
+// Hedefin geri yönde kullanabileceği cevap bloğu sayısı
+pub const BACKWARD_REPLY_BLOCKS: usize = 8;
+// Bir hücreye eklenebilecek en fazla cevap bloğu
+pub const MAX_REPLY_BLOCKS_PER_CELL: usize = 4;
+
+// Devre üzerinden taşınan hücreler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CircuitCell {
+    Data {
+        circuit_id: CircuitId,
+        seq: u64,
+        frame: StreamFrame,
+        // SENDME istenen hücrelerde alıcının onayı geri göndereceği cevap bloğu
+        sendme_block: Option<ReplyBlock>,
+        // Hedefin geri yönde akış verisi göndermek için kullanacağı cevap blokları
+        reply_blocks: Vec<ReplyBlock>,
+    },
+    Sendme {
+        circuit_id: CircuitId,
+        acked_seq: u64,
+    },
+    // Hedeften devreyi açan tarafa cevap bloğuyla dönen çerçeve
+    Backward {
+        circuit_id: CircuitId,
+        seq: u64,
+        frame: StreamFrame,
+    },
+}
+
+// Bu düğümün açtığı devre
+pub struct OutboundCircuit {
+    pub id: CircuitId,
+    // Hedef dahil tüm rota
+    pub route: Vec<RouteHop>,
+    pub flow: CircuitFlow<StreamFrame>,
+    // Açık akışlar ve gönderim öncelikleri
+    pub stream_priorities: HashMap<StreamId, Priority>,
+    // Hedefe verilip henüz kullanılmamış cevap blokları
+    pub backward_credit: usize,
+    pub backward: ReorderBuffer<StreamFrame>,
+    // Proxy akışlarını ayıran yalıtım anahtarı, yalıtılmış devreler yalnızca aynı anahtarla paylaşılır
+    pub isolation: Option<String>,
+    pub created_at: Instant,
+}
+
+impl OutboundCircuit {
+    pub fn new(id: CircuitId, route: Vec<RouteHop>, isolation: Option<String>) -> Self {
+        Self {
+            id,
+            route,
+            flow: CircuitFlow::new(
+                CIRCUIT_WINDOW,
+                SENDME_INCREMENT,
+                CongestionController::new(INITIAL_CWND, MIN_CWND, MAX_CWND, SENDME_INCREMENT),
+            ),
+            stream_priorities: HashMap::new(),
+            backward_credit: 0,
+            backward: ReorderBuffer::new(),
+            isolation,
+            created_at: Instant::now(),
+        }
+    }
+
+    // Yalıtılmış devre son akışı da kapandıktan ve kuyruğu boşaldıktan sonra kapatılabilir
+    pub fn is_finished(&self) -> bool {
+        self.isolation.is_some() && self.stream_priorities.is_empty() && self.flow.queued() == 0
+    }
+
+    // Hedefin cevap bloğu havuzunu doldurmak için gereken blok sayısı
+    pub fn missing_reply_blocks(&self) -> usize {
+        BACKWARD_REPLY_BLOCKS
+            .saturating_sub(self.backward_credit)
+            .min(MAX_REPLY_BLOCKS_PER_CELL)
+    }
+
+    pub fn destination(&self) -> Option<PeerId> {
+        self.route.last().map(|hop| hop.peer_id)
+    }
+
+    // Durum raporu için devre bilgisi
+    pub fn report(&self) -> CircuitReport {
+        CircuitReport {
+            id: hex::encode(&self.id[..4]),
+            destination: self.destination().map(|peer_id| peer_id.to_string()).unwrap_or_default(),
+            isolation: self.isolation.clone(),
+            window: self.flow.window().available(),
+            cwnd: self.flow.congestion().cwnd(),
+            in_flight: self.flow.congestion().in_flight(),
+            rtt_ms: self.flow.congestion().smoothed_rtt().map(|rtt| rtt.as_millis()),
+            queued: self.flow.queued(),
+            age_secs: self.created_at.elapsed().as_secs(),
+        }
+    }
+}
+
+// Bu düğümde sonlanan devre
+pub struct InboundCircuit {
+    pub window: ReceiveWindow,
+    pub forward: ReorderBuffer<StreamFrame>,
+    // Geri yönde gönderim için kullanılacak cevap blokları
+    pub reply_blocks: Vec<ReplyBlock>,
+    // Cevap bloğu gelene kadar bekleyen geri yön çerçeveleri
+    pub pending_backward: VecDeque<StreamFrame>,
+    pub next_backward_seq: u64,
+    pub last_seen: Instant,
+}
+
+impl InboundCircuit {
+    pub fn new() -> Self {
+        Self {
+            window: ReceiveWindow::new(CIRCUIT_WINDOW),
+            forward: ReorderBuffer::new(),
+            reply_blocks: Vec::new(),
+            pending_backward: VecDeque::new(),
+            next_backward_seq: 0,
+            last_seen: Instant::now(),
+        }
+    }
+
+    // Cevap bloğu varsa sıradaki geri yön çerçevesini sıra numarasıyla döndür
+    pub fn next_backward(&mut self) -> Option<(ReplyBlock, u64, StreamFrame)> {
+        if self.pending_backward.is_empty() {
+            return None;
+        }
+
+        let reply_block = self.reply_blocks.pop()?;
+        let frame = self.pending_backward.pop_front()?;
+        let seq = self.next_backward_seq;
+        self.next_backward_seq += 1;
+
+        Some((reply_block, seq, frame))
+    }
+
+    pub fn is_idle(&self, timeout: Duration) -> bool {
+        self.last_seen.elapsed() > timeout
+    }
+}
+
+impl Default for InboundCircuit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Durum raporunda gösterilen devre
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitReport {
+    pub id: String,
+    pub destination: String,
+    pub isolation: Option<String>,
+    pub window: u32,
+    pub cwnd: u32,
+    pub in_flight: u32,
+    pub rtt_ms: Option<u128>,
+    pub queued: usize,
+    pub age_secs: u64,
+}
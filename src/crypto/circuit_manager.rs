@@ -0,0 +1,722 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::circuit::{CircuitCell, CircuitId, CircuitReport, InboundCircuit, OutboundCircuit};
+use super::flow_control::Priority;
+use super::onion_routing::RouteHop;
+use super::reply_block::ReplyBlock;
+use super::stream_mux::{KuantumStream, StreamCommand, StreamFrame, StreamKey, StreamManager};
+
+// Devre mesajlarında bir hücrenin taşıdığı en fazla veri
+pub const CIRCUIT_CELL_SIZE: usize = 4096;
+
+// Devre bakımında kullanılan süreler
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitTimeouts {
+    // Bu süre içinde gelmeyen SENDME kayıp sayılır
+    pub sendme: Duration,
+    // Sırası gelmeyen hücre bu süreden sonra kayıp sayılır ve devrenin akışları kesilir
+    pub reorder: Duration,
+    // Bu süre boyunca hücre gelmeyen devreler unutulur
+    pub idle: Duration,
+}
+
+// Yöneticinin ağa gönderilmek üzere bıraktığı hücreler
+// Soğan paketleri ve cevap blokları düğüm tarafından oluşturulur, yönetici ağa erişmez
+pub enum CircuitOutput {
+    // Devrenin rotası üzerinden hedefe gidecek veri hücresi
+    // Gönderen, SENDME isteniyorsa onay için bir cevap bloğu ve `reply_blocks` kadar geri yön bloğu ekler
+    Forward {
+        route: Vec<RouteHop>,
+        circuit_id: CircuitId,
+        seq: u64,
+        frame: StreamFrame,
+        wants_sendme: bool,
+        reply_blocks: usize,
+    },
+    // Hedeften devreyi açan tarafa cevap bloğuyla dönen hücre
+    Reply { reply_block: ReplyBlock, cell: CircuitCell },
+}
+
+// Devre üzerinden alınan, akışa bağlı olmayan mesaj
+pub struct CircuitMessage {
+    pub circuit_id: CircuitId,
+    pub priority: Priority,
+    pub body: Vec<u8>,
+}
+
+// Devre bakımı sırasında bildirilen durumlar
+pub enum CircuitNotice {
+    // Zamanında gelmeyen SENDME'ler, devrenin penceresi küçültüldü
+    SendmesLost { circuit_id: CircuitId, lost: usize },
+    // Kayıp geri yön hücresi nedeniyle kapatılan devre
+    BackwardLost(CircuitId),
+    // Kayıp ileri yön hücresi nedeniyle kapatılan hedef devresi
+    ForwardLost(CircuitId),
+    // Tutamacı bırakılan akış kapatılamadı
+    CloseFailed { key: StreamKey, error: anyhow::Error },
+}
+
+// Bu düğümün açtığı ve bu düğümde sonlanan devreleri ve üzerlerindeki akışları yönetir
+// Akış komutlarını ve gelen hücreleri çerçevelere çevirir, pencerelerin izin verdiği hücreleri çıktı kuyruğuna ekler
+pub struct CircuitManager {
+    circuits: HashMap<CircuitId, OutboundCircuit>,
+    inbound: HashMap<CircuitId, InboundCircuit>,
+    streams: StreamManager,
+    timeouts: CircuitTimeouts,
+    outputs: Vec<CircuitOutput>,
+}
+
+impl CircuitManager {
+    pub fn new(streams: StreamManager, timeouts: CircuitTimeouts) -> Self {
+        Self {
+            circuits: HashMap::new(),
+            inbound: HashMap::new(),
+            streams,
+            timeouts,
+            outputs: Vec::new(),
+        }
+    }
+
+    // Hedef dahil verilen rota üzerinde yeni devre kaydet
+    pub fn add_circuit(&mut self, id: CircuitId, route: Vec<RouteHop>, isolation: Option<String>) {
+        self.circuits.insert(id, OutboundCircuit::new(id, route, isolation));
+    }
+
+    // Çıkış düğümüne giden ve aynı yalıtım anahtarını taşıyan açık devre
+    pub fn find_exit(&self, exit: &PeerId, isolation: &Option<String>) -> Option<CircuitId> {
+        self.circuits.values()
+            .find(|circuit| circuit.destination().as_ref() == Some(exit) && circuit.isolation == *isolation)
+            .map(|circuit| circuit.id)
+    }
+
+    // Kısaltılmış devre numarasından devreyi bul
+    pub fn find(&self, handle: &str) -> Result<CircuitId> {
+        self.circuits.keys()
+            .find(|id| hex::encode(&id[..4]) == handle)
+            .copied()
+            .ok_or_else(|| anyhow!("Devre bulunamadı: {}", handle))
+    }
+
+    pub fn reports(&self) -> Vec<CircuitReport> {
+        self.circuits.values().map(OutboundCircuit::report).collect()
+    }
+
+    pub fn stream_count(&self) -> usize {
+        self.streams.stream_count()
+    }
+
+    // Gönderilmeyi bekleyen hücreleri al
+    pub fn take_outputs(&mut self) -> Vec<CircuitOutput> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    // Veriyi devrenin kuyruğuna ekle, pencere izin verdikçe gönderilir
+    pub fn send_message(&mut self, id: CircuitId, priority: Priority, data: &[u8]) -> Result<()> {
+        let circuit = self.circuits.get_mut(&id)
+            .ok_or_else(|| anyhow!("Devre bulunamadı: {}", hex::encode(&id[..4])))?;
+
+        for chunk in data.chunks(CIRCUIT_CELL_SIZE) {
+            circuit.flow.push(priority, StreamFrame::Message { priority, body: chunk.to_vec() });
+        }
+        self.flush_circuit(id);
+
+        Ok(())
+    }
+
+    pub fn close(&mut self, id: &CircuitId) {
+        self.circuits.remove(id);
+        self.streams.close_circuit(id, true);
+    }
+
+    // Devre üzerinde yeni bir akış aç
+    // Dönen tutamaç AsyncRead + AsyncWrite olarak doğrudan kullanılabilir
+    pub fn open_stream(&mut self, circuit_id: CircuitId, priority: Priority, target: Option<String>) -> Result<KuantumStream> {
+        let circuit = self.circuits.get_mut(&circuit_id)
+            .ok_or_else(|| anyhow!("Devre bulunamadı: {}", hex::encode(&circuit_id[..4])))?;
+
+        let stream = self.streams.open(circuit_id, target.clone());
+        let stream_id = stream.key().stream_id;
+        circuit.stream_priorities.insert(stream_id, priority);
+        circuit.flow.push(Priority::Interactive, StreamFrame::Open { stream_id, target });
+        self.flush_circuit(circuit_id);
+
+        Ok(stream)
+    }
+
+    // Akış tutamaçlarından gelen komutu işle
+    pub fn handle_stream_command(&mut self, command: StreamCommand) -> Result<()> {
+        let (key, frame) = match command {
+            StreamCommand::Data { key, .. } if !self.streams.is_open(&key) => {
+                return Err(anyhow!("Akış kapalı: {}", key.handle()));
+            }
+            StreamCommand::Data { key, data } => (key, StreamFrame::Data { stream_id: key.stream_id, data }),
+            StreamCommand::Close { key } => {
+                // Karşı taraf zaten kapattıysa bildirecek bir şey yok
+                if !self.streams.close(&key) {
+                    return Ok(());
+                }
+
+                (key, StreamFrame::Close { stream_id: key.stream_id })
+            }
+            StreamCommand::Sendme { key } if !self.streams.is_open(&key) => return Ok(()),
+            StreamCommand::Sendme { key } => (key, StreamFrame::Sendme { stream_id: key.stream_id }),
+            // Bağlanma sonucunu yalnızca akışı kabul eden hedef bildirir
+            StreamCommand::Connected { key, .. } if key.outbound => {
+                return Err(anyhow!("Akışı açan taraf bağlanma sonucu gönderemez: {}", key.handle()));
+            }
+            StreamCommand::Connected { key, .. } if !self.streams.is_open(&key) => return Ok(()),
+            StreamCommand::Connected { key, result } => (key, StreamFrame::Connected { stream_id: key.stream_id, result }),
+        };
+
+        if !key.outbound {
+            return self.queue_backward(key.circuit_id, frame);
+        }
+
+        let circuit = self.circuits.get_mut(&key.circuit_id)
+            .ok_or_else(|| anyhow!("Akışın devresi kapalı: {}", key.handle()))?;
+
+        // Kapanış aynı öncelikte gider ki akışın verisini geçmesin
+        // SENDME karşı tarafın yazmasını beklettiği için öne alınır
+        let priority = match frame {
+            StreamFrame::Close { stream_id } => circuit.stream_priorities.remove(&stream_id),
+            StreamFrame::Sendme { .. } => Some(Priority::Interactive),
+            _ => circuit.stream_priorities.get(&key.stream_id).copied(),
+        }
+        .unwrap_or(Priority::Bulk);
+
+        circuit.flow.push(priority, frame);
+        self.flush_circuit(key.circuit_id);
+
+        Ok(())
+    }
+
+    // Pencere ve tıkanıklık denetiminin izin verdiği hücreleri çıktı kuyruğuna ekle
+    fn flush_circuit(&mut self, id: CircuitId) {
+        let circuit = match self.circuits.get_mut(&id) {
+            Some(circuit) => circuit,
+            None => return,
+        };
+
+        while let Some(ready) = circuit.flow.next_ready() {
+            // Hedefin geri yönde kullanacağı cevap bloklarını tamamla
+            let reply_blocks = circuit.missing_reply_blocks();
+            circuit.backward_credit += reply_blocks;
+
+            self.outputs.push(CircuitOutput::Forward {
+                route: circuit.route.clone(),
+                circuit_id: id,
+                seq: ready.seq,
+                frame: ready.item,
+                wants_sendme: ready.wants_sendme,
+                reply_blocks,
+            });
+        }
+    }
+
+    // Geri yön çerçevesini kuyruğa ekle, cevap bloğu oldukça gönderilir
+    fn queue_backward(&mut self, circuit_id: CircuitId, frame: StreamFrame) -> Result<()> {
+        self.inbound.get_mut(&circuit_id)
+            .ok_or_else(|| anyhow!("Bilinmeyen devre: {}", hex::encode(&circuit_id[..4])))?
+            .pending_backward
+            .push_back(frame);
+        self.flush_backward(circuit_id);
+
+        Ok(())
+    }
+
+    fn flush_backward(&mut self, circuit_id: CircuitId) {
+        let circuit = match self.inbound.get_mut(&circuit_id) {
+            Some(circuit) => circuit,
+            None => return,
+        };
+
+        while let Some((reply_block, seq, frame)) = circuit.next_backward() {
+            let cell = CircuitCell::Backward { circuit_id, seq, frame };
+            self.outputs.push(CircuitOutput::Reply { reply_block, cell });
+        }
+    }
+
+    // Zaman aşımına uğrayan SENDME'leri işle, kayıp hücreli ve boşta kalan devreleri kapat
+    pub fn maintain(&mut self) -> Vec<CircuitNotice> {
+        let mut notices = Vec::new();
+
+        for key in self.streams.abandoned() {
+            if let Err(error) = self.handle_stream_command(StreamCommand::Close { key }) {
+                notices.push(CircuitNotice::CloseFailed { key, error });
+            }
+        }
+
+        for id in self.circuits.keys().copied().collect::<Vec<_>>() {
+            if let Some(circuit) = self.circuits.get_mut(&id) {
+                let lost = circuit.flow.expire_sendmes(self.timeouts.sendme);
+                if lost > 0 {
+                    notices.push(CircuitNotice::SendmesLost { circuit_id: id, lost });
+                }
+
+                // Kayıp geri yön hücresi atlanamaz, devre ve akışları kapatılır
+                if circuit.backward.has_stale_gap(self.timeouts.reorder) {
+                    notices.push(CircuitNotice::BackwardLost(id));
+                    self.circuits.remove(&id);
+                    self.streams.reset_circuit(&id, true);
+                    continue;
+                }
+
+                // Açık akışı olan devrelerde hedefin cevap blokları tükenmesin
+                if !circuit.stream_priorities.is_empty() && circuit.missing_reply_blocks() > 0 && circuit.flow.queued() == 0 {
+                    circuit.flow.push(Priority::Interactive, StreamFrame::Refill);
+                }
+            }
+
+            self.flush_circuit(id);
+        }
+
+        // Akışları biten yalıtılmış devreler kapatılır, sonraki bağlantılar yeni devre kullanır
+        self.circuits.retain(|_, circuit| !circuit.is_finished());
+
+        let broken: Vec<CircuitId> = self.inbound.iter()
+            .filter(|(_, circuit)| circuit.forward.has_stale_gap(self.timeouts.reorder))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in broken {
+            notices.push(CircuitNotice::ForwardLost(id));
+            self.inbound.remove(&id);
+            self.streams.reset_circuit(&id, false);
+        }
+
+        let idle: Vec<CircuitId> = self.inbound.iter()
+            .filter(|(_, circuit)| circuit.is_idle(self.timeouts.idle))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in idle {
+            self.inbound.remove(&id);
+            self.streams.close_circuit(&id, false);
+        }
+
+        notices
+    }
+
+    // Hücre bu düğümde yeni bir hedef devresi başlatıyor mu
+    // Yeni devreler hücreyi getiren eşin kotasından düşülür
+    pub fn is_new_inbound(&self, cell: &CircuitCell) -> bool {
+        match cell {
+            CircuitCell::Data { circuit_id, .. } => !self.inbound.contains_key(circuit_id),
+            _ => false,
+        }
+    }
+
+    // Devre hücresini işle ve sırası gelen devre mesajlarını döndür
+    pub fn handle_cell(&mut self, cell: CircuitCell) -> Result<Vec<CircuitMessage>> {
+        let mut messages = Vec::new();
+
+        match cell {
+            CircuitCell::Data { circuit_id, seq, frame, sendme_block, reply_blocks } => {
+                let circuit = self.inbound.entry(circuit_id).or_default();
+                circuit.window.accept(seq)?;
+                circuit.last_seen = Instant::now();
+                circuit.reply_blocks.extend(reply_blocks);
+
+                // Gönderenin penceresini aç
+                if let Some(reply_block) = sendme_block {
+                    circuit.window.on_sendme(seq);
+                    let cell = CircuitCell::Sendme { circuit_id, acked_seq: seq };
+                    self.outputs.push(CircuitOutput::Reply { reply_block, cell });
+                }
+
+                // Çerçeveler gönderildikleri sırayla işlenir
+                for frame in circuit.forward.push(seq, frame) {
+                    self.deliver_stream_frame(circuit_id, false, frame, &mut messages)?;
+                }
+                self.flush_backward(circuit_id);
+            }
+            CircuitCell::Sendme { circuit_id, acked_seq } => {
+                self.circuits.get_mut(&circuit_id)
+                    .ok_or_else(|| anyhow!("Bilinmeyen devre için SENDME"))?
+                    .flow
+                    .on_sendme(acked_seq)?;
+                self.flush_circuit(circuit_id);
+            }
+            CircuitCell::Backward { circuit_id, seq, frame } => {
+                let circuit = self.circuits.get_mut(&circuit_id)
+                    .ok_or_else(|| anyhow!("Bilinmeyen devre için geri yön hücresi"))?;
+                circuit.backward_credit = circuit.backward_credit.saturating_sub(1);
+
+                for frame in circuit.backward.push(seq, frame) {
+                    self.deliver_stream_frame(circuit_id, true, frame, &mut messages)?;
+                }
+            }
+        }
+
+        Ok(messages)
+    }
+
+    // Sırası gelen çerçeveyi işle
+    // outbound, devrenin bu düğüm tarafından açıldığını belirtir
+    fn deliver_stream_frame(&mut self, circuit_id: CircuitId, outbound: bool, frame: StreamFrame, messages: &mut Vec<CircuitMessage>) -> Result<()> {
+        match frame {
+            StreamFrame::Message { priority, body } => messages.push(CircuitMessage { circuit_id, priority, body }),
+            StreamFrame::Open { stream_id, target } => {
+                // Akışları yalnızca devreyi açan taraf başlatabilir
+                if outbound {
+                    return Err(anyhow!("Hedef devre üzerinde akış açamaz"));
+                }
+                self.streams.accept(circuit_id, stream_id, target)?;
+            }
+            StreamFrame::Data { stream_id, data } => {
+                self.streams.deliver(&StreamKey { circuit_id, stream_id, outbound }, data)?;
+            }
+            StreamFrame::Sendme { stream_id } => {
+                self.streams.on_sendme(&StreamKey { circuit_id, stream_id, outbound })?;
+            }
+            StreamFrame::Close { stream_id } => {
+                let key = StreamKey { circuit_id, stream_id, outbound };
+                self.streams.close(&key);
+                if let Some(circuit) = self.circuits.get_mut(&circuit_id).filter(|_| outbound) {
+                    circuit.stream_priorities.remove(&stream_id);
+                }
+            }
+            StreamFrame::Connected { stream_id, result } => {
+                if !outbound {
+                    return Err(anyhow!("Bağlanma sonucu yalnızca hedeften gelebilir"));
+                }
+                self.streams.on_connected(&StreamKey { circuit_id, stream_id, outbound }, result)?;
+            }
+            StreamFrame::Refill => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::crypto::onion_routing::RelayKeyPair;
+    use crate::crypto::reply_block::ReplyBlockStore;
+    use crate::crypto::stream_mux::{ConnectResult, STREAM_CHUNK_SIZE, STREAM_WINDOW};
+    use crate::proxy::StreamRequest;
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+    use rand::thread_rng;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::timeout;
+
+    const TIMEOUTS: CircuitTimeouts = CircuitTimeouts {
+        sendme: Duration::from_secs(10),
+        reorder: Duration::from_millis(50),
+        idle: Duration::from_secs(600),
+    };
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+    const TICK: Duration = Duration::from_millis(10);
+
+    // Devreyi açan istemci ile hedef arasındaki bağlantı
+    // Hücreler soğan paketine sarılmadan karşı uca taşınır, cevap blokları gerçek bloklardır
+    pub(crate) struct TestLink {
+        pub(crate) client: CircuitManager,
+        pub(crate) exit: CircuitManager,
+        client_commands: mpsc::Receiver<StreamCommand>,
+        exit_commands: mpsc::Receiver<StreamCommand>,
+        _client_accepted: mpsc::UnboundedReceiver<KuantumStream>,
+        client_hop: RouteHop,
+        exit_hop: RouteHop,
+        reply_blocks: ReplyBlockStore,
+        // Hedefe ulaşan devre mesajları
+        pub(crate) messages: Vec<CircuitMessage>,
+    }
+
+    impl TestLink {
+        // Bağlantıyla birlikte hedefin kabul ettiği akışların alıcısını döndür
+        pub(crate) fn new() -> (Self, mpsc::UnboundedReceiver<KuantumStream>) {
+            let (client_streams, client_commands, client_accepted) = StreamManager::new();
+            let (exit_streams, exit_commands, exit_accepted) = StreamManager::new();
+            let hop = || RouteHop::new(PeerId::random(), RelayKeyPair::generate(&mut thread_rng()).public_bytes());
+
+            let link = Self {
+                client: CircuitManager::new(client_streams, TIMEOUTS),
+                exit: CircuitManager::new(exit_streams, TIMEOUTS),
+                client_commands,
+                exit_commands,
+                _client_accepted: client_accepted,
+                client_hop: hop(),
+                exit_hop: hop(),
+                reply_blocks: ReplyBlockStore::new(Duration::from_secs(600)),
+                messages: Vec::new(),
+            };
+
+            (link, exit_accepted)
+        }
+
+        pub(crate) fn open_circuit(&mut self, isolation: Option<String>) -> CircuitId {
+            let id: CircuitId = rand::random();
+            self.client.add_circuit(id, vec![self.exit_hop.clone()], isolation);
+            id
+        }
+
+        fn reply_block(&mut self) -> ReplyBlock {
+            self.reply_blocks.create(std::slice::from_ref(&self.client_hop), &mut thread_rng()).unwrap()
+        }
+
+        // Bekleyen akış komutlarını işle ve hücreleri karşı uca taşı
+        pub(crate) fn pump(&mut self) -> Result<()> {
+            loop {
+                while let Ok(Some(command)) = self.client_commands.try_next() {
+                    self.client.handle_stream_command(command)?;
+                }
+                while let Ok(Some(command)) = self.exit_commands.try_next() {
+                    self.exit.handle_stream_command(command)?;
+                }
+
+                let forward = self.client.take_outputs();
+                let backward = self.exit.take_outputs();
+                if forward.is_empty() && backward.is_empty() {
+                    return Ok(());
+                }
+
+                for output in forward {
+                    if let CircuitOutput::Forward { circuit_id, seq, frame, wants_sendme, reply_blocks, .. } = output {
+                        let sendme_block = if wants_sendme { Some(self.reply_block()) } else { None };
+                        let reply_blocks = (0..reply_blocks).map(|_| self.reply_block()).collect();
+                        let cell = CircuitCell::Data { circuit_id, seq, frame, sendme_block, reply_blocks };
+                        let messages = self.exit.handle_cell(cell)?;
+                        self.messages.extend(messages);
+                    }
+                }
+                for output in backward {
+                    if let CircuitOutput::Reply { cell, .. } = output {
+                        self.client.handle_cell(cell)?;
+                    }
+                }
+            }
+        }
+
+        // Akış isteklerini karşıla, komutları ve hücreleri istek sahibi vazgeçene kadar taşı
+        // Her istek çıkış düğümüne giden devrelerde akış açar, yalıtım anahtarı farklıysa yeni devre kurulur
+        pub(crate) async fn run(mut self, mut requests: tokio::sync::mpsc::UnboundedReceiver<StreamRequest>) {
+            let mut tick = tokio::time::interval(TICK);
+
+            loop {
+                tokio::select! {
+                    request = requests.recv() => match request {
+                        Some(request) => {
+                            let exit = self.exit_hop.peer_id;
+                            let circuit_id = match self.client.find_exit(&exit, &request.isolation) {
+                                Some(id) => id,
+                                None => self.open_circuit(request.isolation),
+                            };
+                            let stream = self.client.open_stream(circuit_id, Priority::Interactive, Some(request.target));
+                            let _ = request.respond.send(stream);
+                        }
+                        None => break,
+                    },
+                    Some(command) = self.client_commands.next() => {
+                        let _ = self.client.handle_stream_command(command);
+                    }
+                    Some(command) = self.exit_commands.next() => {
+                        let _ = self.exit.handle_stream_command(command);
+                    }
+                    _ = tick.tick() => {
+                        self.client.maintain();
+                        self.exit.maintain();
+                    }
+                }
+
+                let _ = self.pump();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_carries_data_both_ways_and_close_reaches_exit() {
+        let (mut link, mut accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let mut stream = link.client.open_stream(id, Priority::Interactive, Some("example.com:80".to_string())).unwrap();
+        link.pump().unwrap();
+
+        let mut remote = accepted.next().await.unwrap();
+        assert_eq!(remote.target(), Some("example.com:80"));
+        assert_eq!(remote.key().stream_id, stream.key().stream_id);
+        assert!(!remote.key().outbound);
+
+        stream.write_all(b"istek").await.unwrap();
+        remote.write_all(b"cevap").await.unwrap();
+        link.pump().unwrap();
+
+        let mut buffer = [0u8; 5];
+        remote.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"istek");
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"cevap");
+
+        stream.shutdown().await.unwrap();
+        link.pump().unwrap();
+        assert_eq!(remote.read(&mut buffer).await.unwrap(), 0);
+        assert_eq!(link.client.stream_count() + link.exit.stream_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn circuit_messages_arrive_in_order_and_in_cells() {
+        let (mut link, _accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let data: Vec<u8> = (0..CIRCUIT_CELL_SIZE * 2 + 10).map(|i| i as u8).collect();
+
+        link.client.send_message(id, Priority::Bulk, &data).unwrap();
+        link.pump().unwrap();
+
+        let sizes: Vec<usize> = link.messages.iter().map(|message| message.body.len()).collect();
+        assert_eq!(sizes, vec![CIRCUIT_CELL_SIZE, CIRCUIT_CELL_SIZE, 10]);
+        let received: Vec<u8> = link.messages.iter().flat_map(|message| message.body.clone()).collect();
+        assert_eq!(received, data);
+        assert!(link.messages.iter().all(|message| message.circuit_id == id && message.priority == Priority::Bulk));
+    }
+
+    #[tokio::test]
+    async fn bulk_echo_crosses_circuit_and_stream_windows() {
+        let (link, mut accepted) = TestLink::new();
+        let (requester, requests) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(link.run(requests));
+        tokio::spawn(async move {
+            while let Some(stream) = accepted.next().await {
+                let (mut reader, mut writer) = tokio::io::split(stream);
+                tokio::spawn(async move { tokio::io::copy(&mut reader, &mut writer).await });
+            }
+        });
+
+        let mut stream = crate::proxy::request_stream(&requester, PeerId::random(), "echo:7".to_string(), crate::proxy::Isolation::Shared)
+            .await
+            .unwrap();
+
+        // Devre ve akış pencerelerini birkaç kez dolduracak kadar veri, yankı hedefin cevap bloklarını tüketir
+        let payload: Vec<u8> = (0..STREAM_CHUNK_SIZE * STREAM_WINDOW as usize * 3).map(|i| i as u8).collect();
+        let mut echoed = vec![0u8; payload.len()];
+        let (mut reader, mut writer) = tokio::io::split(&mut stream);
+        let transfer = async { tokio::try_join!(writer.write_all(&payload), reader.read_exact(&mut echoed)) };
+        timeout(TEST_TIMEOUT, transfer).await.unwrap().unwrap();
+
+        assert!(echoed == payload);
+    }
+
+    #[tokio::test]
+    async fn destination_cannot_open_streams() {
+        let (mut link, _accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+
+        let cell = CircuitCell::Backward { circuit_id: id, seq: 0, frame: StreamFrame::Open { stream_id: 1, target: None } };
+        assert!(link.client.handle_cell(cell).is_err());
+        assert_eq!(link.client.stream_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn lost_forward_cell_resets_exit_streams() {
+        let (mut link, mut accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let _stream = link.client.open_stream(id, Priority::Interactive, None).unwrap();
+        link.pump().unwrap();
+        let mut remote = accepted.next().await.unwrap();
+
+        // İkinci hücre gelmeden üçüncüsü gelir
+        let cell = CircuitCell::Data {
+            circuit_id: id,
+            seq: 2,
+            frame: StreamFrame::Data { stream_id: remote.key().stream_id, data: b"veri".to_vec() },
+            sendme_block: None,
+            reply_blocks: Vec::new(),
+        };
+        link.exit.handle_cell(cell).unwrap();
+        assert!(link.exit.maintain().is_empty());
+
+        tokio::time::sleep(TIMEOUTS.reorder * 2).await;
+        let notices = link.exit.maintain();
+        assert!(matches!(&notices[..], [CircuitNotice::ForwardLost(lost)] if *lost == id));
+        assert!(!link.exit.is_new_inbound(&CircuitCell::Sendme { circuit_id: id, acked_seq: 0 }));
+        assert_eq!(link.exit.stream_count(), 0);
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(remote.read(&mut buffer).await.unwrap_err().kind(), std::io::ErrorKind::ConnectionReset);
+    }
+
+    #[tokio::test]
+    async fn lost_backward_cell_closes_circuit() {
+        let (mut link, _accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let mut stream = link.client.open_stream(id, Priority::Interactive, None).unwrap();
+        link.pump().unwrap();
+
+        let cell = CircuitCell::Backward { circuit_id: id, seq: 1, frame: StreamFrame::Refill };
+        link.client.handle_cell(cell).unwrap();
+        tokio::time::sleep(TIMEOUTS.reorder * 2).await;
+
+        let notices = link.client.maintain();
+        assert!(matches!(&notices[..], [CircuitNotice::BackwardLost(lost)] if *lost == id));
+        assert!(link.client.find(&hex::encode(&id[..4])).is_err());
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(stream.read(&mut buffer).await.unwrap_err().kind(), std::io::ErrorKind::ConnectionReset);
+    }
+
+    #[tokio::test]
+    async fn isolated_circuits_are_shared_by_key_and_closed_after_last_stream() {
+        let (mut link, _accepted) = TestLink::new();
+        let exit = link.exit_hop.peer_id;
+        let isolation = Some("host:example.com".to_string());
+        let isolated = link.open_circuit(isolation.clone());
+        let shared = link.open_circuit(None);
+
+        assert_eq!(link.client.find_exit(&exit, &isolation), Some(isolated));
+        assert_eq!(link.client.find_exit(&exit, &None), Some(shared));
+        assert_eq!(link.client.find_exit(&exit, &Some("host:other.org".to_string())), None);
+        assert_eq!(link.client.find_exit(&PeerId::random(), &None), None);
+
+        let mut stream = link.client.open_stream(isolated, Priority::Interactive, None).unwrap();
+        let _shared_stream = link.client.open_stream(shared, Priority::Interactive, None).unwrap();
+        link.pump().unwrap();
+        link.client.maintain();
+        assert_eq!(link.client.find_exit(&exit, &isolation), Some(isolated));
+
+        stream.shutdown().await.unwrap();
+        link.pump().unwrap();
+        link.client.maintain();
+        assert_eq!(link.client.find_exit(&exit, &isolation), None);
+        // Paylaşılan devre akışı olmasa da açık kalır
+        assert_eq!(link.client.find_exit(&exit, &None), Some(shared));
+    }
+
+    #[tokio::test]
+    async fn new_inbound_circuits_are_reported_once() {
+        let (mut link, _accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let cell = |seq| CircuitCell::Data { circuit_id: id, seq, frame: StreamFrame::Refill, sendme_block: None, reply_blocks: Vec::new() };
+
+        assert!(link.exit.is_new_inbound(&cell(0)));
+        link.exit.handle_cell(cell(0)).unwrap();
+        assert!(!link.exit.is_new_inbound(&cell(1)));
+        // Pencere dışındaki hücre reddedilir
+        assert!(link.exit.handle_cell(cell(u64::MAX)).is_err());
+    }
+
+    #[tokio::test]
+    async fn exit_reports_connect_result_to_stream_opener() {
+        let (mut link, mut accepted) = TestLink::new();
+        let id = link.open_circuit(None);
+        let mut stream = link.client.open_stream(id, Priority::Interactive, Some("example.com:80".to_string())).unwrap();
+        link.pump().unwrap();
+        let mut remote = accepted.next().await.unwrap();
+
+        remote.report_connect(ConnectResult::HostUnreachable).await.unwrap();
+        link.pump().unwrap();
+        assert_eq!(timeout(TEST_TIMEOUT, stream.connected()).await.unwrap(), ConnectResult::HostUnreachable);
+
+        // Akışı açan taraf sonuç bildiremez
+        let key = stream.key();
+        assert!(link.client.handle_stream_command(StreamCommand::Connected { key, result: ConnectResult::Succeeded }).is_err());
+        let cell = CircuitCell::Data {
+            circuit_id: id,
+            seq: 1,
+            frame: StreamFrame::Connected { stream_id: key.stream_id, result: ConnectResult::Succeeded },
+            sendme_block: None,
+            reply_blocks: Vec::new(),
+        };
+        assert!(link.exit.handle_cell(cell).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use libp2p::core::connection::ConnectionLimits;
+use libp2p::core::ConnectedPoint;
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+// Bağlantı sınırları ve boşta kalma süresi
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionConfig {
+    pub max_inbound: u32,
+    pub max_outbound: u32,
+    pub max_pending_inbound: u32,
+    pub max_pending_outbound: u32,
+    pub max_per_peer: u32,
+    // Aynı IP adresinden bağlanabilecek en fazla düğüm, geri döngü adresleri hariç
+    pub max_per_ip: usize,
+    // Bağlı düğüm sayısı bunu aşınca fazlalar çıkarılır, sert sınıra ulaşmadan yer açılır
+    pub target_peers: usize,
+    // Bağlı kalınacak en az düğüm, boşta olsalar da bunun altına inilmez
+    pub min_peers: usize,
+    pub idle_timeout: Duration,
+}
+
+impl ConnectionConfig {
+    // Swarm'ın uyguladığı sert sınırlar
+    pub fn swarm_limits(&self) -> ConnectionLimits {
+        ConnectionLimits::default()
+            .with_max_established_incoming(Some(self.max_inbound))
+            .with_max_established_outgoing(Some(self.max_outbound))
+            .with_max_pending_incoming(Some(self.max_pending_inbound))
+            .with_max_pending_outgoing(Some(self.max_pending_outbound))
+            .with_max_established_per_peer(Some(self.max_per_peer))
+    }
+}
+
+// Bağlı bir düğümün durumu
+struct PeerConnection {
+    connections: u32,
+    inbound: bool,
+    ip: Option<IpAddr>,
+    established_at: Instant,
+    last_activity: Instant,
+}
+
+// Durum raporunda gösterilen bağlantı özeti
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionReport {
+    pub connected_peers: usize,
+    pub inbound: usize,
+    pub outbound: usize,
+    pub distinct_subnets: usize,
+    pub evicted: u64,
+    pub idle_closed: u64,
+    pub rejected_per_ip: u64,
+}
+
+// Bağlantıları izleyen ve hangilerinin kapatılacağına karar veren yönetici
+// Çıkarma sırasında aynı alt ağda yoğunlaşan ve puanı düşük düğümler önce gider, korunan düğümlere dokunulmaz
+pub struct ConnectionManager {
+    config: ConnectionConfig,
+    peers: HashMap<PeerId, PeerConnection>,
+    evicted: u64,
+    idle_closed: u64,
+    rejected_per_ip: u64,
+}
+
+impl ConnectionManager {
+    pub fn new(config: ConnectionConfig) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+            evicted: 0,
+            idle_closed: 0,
+            rejected_per_ip: 0,
+        }
+    }
+
+    // Bağlantı kuruldu, aynı IP'den çok fazla düğüm bağlıysa false döner ve bağlantı kapatılmalıdır
+    pub fn on_established(&mut self, peer_id: PeerId, endpoint: &ConnectedPoint) -> bool {
+        let now = Instant::now();
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.connections += 1;
+            peer.last_activity = now;
+            return true;
+        }
+
+        let (inbound, address) = match endpoint {
+            ConnectedPoint::Dialer { address } => (false, address),
+            ConnectedPoint::Listener { send_back_addr, .. } => (true, send_back_addr),
+        };
+        let ip = ip_of(address);
+
+        if let Some(ip) = ip.filter(|ip| !ip.is_loopback()) {
+            let same_ip = self.peers.values().filter(|peer| peer.ip == Some(ip)).count();
+            if same_ip >= self.config.max_per_ip {
+                self.rejected_per_ip += 1;
+                return false;
+            }
+        }
+
+        self.peers.insert(peer_id, PeerConnection {
+            connections: 1,
+            inbound,
+            ip,
+            established_at: now,
+            last_activity: now,
+        });
+
+        true
+    }
+
+    // Düğümle son bağlantı kapandıysa kaydı sil
+    pub fn on_closed(&mut self, peer_id: &PeerId, remaining: u32) {
+        if remaining == 0 {
+            self.peers.remove(peer_id);
+        } else if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.connections = remaining;
+        }
+    }
+
+    // Düğümden uygulama verisi geldi ya da düğüme gönderildi
+    pub fn on_activity(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_activity = Instant::now();
+        }
+    }
+
+    // Kapatılacak düğümler: önce boşta kalanlar, sonra hedef sayıyı aşan fazlalar
+    // `min_peers` altına inilmez, korunan düğümler listeye girmez
+    pub fn select_for_closing(&mut self, scores: &HashMap<PeerId, f64>, protected: &HashSet<PeerId>) -> Vec<PeerId> {
+        let mut removable = self.peers.len().saturating_sub(self.config.min_peers);
+        let mut closing = Vec::new();
+
+        let mut idle: Vec<(&PeerId, &PeerConnection)> = self.peers.iter()
+            .filter(|(peer_id, peer)| !protected.contains(peer_id) && peer.last_activity.elapsed() >= self.config.idle_timeout)
+            .collect();
+        idle.sort_by_key(|(_, peer)| peer.last_activity);
+        for (peer_id, _) in idle.into_iter().take(removable) {
+            closing.push(*peer_id);
+        }
+        self.idle_closed += closing.len() as u64;
+        removable -= closing.len();
+
+        let excess = (self.peers.len() - closing.len()).saturating_sub(self.config.target_peers).min(removable);
+        for _ in 0..excess {
+            match self.eviction_candidate(scores, protected, &closing) {
+                Some(peer_id) => closing.push(peer_id),
+                None => break,
+            }
+            self.evicted += 1;
+        }
+
+        closing
+    }
+
+    // En kalabalık alt ağdaki en düşük puanlı düğüm, eşitlikte en yeni bağlantı
+    fn eviction_candidate(&self, scores: &HashMap<PeerId, f64>, protected: &HashSet<PeerId>, closing: &[PeerId]) -> Option<PeerId> {
+        let remaining: Vec<(&PeerId, &PeerConnection)> = self.peers.iter()
+            .filter(|(peer_id, _)| !closing.contains(peer_id))
+            .collect();
+
+        let mut subnet_sizes: HashMap<Option<Vec<u8>>, usize> = HashMap::new();
+        for (_, peer) in &remaining {
+            *subnet_sizes.entry(peer.ip.map(subnet)).or_insert(0) += 1;
+        }
+
+        remaining.into_iter()
+            .filter(|(peer_id, _)| !protected.contains(peer_id))
+            .max_by(|(a_id, a), (b_id, b)| {
+                let a_key = (subnet_sizes[&a.ip.map(subnet)], -scores.get(a_id).copied().unwrap_or(0.0), a.established_at);
+                let b_key = (subnet_sizes[&b.ip.map(subnet)], -scores.get(b_id).copied().unwrap_or(0.0), b.established_at);
+                a_key.partial_cmp(&b_key).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(peer_id, _)| *peer_id)
+    }
+
+    pub fn report(&self) -> ConnectionReport {
+        let subnets: HashSet<Option<Vec<u8>>> = self.peers.values().map(|peer| peer.ip.map(subnet)).collect();
+        let inbound = self.peers.values().filter(|peer| peer.inbound).count();
+
+        ConnectionReport {
+            connected_peers: self.peers.len(),
+            inbound,
+            outbound: self.peers.len() - inbound,
+            distinct_subnets: subnets.len(),
+            evicted: self.evicted,
+            idle_closed: self.idle_closed,
+            rejected_per_ip: self.rejected_per_ip,
+        }
+    }
+}
+
+fn ip_of(address: &Multiaddr) -> Option<IpAddr> {
+    address.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+// Çeşitlilik için gruplanan alt ağ: IPv4 için /24, IPv6 için /48
+fn subnet(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets()[..3].to_vec(),
+        IpAddr::V6(ip) => ip.octets()[..6].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ConnectionConfig {
+        ConnectionConfig {
+            max_inbound: 10,
+            max_outbound: 10,
+            max_pending_inbound: 5,
+            max_pending_outbound: 5,
+            max_per_peer: 1,
+            max_per_ip: 2,
+            target_peers: 3,
+            min_peers: 1,
+            idle_timeout: Duration::from_secs(3600),
+        }
+    }
+
+    fn dialer(address: &str) -> ConnectedPoint {
+        ConnectedPoint::Dialer { address: address.parse().unwrap() }
+    }
+
+    fn connect(manager: &mut ConnectionManager, address: &str) -> PeerId {
+        let peer_id = PeerId::random();
+        assert!(manager.on_established(peer_id, &dialer(address)));
+        peer_id
+    }
+
+    #[test]
+    fn limits_peers_per_ip_except_loopback() {
+        let mut manager = ConnectionManager::new(config());
+        connect(&mut manager, "/ip4/203.0.113.1/tcp/1");
+        connect(&mut manager, "/ip4/203.0.113.1/tcp/2");
+        assert!(!manager.on_established(PeerId::random(), &dialer("/ip4/203.0.113.1/tcp/3")));
+
+        for port in 0..3 {
+            connect(&mut manager, &format!("/ip4/127.0.0.1/tcp/{}", port));
+        }
+        assert_eq!(manager.report().rejected_per_ip, 1);
+    }
+
+    #[test]
+    fn evicts_low_scored_peers_from_crowded_subnet() {
+        let mut manager = ConnectionManager::new(ConnectionConfig { max_per_ip: 10, ..config() });
+        let trusted = connect(&mut manager, "/ip4/198.51.100.1/tcp/1");
+        let misbehaving = connect(&mut manager, "/ip4/198.51.100.2/tcp/1");
+        let guard = connect(&mut manager, "/ip4/198.51.100.3/tcp/1");
+        let lone = connect(&mut manager, "/ip4/203.0.113.1/tcp/1");
+        let other = connect(&mut manager, "/ip6/2001:db8::1/tcp/1");
+
+        let scores = HashMap::from([(trusted, 5.0), (misbehaving, -3.0), (lone, -10.0), (other, -10.0)]);
+        let protected = HashSet::from([guard]);
+
+        // Hedefin iki fazlası aynı /24 alt ağdan çıkarılır, korunan düğüme dokunulmaz
+        assert_eq!(manager.select_for_closing(&scores, &protected), vec![misbehaving, trusted]);
+        assert_eq!(manager.report().evicted, 2);
+    }
+
+    #[test]
+    fn idle_peers_are_closed_down_to_minimum() {
+        let mut manager = ConnectionManager::new(ConnectionConfig { idle_timeout: Duration::ZERO, min_peers: 2, ..config() });
+        let guard = connect(&mut manager, "/ip4/198.51.100.1/tcp/1");
+        connect(&mut manager, "/ip4/203.0.113.1/tcp/1");
+        connect(&mut manager, "/ip4/192.0.2.1/tcp/1");
+
+        let closing = manager.select_for_closing(&HashMap::new(), &HashSet::from([guard]));
+        assert_eq!(closing.len(), 1);
+        assert!(!closing.contains(&guard));
+        assert_eq!(manager.report().idle_closed, 1);
+    }
+
+    #[test]
+    fn last_closed_connection_forgets_peer() {
+        let mut manager = ConnectionManager::new(config());
+        let peer_id = connect(&mut manager, "/ip4/203.0.113.1/tcp/1");
+        assert!(manager.on_established(peer_id, &dialer("/ip4/203.0.113.1/tcp/2")));
+
+        manager.on_closed(&peer_id, 1);
+        assert_eq!(manager.report().connected_peers, 1);
+        manager.on_closed(&peer_id, 0);
+        assert_eq!(manager.report().connected_peers, 0);
+    }
+}
@@ -0,0 +1,99 @@
+use libp2p::PeerId;
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+
+use super::traffic_model::MAX_BODY_SIZE;
+
+// Örtü trafiği istatistikleri
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoverStats {
+    // Rotaya çıkarılan örtü paketleri
+    pub sent: u64,
+    // Rota kurulamadığı için gönderilemeyenler
+    pub failed: u64,
+    // Son düğüm olarak alınıp atılanlar
+    pub discarded: u64,
+    // Son düğüm olarak cevap bloğuyla sahte cevap gönderilenler
+    pub answered: u64,
+    // Gönderilen örtü yüklerinin dolgu öncesi toplam boyutu
+    pub bytes: u64,
+}
+
+impl CoverStats {
+    // Örtü paketinin gönderim sonucunu kaydet
+    pub fn record_sent(&mut self, size: usize, sent: bool) {
+        if sent {
+            self.sent += 1;
+            self.bytes += size as u64;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    // Son düğüm olarak alınan örtü paketini kaydet
+    pub fn record_received(&mut self) {
+        self.discarded += 1;
+    }
+
+    // Cevap bloğuyla gönderilen sahte cevabı kaydet
+    pub fn record_answered(&mut self) {
+        self.answered += 1;
+    }
+}
+
+// Örtü paketinin gönderileceği aracıyı seç, henüz aracı yoksa örtü trafiği de gönderilmez
+pub fn choose_destination<R: Rng + ?Sized>(relays: &[PeerId], local_peer_id: &PeerId, rng: &mut R) -> Option<PeerId> {
+    let destinations: Vec<PeerId> = relays.iter()
+        .filter(|peer_id| *peer_id != local_peer_id)
+        .cloned()
+        .collect();
+
+    destinations.choose(rng).cloned()
+}
+
+// Sahte cevabın gövde boyutu
+// Cevap bloğu tek paket taşır, istenen boyut gövde üst sınırına ve paketteki boşluğa göre kısaltılır
+pub fn response_body_size(requested: u32, room: usize) -> usize {
+    (requested as usize).min(MAX_BODY_SIZE).min(room)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::seeded_rng;
+
+    #[test]
+    fn stats_count_sent_failed_and_answered_packets() {
+        let mut stats = CoverStats::default();
+        stats.record_sent(40, true);
+        stats.record_sent(60, true);
+        stats.record_sent(50, false);
+        stats.record_received();
+        stats.record_received();
+        stats.record_answered();
+
+        assert_eq!((stats.sent, stats.failed, stats.bytes), (2, 1, 100));
+        assert_eq!((stats.discarded, stats.answered), (2, 1));
+    }
+
+    #[test]
+    fn destination_is_never_the_local_node() {
+        let local = PeerId::random();
+        let relay = PeerId::random();
+        let mut rng = seeded_rng(1);
+
+        assert_eq!(choose_destination(&[], &local, &mut *rng), None);
+        assert_eq!(choose_destination(&[local], &local, &mut *rng), None);
+        for _ in 0..20 {
+            assert_eq!(choose_destination(&[local, relay], &local, &mut *rng), Some(relay));
+        }
+    }
+
+    #[test]
+    fn response_fits_body_limit_and_packet_room() {
+        assert_eq!(response_body_size(500, 8000), 500);
+        assert_eq!(response_body_size(20_000, 8000), 8000);
+        assert_eq!(response_body_size(u32::MAX, usize::MAX), MAX_BODY_SIZE);
+        assert_eq!(response_body_size(0, 8000), 0);
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use futures::prelude::*;
+use libp2p::core::upgrade::{read_length_prefixed, write_length_prefixed, ProtocolName};
+use libp2p::request_response::{
+    ProtocolSupport, RequestId, RequestResponse, RequestResponseCodec, RequestResponseConfig,
+};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::iter;
+use std::time::{Duration, Instant};
+
+// Tek bir isteğin en fazla boyutu; yayın katmanının aksine parçalamaya gerek yoktur
+const MAX_REQUEST_SIZE: usize = 256 * 1024;
+// Onay ya da hata cevabının en fazla boyutu
+const MAX_RESPONSE_SIZE: usize = 1024;
+
+// Düğümden düğüme doğrudan paket teslim protokolü
+#[derive(Debug, Clone)]
+pub struct DirectProtocol;
+
+impl ProtocolName for DirectProtocol {
+    fn protocol_name(&self) -> &[u8] {
+        b"/kuantum/direct/1.0.0"
+    }
+}
+
+// Alıcının paketi işledikten sonra döndürdüğü cevap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DirectResponse {
+    // Paket alındı ve işlendi
+    Ack,
+    // Paket reddedildi, gerekçesiyle
+    Error(String),
+}
+
+// İstek gövdesi serileştirilmiş ağ çerçevesidir, cevaplar bincode ile kodlanır
+#[derive(Debug, Clone, Default)]
+pub struct DirectCodec;
+
+#[async_trait]
+impl RequestResponseCodec for DirectCodec {
+    type Protocol = DirectProtocol;
+    type Request = Vec<u8>;
+    type Response = DirectResponse;
+
+    async fn read_request<T>(&mut self, _: &DirectProtocol, io: &mut T) -> io::Result<Vec<u8>>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let data = read_length_prefixed(io, MAX_REQUEST_SIZE).await?;
+        if data.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(data)
+    }
+
+    async fn read_response<T>(&mut self, _: &DirectProtocol, io: &mut T) -> io::Result<DirectResponse>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let data = read_length_prefixed(io, MAX_RESPONSE_SIZE).await?;
+
+        bincode::deserialize(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    async fn write_request<T>(&mut self, _: &DirectProtocol, io: &mut T, request: Vec<u8>) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        write_length_prefixed(io, request).await?;
+        io.close().await
+    }
+
+    async fn write_response<T>(&mut self, _: &DirectProtocol, io: &mut T, response: DirectResponse) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        let data = bincode::serialize(&response).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write_length_prefixed(io, data).await?;
+        io.close().await
+    }
+}
+
+// Doğrudan teslim davranışını oluştur
+pub fn behaviour(request_timeout: Duration) -> RequestResponse<DirectCodec> {
+    let mut config = RequestResponseConfig::default();
+    config.set_request_timeout(request_timeout);
+
+    RequestResponse::new(DirectCodec, iter::once((DirectProtocol, ProtocolSupport::Full)), config)
+}
+
+// Onayı beklenen teslim
+pub struct PendingDelivery {
+    pub peer: PeerId,
+    // Yayın katmanına geri düşmek için saklanan çerçeve
+    pub data: Vec<u8>,
+    sent_at: Instant,
+}
+
+// Doğrudan teslim istatistikleri
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeliveryStats {
+    pub pending: usize,
+    pub acknowledged: u64,
+    pub rejected: u64,
+    pub failed: u64,
+    // Son onayların üstel ortalamalı gidiş-dönüş süresi (ms)
+    pub average_rtt_ms: Option<f64>,
+}
+
+// Gönderilen isteklerin onay, ret ve zaman aşımlarını izleyen kayıt
+#[derive(Default)]
+pub struct DeliveryTracker {
+    pending: HashMap<RequestId, PendingDelivery>,
+    stats: DeliveryStats,
+}
+
+impl DeliveryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, request_id: RequestId, peer: PeerId, data: Vec<u8>) {
+        self.pending.insert(request_id, PendingDelivery { peer, data, sent_at: Instant::now() });
+    }
+
+    // Cevap geldi, teslim kaydı ve gidiş-dönüş süresi döner
+    pub fn on_response(&mut self, request_id: &RequestId, response: &DirectResponse) -> Option<(PendingDelivery, Duration)> {
+        let delivery = self.pending.remove(request_id)?;
+        let rtt = delivery.sent_at.elapsed();
+
+        match response {
+            DirectResponse::Ack => {
+                self.stats.acknowledged += 1;
+                let sample = rtt.as_secs_f64() * 1000.0;
+                self.stats.average_rtt_ms = Some(match self.stats.average_rtt_ms {
+                    Some(average) => average * 0.875 + sample * 0.125,
+                    None => sample,
+                });
+            }
+            DirectResponse::Error(_) => self.stats.rejected += 1,
+        }
+
+        Some((delivery, rtt))
+    }
+
+    // İstek bağlantı hatası ya da zaman aşımıyla sonuçlandı
+    pub fn on_failure(&mut self, request_id: &RequestId) -> Option<PendingDelivery> {
+        let delivery = self.pending.remove(request_id)?;
+        self.stats.failed += 1;
+
+        Some(delivery)
+    }
+
+    pub fn stats(&self) -> DeliveryStats {
+        DeliveryStats { pending: self.pending.len(), ..self.stats.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::Cursor;
+
+    // Gerçek istek kimlikleri için bağlantı kurmadan istek kuyruğa alınır
+    fn request_ids(count: usize) -> Vec<RequestId> {
+        let mut direct = behaviour(Duration::from_secs(10));
+        let peer = PeerId::random();
+        (0..count).map(|_| direct.send_request(&peer, vec![1])).collect()
+    }
+
+    #[tokio::test]
+    async fn codec_round_trips_requests_and_responses() {
+        let mut codec = DirectCodec;
+
+        let mut buffer = Cursor::new(Vec::new());
+        codec.write_request(&DirectProtocol, &mut buffer, b"paket".to_vec()).await.unwrap();
+        buffer.set_position(0);
+        assert_eq!(codec.read_request(&DirectProtocol, &mut buffer).await.unwrap(), b"paket");
+
+        let mut buffer = Cursor::new(Vec::new());
+        codec.write_response(&DirectProtocol, &mut buffer, DirectResponse::Error("red".to_string())).await.unwrap();
+        buffer.set_position(0);
+        match codec.read_response(&DirectProtocol, &mut buffer).await.unwrap() {
+            DirectResponse::Error(reason) => assert_eq!(reason, "red"),
+            DirectResponse::Ack => panic!("hata cevabı bekleniyordu"),
+        }
+    }
+
+    #[tokio::test]
+    async fn codec_rejects_empty_and_oversized_requests() {
+        let mut codec = DirectCodec;
+
+        let mut buffer = Cursor::new(Vec::new());
+        codec.write_request(&DirectProtocol, &mut buffer, Vec::new()).await.unwrap();
+        buffer.set_position(0);
+        assert!(codec.read_request(&DirectProtocol, &mut buffer).await.is_err());
+
+        let mut buffer = Cursor::new(Vec::new());
+        codec.write_request(&DirectProtocol, &mut buffer, vec![0; MAX_REQUEST_SIZE + 1]).await.unwrap();
+        buffer.set_position(0);
+        assert!(codec.read_request(&DirectProtocol, &mut buffer).await.is_err());
+
+        let mut buffer = Cursor::new(Vec::new());
+        codec.write_response(&DirectProtocol, &mut buffer, DirectResponse::Error("x".repeat(MAX_RESPONSE_SIZE))).await.unwrap();
+        buffer.set_position(0);
+        assert!(codec.read_response(&DirectProtocol, &mut buffer).await.is_err());
+    }
+
+    #[test]
+    fn tracker_counts_acks_rejections_and_failures() {
+        let ids = request_ids(3);
+        let peer = PeerId::random();
+        let mut tracker = DeliveryTracker::new();
+        for id in &ids {
+            tracker.insert(*id, peer, b"cerceve".to_vec());
+        }
+        assert_eq!(tracker.stats().pending, 3);
+
+        let (delivery, _) = tracker.on_response(&ids[0], &DirectResponse::Ack).unwrap();
+        assert_eq!(delivery.peer, peer);
+        tracker.on_response(&ids[1], &DirectResponse::Error("red".to_string())).unwrap();
+
+        // Yayın katmanına geri düşmek için çerçeve geri verilir
+        let failed = tracker.on_failure(&ids[2]).unwrap();
+        assert_eq!(failed.data, b"cerceve");
+
+        // Aynı isteğin ikinci sonucu sayılmaz
+        assert!(tracker.on_response(&ids[0], &DirectResponse::Ack).is_none());
+        assert!(tracker.on_failure(&ids[1]).is_none());
+
+        let stats = tracker.stats();
+        assert_eq!((stats.pending, stats.acknowledged, stats.rejected, stats.failed), (0, 1, 1, 1));
+        assert!(stats.average_rtt_ms.is_some());
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::Result;
-use rand::{Rng, thread_rng};
-use std::time::Duration;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use uuid::Uuid;
 
+use super::traffic_model::{TrafficModel, TrafficModelSummary};
+use super::traffic_schedule::{Poisson, TrafficScheduler};
+use super::{os_rng, BoxedRng};
+
 // Sahte HTTP yöntemleri
 pub enum HttpMethod {
     GET,
@@ -23,8 +30,7 @@ impl HttpMethod {
     }
     
     // Rastgele bir HTTP yöntemi döndür
-    pub fn random() -> Self {
-        let mut rng = thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
         match rng.gen_range(0..4) {
             0 => HttpMethod::GET,
             1 => HttpMethod::POST,
@@ -41,13 +47,14 @@ pub struct FakeHttpRequest {
     pub method: String,
     pub url: String,
     pub data: Vec<u8>,
+    // Karşılığında beklenen cevabın boyutu, 0 ise cevap beklenmez
+    pub response_size: usize,
 }
 
 impl FakeHttpRequest {
     // Rastgele bir HTTP isteği oluştur
-    pub fn random() -> Self {
-        let mut rng = thread_rng();
-        
+    // Kimlik dahil tüm alanlar verilen RNG'den türetilir
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
         // Rastgele URL'ler
         let urls = [
             "https://example.com",
@@ -65,70 +72,345 @@ impl FakeHttpRequest {
         }
         
         Self {
-            id: Uuid::new_v4().to_string(),
-            method: HttpMethod::random().as_str().to_string(),
+            id: Uuid::from_bytes(rng.gen()).to_string(),
+            method: HttpMethod::random(rng).as_str().to_string(),
             url: urls[rng.gen_range(0..urls.len())].to_string(),
             data,
+            response_size: 0,
         }
     }
 }
 
+// Üreteç görevine iletilen kontrol durumu
+#[derive(Debug, Clone, Copy)]
+struct Control {
+    rate_per_second: f64,
+    paused: bool,
+    stopped: bool,
+}
+
+// Üretilen sahte trafik istatistikleri
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FakeTrafficStats {
+    pub rate_per_second: f64,
+    pub scheduler: String,
+    // İstekler kayıtlı trafikten örnekleniyorsa modelin özeti
+    pub model: Option<TrafficModelSummary>,
+    pub paused: bool,
+    pub running: bool,
+    pub generated: u64,
+    // Üretilen isteklerin gövde boyutlarının toplamı
+    pub bytes: u64,
+}
+
 // Sahte trafik üreteci
 pub struct FakeTrafficGenerator {
     // Saniyede ortalama oluşturulacak sahte istek sayısı
     pub rate_per_second: f64,
-    // Çalışıp çalışmadığı
-    pub active: bool,
+    // Bekleme sürelerini belirleyen zamanlayıcı, çalışan görevle paylaşılır
+    scheduler: Arc<Mutex<Box<dyn TrafficScheduler>>>,
+    // İsteklerin örneklendiği trafik modeli, yoksa rastgele istekler üretilir
+    model: Arc<Mutex<Option<TrafficModel>>>,
+    // Bekleme süreleri ve istek içerikleri için RNG
+    rng: BoxedRng,
 }
 
 impl FakeTrafficGenerator {
     pub fn new(rate_per_second: f64) -> Self {
+        Self::with_rng(rate_per_second, os_rng())
+    }
+    
+    // Verilen RNG ile üreteç oluştur, sabit tohum aynı trafik dizisini üretir
+    pub fn with_rng(rate_per_second: f64, rng: BoxedRng) -> Self {
         Self {
             rate_per_second,
-            active: false,
+            scheduler: Arc::new(Mutex::new(Box::new(Poisson))),
+            model: Arc::new(Mutex::new(None)),
+            rng,
         }
     }
     
-    // Sahte trafik üretmeye başla
-    pub async fn start<F>(&mut self, mut callback: F) -> Result<()>
+    // Zamanlayıcıyı değiştir, üreteç çalışıyorsa bir sonraki bekleme süresinden itibaren geçerli olur
+    pub fn set_scheduler(&mut self, scheduler: Box<dyn TrafficScheduler>) {
+        *self.scheduler.lock().unwrap() = scheduler;
+    }
+    
+    // İstekleri kayıtlı trafik modelinden örnekle, zamanlama ve hız da kayda göre ayarlanır
+    pub fn set_model(&mut self, model: TrafficModel) {
+        self.rate_per_second = model.mean_rate();
+        self.set_scheduler(model.scheduler());
+        *self.model.lock().unwrap() = Some(model);
+    }
+    
+    // Sahte trafik üretmeye başla, görev dönen tutamaçla denetlenir
+    // Tutamaç düşürülürse görev de sonlanır
+    pub fn start<F>(&mut self, mut callback: F) -> Result<FakeTrafficHandle>
     where
         F: FnMut(FakeHttpRequest) + Send + 'static
     {
-        self.active = true;
+        let (control, mut updates) = watch::channel(Control {
+            rate_per_second: self.rate_per_second,
+            paused: false,
+            stopped: false,
+        });
+        let stats = Arc::new(Mutex::new(FakeTrafficStats {
+            rate_per_second: self.rate_per_second,
+            running: true,
+            ..FakeTrafficStats::default()
+        }));
         
-        let rate = self.rate_per_second;
+        // Görev kendi RNG'sini üretecin RNG'sinden türetir
+        let mut rng = StdRng::from_rng(&mut self.rng)?;
+        let task_stats = stats.clone();
+        let scheduler = self.scheduler.clone();
+        let model = self.model.clone();
         
         // Ayrı bir tokio görevinde sahte istekleri oluştur
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             loop {
-                // Rastgele bir bekleme süresi (ortalama hıza göre)
-                let wait_time = if rate > 0.0 {
-                    // Üretilme hızı saniyede kaç istek
-                    let mean_delay_secs = 1.0 / rate;
-                    // Rastgele bir bekleme süresi hesapla
-                    let delay_secs = {
-                        let mut rng = thread_rng();
-                        rng.gen_range(0.0..(mean_delay_secs * 2.0))
-                    };
-                    Duration::from_secs_f64(delay_secs)
-                } else {
-                    Duration::from_secs(10) // Eğer hız 0 ise, 10 saniye bekle
-                };
+                let current = *updates.borrow();
+                if current.stopped {
+                    break;
+                }
                 
-                // Bekleme süresini uygula
-                sleep(wait_time).await;
+                // Duraklatıldıysa ya da hız 0 ise yeni bir ayar gelene kadar bekle
+                if current.paused || current.rate_per_second <= 0.0 {
+                    if updates.changed().await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                
+                // Zamanlayıcının ortalama hıza göre seçtiği bekleme süresi
+                let wait_time = scheduler.lock().unwrap().next_delay(&mut rng, current.rate_per_second);
+                
+                // Beklerken ayar değişirse yeni ayarla baştan başla
+                tokio::select! {
+                    _ = sleep(wait_time) => {}
+                    changed = updates.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                }
                 
                 // Sahte istek oluştur ve callback ile gönder
-                let request = FakeHttpRequest::random();
+                let request = match &*model.lock().unwrap() {
+                    Some(model) => model.sample_request(&mut rng),
+                    None => FakeHttpRequest::random(&mut rng),
+                };
+                {
+                    let mut stats = task_stats.lock().unwrap();
+                    stats.generated += 1;
+                    stats.bytes += request.data.len() as u64;
+                }
                 callback(request);
             }
+            
+            task_stats.lock().unwrap().running = false;
         });
         
+        Ok(FakeTrafficHandle {
+            control,
+            scheduler: self.scheduler.clone(),
+            model: self.model.clone(),
+            stats,
+            task,
+        })
+    }
+}
+
+// Çalışan sahte trafik görevinin tutamacı
+pub struct FakeTrafficHandle {
+    control: watch::Sender<Control>,
+    scheduler: Arc<Mutex<Box<dyn TrafficScheduler>>>,
+    model: Arc<Mutex<Option<TrafficModel>>>,
+    stats: Arc<Mutex<FakeTrafficStats>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeTrafficHandle {
+    // Üretimi duraklat, görev çalışmaya devam eder
+    pub fn pause(&self) {
+        self.update(|control| control.paused = true);
+        self.stats.lock().unwrap().paused = true;
+    }
+    
+    pub fn resume(&self) {
+        self.update(|control| control.paused = false);
+        self.stats.lock().unwrap().paused = false;
+    }
+    
+    // Ortalama hızı değiştir, bekleyen istek yeni hıza göre yeniden zamanlanır
+    pub fn set_rate(&self, rate_per_second: f64) {
+        self.update(|control| control.rate_per_second = rate_per_second);
+        self.stats.lock().unwrap().rate_per_second = rate_per_second;
+    }
+    
+    // Zamanlayıcıyı değiştir, bekleyen istek yeni zamanlayıcıyla yeniden zamanlanır
+    pub fn set_scheduler(&self, scheduler: Box<dyn TrafficScheduler>) {
+        *self.scheduler.lock().unwrap() = scheduler;
+        self.update(|_| {});
+    }
+    
+    // Çalışan üreteci kayıtlı trafik modeline geçir
+    pub fn set_model(&self, model: TrafficModel) {
+        let rate = model.mean_rate();
+        *self.scheduler.lock().unwrap() = model.scheduler();
+        *self.model.lock().unwrap() = Some(model);
+        self.set_rate(rate);
+    }
+    
+    pub fn stats(&self) -> FakeTrafficStats {
+        FakeTrafficStats {
+            scheduler: self.scheduler.lock().unwrap().name().to_string(),
+            model: self.model.lock().unwrap().as_ref().map(TrafficModel::summary),
+            ..self.stats.lock().unwrap().clone()
+        }
+    }
+    
+    // Görevi durdur ve bitmesini bekle
+    pub async fn stop(self) -> Result<()> {
+        self.update(|control| control.stopped = true);
+        self.task.await?;
+        
         Ok(())
     }
     
-    // Sahte trafik üretmeyi durdur
-    pub fn stop(&mut self) {
-        self.active = false;
+    fn update(&self, change: impl FnOnce(&mut Control)) {
+        let mut control = *self.control.borrow();
+        change(&mut control);
+        // Görev sonlandıysa alıcı yoktur, gönderim hatası yok sayılır
+        let _ = self.control.send(control);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::seeded_rng;
+    use crate::crypto::traffic_schedule::ConstantRate;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
+
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+    // Bekleyen son isteğin teslim edilmesi için tanınan süre
+    const SETTLE: Duration = Duration::from_millis(50);
+
+    fn start(rate_per_second: f64, seed: u64) -> (FakeTrafficHandle, mpsc::UnboundedReceiver<FakeHttpRequest>) {
+        let mut generator = FakeTrafficGenerator::with_rng(rate_per_second, seeded_rng(seed));
+        generator.set_scheduler(Box::new(ConstantRate));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let handle = generator.start(move |request| {
+            let _ = sender.send(request);
+        }).unwrap();
+
+        (handle, receiver)
+    }
+
+    async fn receive(receiver: &mut mpsc::UnboundedReceiver<FakeHttpRequest>, count: usize) -> Vec<FakeHttpRequest> {
+        let mut requests = Vec::new();
+        while requests.len() < count {
+            requests.push(timeout(TEST_TIMEOUT, receiver.recv()).await.unwrap().unwrap());
+        }
+        requests
+    }
+
+    // Duraklatılmış üreteç yeni istek üretmemeli
+    async fn assert_idle(handle: &FakeTrafficHandle, receiver: &mut mpsc::UnboundedReceiver<FakeHttpRequest>) {
+        tokio::time::sleep(SETTLE).await;
+        while receiver.try_recv().is_ok() {}
+        let generated = handle.stats().generated;
+
+        tokio::time::sleep(SETTLE).await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(handle.stats().generated, generated);
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_control_generation() {
+        let (handle, mut receiver) = start(500.0, 1);
+        receive(&mut receiver, 3).await;
+
+        handle.pause();
+        assert!(handle.stats().paused);
+        assert_idle(&handle, &mut receiver).await;
+
+        handle.resume();
+        assert!(!handle.stats().paused);
+        receive(&mut receiver, 3).await;
+
+        handle.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rate_changes_apply_to_running_generator() {
+        let (handle, mut receiver) = start(500.0, 2);
+        receive(&mut receiver, 2).await;
+
+        handle.set_rate(0.0);
+        assert_eq!(handle.stats().rate_per_second, 0.0);
+        assert_idle(&handle, &mut receiver).await;
+
+        // Çok düşük hızda bekleyen istek, hız artınca yeniden zamanlanır
+        handle.set_rate(0.001);
+        tokio::time::sleep(SETTLE).await;
+        handle.set_rate(500.0);
+        receive(&mut receiver, 2).await;
+
+        handle.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stats_match_delivered_requests() {
+        let (handle, mut receiver) = start(500.0, 3);
+        let mut requests = receive(&mut receiver, 5).await;
+        handle.pause();
+        tokio::time::sleep(SETTLE).await;
+
+        while let Ok(request) = receiver.try_recv() {
+            requests.push(request);
+        }
+        let stats = handle.stats();
+        assert!(stats.running);
+        assert_eq!(stats.scheduler, "constant");
+        assert_eq!(stats.generated, requests.len() as u64);
+        assert_eq!(stats.bytes, requests.iter().map(|request| request.data.len() as u64).sum::<u64>());
+
+        handle.stop().await.unwrap();
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn stop_ends_task_and_releases_callback() {
+        let (handle, mut receiver) = start(500.0, 4);
+        receive(&mut receiver, 1).await;
+
+        timeout(TEST_TIMEOUT, handle.stop()).await.unwrap().unwrap();
+
+        // Görev bitince callback ve içindeki gönderen bırakılır
+        while timeout(TEST_TIMEOUT, receiver.recv()).await.unwrap().is_some() {}
+    }
+
+    #[tokio::test]
+    async fn dropping_handle_stops_generator() {
+        let (handle, mut receiver) = start(1.0, 5);
+        drop(handle);
+
+        assert!(timeout(TEST_TIMEOUT, receiver.recv()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn same_seed_generates_same_requests() {
+        let (first, mut first_requests) = start(500.0, 6);
+        let (second, mut second_requests) = start(500.0, 6);
+
+        let first_ids: Vec<String> = receive(&mut first_requests, 3).await.into_iter().map(|request| request.id).collect();
+        let second_ids: Vec<String> = receive(&mut second_requests, 3).await.into_iter().map(|request| request.id).collect();
+        assert_eq!(first_ids, second_ids);
+
+        first.stop().await.unwrap();
+        second.stop().await.unwrap();
+    }
+}
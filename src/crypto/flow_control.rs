@@ -0,0 +1,306 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::anon_protocol::MessageType;
+
+// Gönderim önceliği
+// Etkileşimli mesajlar her zaman toplu aktarımlardan önce gönderilir
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Priority {
+    Interactive,
+    Bulk,
+}
+
+impl From<MessageType> for Priority {
+    fn from(msg_type: MessageType) -> Self {
+        match msg_type {
+            MessageType::Binary => Priority::Bulk,
+            MessageType::Text | MessageType::Command | MessageType::Handshake => Priority::Interactive,
+        }
+    }
+}
+
+// Gönderen tarafın kredi penceresi
+// Her hücre bir kredi harcar, her SENDME `increment` kredi geri verir
+#[derive(Debug, Clone)]
+pub struct SendWindow {
+    available: u32,
+    increment: u32,
+}
+
+impl SendWindow {
+    pub fn new(size: u32, increment: u32) -> Self {
+        Self {
+            available: size,
+            increment,
+        }
+    }
+
+    pub fn available(&self) -> u32 {
+        self.available
+    }
+
+    pub fn can_send(&self) -> bool {
+        self.available > 0
+    }
+
+    pub fn on_send(&mut self) {
+        self.available = self.available.saturating_sub(1);
+    }
+
+    pub fn on_sendme(&mut self) {
+        self.available += self.increment;
+    }
+}
+
+// Alıcı tarafın penceresi
+// Son SENDME'den sonra pencereden fazla hücre gönderen devrenin hücreleri atılır
+#[derive(Debug, Clone)]
+pub struct ReceiveWindow {
+    size: u32,
+    // SENDME gönderilen son hücrenin sıra numarası
+    last_sendme: Option<u64>,
+}
+
+impl ReceiveWindow {
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            last_sendme: None,
+        }
+    }
+
+    // Gelen hücrenin pencere içinde olduğunu doğrula
+    pub fn accept(&self, seq: u64) -> Result<()> {
+        let limit = self.last_sendme.map_or(0, |last| last + 1) + self.size as u64;
+        if seq >= limit {
+            return Err(anyhow!("Gönderen akış penceresini aştı: {} >= {}", seq, limit));
+        }
+
+        Ok(())
+    }
+
+    // Hücre için SENDME gönderildi, pencere ilerler
+    pub fn on_sendme(&mut self, seq: u64) {
+        self.last_sendme = Some(self.last_sendme.map_or(seq, |last| last.max(seq)));
+    }
+}
+
+// RTT'ye göre pencere boyutunu ayarlayan tıkanıklık denetleyicisi
+// Gecikme en düşük RTT'ye yakınken pencere büyür, kuyruklar dolup RTT arttıkça küçülür,
+// kayıpta yarıya iner
+#[derive(Debug, Clone)]
+pub struct CongestionController {
+    cwnd: u32,
+    min_cwnd: u32,
+    max_cwnd: u32,
+    increment: u32,
+    in_flight: u32,
+    min_rtt: Option<Duration>,
+    smoothed_rtt: Option<Duration>,
+}
+
+impl CongestionController {
+    pub fn new(initial_cwnd: u32, min_cwnd: u32, max_cwnd: u32, increment: u32) -> Self {
+        Self {
+            cwnd: initial_cwnd,
+            min_cwnd,
+            max_cwnd,
+            increment,
+            in_flight: 0,
+            min_rtt: None,
+            smoothed_rtt: None,
+        }
+    }
+
+    pub fn cwnd(&self) -> u32 {
+        self.cwnd
+    }
+
+    pub fn in_flight(&self) -> u32 {
+        self.in_flight
+    }
+
+    pub fn smoothed_rtt(&self) -> Option<Duration> {
+        self.smoothed_rtt
+    }
+
+    pub fn can_send(&self) -> bool {
+        self.in_flight < self.cwnd
+    }
+
+    pub fn on_send(&mut self) {
+        self.in_flight += 1;
+    }
+
+    // `acked` hücrenin ulaştığı SENDME ile bildirildi
+    pub fn on_ack(&mut self, acked: u32, rtt: Duration) {
+        self.in_flight = self.in_flight.saturating_sub(acked);
+
+        let min_rtt = self.min_rtt.map_or(rtt, |min_rtt| min_rtt.min(rtt));
+        self.min_rtt = Some(min_rtt);
+        self.smoothed_rtt = Some(match self.smoothed_rtt {
+            Some(smoothed) => smoothed.mul_f64(0.875) + rtt.mul_f64(0.125),
+            None => rtt,
+        });
+
+        // Kuyruk gecikmesi düşükse büyü, belirgin şekilde arttıysa küçül
+        if rtt <= min_rtt.mul_f64(1.25) {
+            self.cwnd = (self.cwnd + self.increment).min(self.max_cwnd);
+        } else if rtt >= min_rtt.mul_f64(2.0) {
+            self.cwnd = (self.cwnd - self.cwnd / 4).max(self.min_cwnd);
+        }
+    }
+
+    // SENDME zamanında gelmedi, hücreler kayıp sayılır
+    pub fn on_loss(&mut self, lost: u32) {
+        self.in_flight = self.in_flight.saturating_sub(lost);
+        self.cwnd = (self.cwnd / 2).max(self.min_cwnd);
+    }
+}
+
+// Gönderilmeye hazır hücre
+pub struct ReadyCell<T> {
+    pub seq: u64,
+    pub priority: Priority,
+    pub item: T,
+    // Alıcının bu hücreden sonra SENDME göndermesi gerekiyor mu
+    pub wants_sendme: bool,
+}
+
+// Bir devrenin gönderim tarafı: öncelik kuyrukları, kredi penceresi ve tıkanıklık denetimi
+pub struct CircuitFlow<T> {
+    window: SendWindow,
+    congestion: CongestionController,
+    interactive: VecDeque<T>,
+    bulk: VecDeque<T>,
+    // SENDME beklenen hücrelerin sıra numarası ve gönderim zamanı
+    awaiting_sendme: VecDeque<(u64, Instant)>,
+    next_seq: u64,
+    increment: u32,
+}
+
+impl<T> CircuitFlow<T> {
+    pub fn new(window_size: u32, increment: u32, congestion: CongestionController) -> Self {
+        Self {
+            window: SendWindow::new(window_size, increment),
+            congestion,
+            interactive: VecDeque::new(),
+            bulk: VecDeque::new(),
+            awaiting_sendme: VecDeque::new(),
+            next_seq: 0,
+            increment,
+        }
+    }
+
+    pub fn window(&self) -> &SendWindow {
+        &self.window
+    }
+
+    pub fn congestion(&self) -> &CongestionController {
+        &self.congestion
+    }
+
+    pub fn queued(&self) -> usize {
+        self.interactive.len() + self.bulk.len()
+    }
+
+    pub fn push(&mut self, priority: Priority, item: T) {
+        match priority {
+            Priority::Interactive => self.interactive.push_back(item),
+            Priority::Bulk => self.bulk.push_back(item),
+        }
+    }
+
+    // Pencere izin veriyorsa gönderilecek sıradaki hücreyi döndür
+    pub fn next_ready(&mut self) -> Option<ReadyCell<T>> {
+        if !self.window.can_send() || !self.congestion.can_send() {
+            return None;
+        }
+
+        let (priority, item) = match self.interactive.pop_front() {
+            Some(item) => (Priority::Interactive, item),
+            None => (Priority::Bulk, self.bulk.pop_front()?),
+        };
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        self.window.on_send();
+        self.congestion.on_send();
+
+        let wants_sendme = (seq + 1).is_multiple_of(self.increment as u64);
+        if wants_sendme {
+            self.awaiting_sendme.push_back((seq, Instant::now()));
+        }
+
+        Some(ReadyCell { seq, priority, item, wants_sendme })
+    }
+
+    // Alıcıdan gelen SENDME'yi işle
+    pub fn on_sendme(&mut self, acked_seq: u64) -> Result<()> {
+        let position = self.awaiting_sendme.iter()
+            .position(|(seq, _)| *seq == acked_seq)
+            .ok_or_else(|| anyhow!("Beklenmeyen SENDME: {}", acked_seq))?;
+        let (_, sent_at) = self.awaiting_sendme.remove(position)
+            .ok_or_else(|| anyhow!("Beklenmeyen SENDME: {}", acked_seq))?;
+
+        self.window.on_sendme();
+        self.congestion.on_ack(self.increment, sent_at.elapsed());
+
+        Ok(())
+    }
+
+    // Zaman aşımına uğrayan SENDME'leri kayıp say ve krediyi geri ver
+    // Kayıp sayısını döndürür
+    pub fn expire_sendmes(&mut self, timeout: Duration) -> usize {
+        let mut lost = 0;
+
+        while let Some((_, sent_at)) = self.awaiting_sendme.front() {
+            if sent_at.elapsed() <= timeout {
+                break;
+            }
+
+            self.awaiting_sendme.pop_front();
+            self.window.on_sendme();
+            self.congestion.on_loss(self.increment);
+            lost += 1;
+        }
+
+        lost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_window_runs_out_and_refills_on_sendme() {
+        let mut window = SendWindow::new(3, 2);
+        for _ in 0..3 {
+            assert!(window.can_send());
+            window.on_send();
+        }
+        assert!(!window.can_send());
+
+        window.on_sendme();
+        assert_eq!(window.available(), 2);
+    }
+
+    #[test]
+    fn receive_window_slides_with_sendme() {
+        let mut window = ReceiveWindow::new(4);
+        assert!(window.accept(3).is_ok());
+        assert!(window.accept(4).is_err());
+
+        window.on_sendme(1);
+        assert!(window.accept(5).is_ok());
+        assert!(window.accept(6).is_err());
+
+        // Eski SENDME pencereyi geri çekmez
+        window.on_sendme(0);
+        assert!(window.accept(5).is_ok());
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Floodsub 2048 byte'tan büyük mesajları sessizce atar. Cevap bloğu taşıyan devre hücreleri
+// bu sınırı aşabildiğinden büyük paketler parçalanarak gönderilir.
+pub const FRAGMENT_SIZE: usize = 1536;
+// Tek bir paketin bölünebileceği en fazla parça
+pub const MAX_FRAGMENTS: u16 = 64;
+// Aynı anda birleştirilmeyi bekleyen en fazla paket
+const MAX_PENDING_PACKETS: usize = 256;
+
+// Büyük bir paketin parçası
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fragment {
+    pub packet_id: [u8; 16],
+    pub index: u16,
+    pub total: u16,
+    pub data: Vec<u8>,
+}
+
+// Veriyi rastgele bir paket kimliğiyle parçalara böl
+pub fn split<R: Rng + ?Sized>(data: &[u8], rng: &mut R) -> Result<Vec<Fragment>> {
+    split_sized(data, FRAGMENT_SIZE, rng)
+}
+
+// Veriyi en fazla `fragment_size` byte'lık parçalara böl
+pub fn split_sized<R: Rng + ?Sized>(data: &[u8], fragment_size: usize, rng: &mut R) -> Result<Vec<Fragment>> {
+    let total = data.len().div_ceil(fragment_size).max(1);
+    if total > MAX_FRAGMENTS as usize {
+        return Err(anyhow!("Paket çok büyük: {} byte", data.len()));
+    }
+
+    let packet_id: [u8; 16] = rng.gen();
+    let fragments = data.chunks(fragment_size)
+        .enumerate()
+        .map(|(index, chunk)| Fragment {
+            packet_id,
+            index: index as u16,
+            total: total as u16,
+            data: chunk.to_vec(),
+        })
+        .collect();
+
+    Ok(fragments)
+}
+
+// Birleştirilmeyi bekleyen paket
+struct PendingPacket {
+    parts: Vec<Option<Vec<u8>>>,
+    received: usize,
+    first_seen: Instant,
+}
+
+// Gelen parçaları toplayıp paketi yeniden oluşturan tampon
+// Tamamlanmayan paketler süre dolunca atılır
+pub struct FragmentBuffer {
+    pending: HashMap<[u8; 16], PendingPacket>,
+    lifetime: Duration,
+    // Bekleyen parçaların toplam boyutu
+    buffered_bytes: usize,
+}
+
+impl FragmentBuffer {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            lifetime,
+            buffered_bytes: 0,
+        }
+    }
+
+    // Parçayı ekle, paket tamamlandıysa birleştirilmiş veriyi döndür
+    pub fn insert(&mut self, fragment: Fragment) -> Result<Option<Vec<u8>>> {
+        if fragment.total == 0 || fragment.total > MAX_FRAGMENTS || fragment.index >= fragment.total {
+            return Err(anyhow!("Geçersiz parça: {}/{}", fragment.index, fragment.total));
+        }
+
+        if !self.pending.contains_key(&fragment.packet_id) && self.pending.len() >= MAX_PENDING_PACKETS {
+            self.prune_expired();
+            if self.pending.len() >= MAX_PENDING_PACKETS {
+                return Err(anyhow!("Birleştirilmeyi bekleyen paket sayısı sınıra ulaştı"));
+            }
+        }
+
+        let packet = self.pending.entry(fragment.packet_id).or_insert_with(|| PendingPacket {
+            parts: vec![None; fragment.total as usize],
+            received: 0,
+            first_seen: Instant::now(),
+        });
+        if packet.parts.len() != fragment.total as usize {
+            return Err(anyhow!("Parça sayısı tutarsız"));
+        }
+
+        let slot = &mut packet.parts[fragment.index as usize];
+        if slot.is_none() {
+            self.buffered_bytes += fragment.data.len();
+            *slot = Some(fragment.data);
+            packet.received += 1;
+        }
+
+        if packet.received < packet.parts.len() {
+            return Ok(None);
+        }
+
+        let packet = self.pending.remove(&fragment.packet_id)
+            .ok_or_else(|| anyhow!("Paket bulunamadı"))?;
+        let data: Vec<u8> = packet.parts.into_iter().flatten().flatten().collect();
+        self.buffered_bytes -= data.len();
+
+        Ok(Some(data))
+    }
+
+    // Süresi dolan eksik paketleri at
+    pub fn prune_expired(&mut self) {
+        let lifetime = self.lifetime;
+        let mut released = 0;
+        self.pending.retain(|_, packet| {
+            let keep = packet.first_seen.elapsed() <= lifetime;
+            if !keep {
+                released += packet.parts.iter().flatten().map(Vec::len).sum::<usize>();
+            }
+            keep
+        });
+        self.buffered_bytes -= released;
+    }
+
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn fragments_reassemble_in_any_order() {
+        let original = data(FRAGMENT_SIZE * 3 + 100);
+        let mut fragments = split(&original, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(fragments.len(), 4);
+        fragments.reverse();
+
+        let mut buffer = FragmentBuffer::new(Duration::from_secs(60));
+        let last = fragments.pop().unwrap();
+        for fragment in fragments {
+            // Aynı parçanın tekrarı sayılmaz
+            assert!(buffer.insert(fragment.clone()).unwrap().is_none());
+            assert!(buffer.insert(fragment).unwrap().is_none());
+        }
+        assert!(buffer.buffered_bytes() > 0);
+
+        assert_eq!(buffer.insert(last).unwrap(), Some(original));
+        assert_eq!(buffer.buffered_bytes(), 0);
+    }
+
+    #[test]
+    fn split_sized_respects_fragment_size() {
+        let original = data(1000);
+        let fragments = split_sized(&original, 300, &mut StdRng::seed_from_u64(2)).unwrap();
+
+        assert_eq!(fragments.len(), 4);
+        assert!(fragments.iter().all(|fragment| fragment.data.len() <= 300 && fragment.total == 4));
+        assert!(split_sized(&original, 10, &mut StdRng::seed_from_u64(2)).is_err());
+    }
+
+    #[test]
+    fn invalid_fragments_are_rejected() {
+        let mut buffer = FragmentBuffer::new(Duration::from_secs(60));
+        let fragment = |index, total| Fragment { packet_id: [1; 16], index, total, data: vec![0; 10] };
+
+        assert!(buffer.insert(fragment(2, 2)).is_err());
+        assert!(buffer.insert(fragment(0, 0)).is_err());
+        assert!(buffer.insert(fragment(0, MAX_FRAGMENTS + 1)).is_err());
+
+        // Aynı paketin parçaları farklı toplam bildiremez
+        assert!(buffer.insert(fragment(0, 3)).unwrap().is_none());
+        assert!(buffer.insert(fragment(1, 4)).is_err());
+    }
+
+    #[test]
+    fn oversized_packets_cannot_be_split() {
+        let original = data(FRAGMENT_SIZE * MAX_FRAGMENTS as usize + 1);
+        assert!(split(&original, &mut StdRng::seed_from_u64(1)).is_err());
+    }
+
+    #[test]
+    fn expired_partial_packets_release_their_bytes() {
+        let original = data(FRAGMENT_SIZE * 2);
+        let mut fragments = split(&original, &mut StdRng::seed_from_u64(1)).unwrap();
+
+        let mut buffer = FragmentBuffer::new(Duration::ZERO);
+        assert!(buffer.insert(fragments.remove(0)).unwrap().is_none());
+        assert_eq!(buffer.buffered_bytes(), FRAGMENT_SIZE);
+
+        std::thread::sleep(Duration::from_millis(5));
+        buffer.prune_expired();
+        assert_eq!(buffer.buffered_bytes(), 0);
+
+        // Eksik parçanın kalanı yeni bir paket olarak beklemeye başlar
+        assert!(buffer.insert(fragments.remove(0)).unwrap().is_none());
+    }
+}
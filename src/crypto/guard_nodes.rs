@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Koruma düğümü seçimi
+// Her rota ilk düğümünü küçük ve uzun ömürlü bir giriş kümesinden seçer,
+// böylece kötü niyetli bir düğümün bir gün ilk düğüm olma olasılığı sınırlanır
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardNode {
+    pub peer_id: String,
+    pub selected_at: u64,
+    // Koruma düğümünün döndürüleceği zaman
+    pub expires_at: u64,
+    // Erişilemez olduğu ilk zaman, erişilebiliyorsa None
+    pub unreachable_since: Option<u64>,
+}
+
+impl GuardNode {
+    fn parsed_peer_id(&self) -> Option<PeerId> {
+        self.peer_id.parse().ok()
+    }
+}
+
+// Koruma düğümlerini seçen, döndüren ve diskte saklayan yönetici
+pub struct GuardManager {
+    path: PathBuf,
+    guards: Vec<GuardNode>,
+    guard_count: usize,
+    rotation_period: Duration,
+    unreachable_timeout: Duration,
+}
+
+impl GuardManager {
+    // Kayıtlı koruma düğümlerini yükle, dosya yoksa boş başla
+    pub fn load(
+        path: impl Into<PathBuf>,
+        guard_count: usize,
+        rotation_period: Duration,
+        unreachable_timeout: Duration,
+    ) -> Result<Self> {
+        let path = path.into();
+        let guards = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Koruma düğümü dosyası okunamadı ({}): {}", path.display(), e))?,
+            Err(_) => Vec::new(),
+        };
+
+        Ok(Self {
+            path,
+            guards,
+            guard_count,
+            rotation_period,
+            unreachable_timeout,
+        })
+    }
+
+    // Koruma düğümlerini diske yaz
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&self.guards)?)?;
+
+        Ok(())
+    }
+
+    // Mevcut koruma düğümleri
+    pub fn guards(&self) -> &[GuardNode] {
+        &self.guards
+    }
+
+    // Süresi dolan ya da uzun süredir erişilemeyen düğümleri çıkar, eksikleri tamamla
+    // Değişiklik olduysa true döner
+    pub fn refresh(&mut self, candidates: &[PeerId]) -> bool {
+        let now = unix_now();
+        let before = self.guards.len();
+        let unreachable_timeout = self.unreachable_timeout.as_secs();
+
+        self.guards.retain(|guard| {
+            let expired = guard.expires_at <= now;
+            let lost = guard.unreachable_since
+                .map(|since| now.saturating_sub(since) >= unreachable_timeout)
+                .unwrap_or(false);
+
+            !expired && !lost
+        });
+
+        let removed = before != self.guards.len();
+
+        // Yeni koruma düğümleri yalnızca küme eksik kaldığında eklenir
+        let mut fresh: Vec<PeerId> = candidates.iter()
+            .filter(|peer_id| !self.is_guard(peer_id))
+            .cloned()
+            .collect();
+        fresh.shuffle(&mut thread_rng());
+
+        let missing = self.guard_count.saturating_sub(self.guards.len());
+        let added: Vec<GuardNode> = fresh.into_iter()
+            .take(missing)
+            .map(|peer_id| self.new_guard(peer_id, now))
+            .collect();
+
+        let changed = removed || !added.is_empty();
+        self.guards.extend(added);
+
+        changed
+    }
+
+    // Rota için kullanılabilir bir koruma düğümü seç
+    // Hiçbir koruma düğümü erişilebilir değilse rastgele bir düğüme geri dönülmez
+    pub fn choose_guard(&self, available: &[PeerId]) -> Result<PeerId> {
+        self.usable_guards(available)
+            .choose(&mut thread_rng())
+            .cloned()
+            .ok_or_else(|| anyhow!("Erişilebilir koruma düğümü yok, rota oluşturulmadı"))
+    }
+
+    // Şu anda kullanılabilen tüm koruma düğümleri
+    pub fn usable_guards(&self, available: &[PeerId]) -> Vec<PeerId> {
+        self.guards.iter()
+            .filter(|guard| guard.unreachable_since.is_none())
+            .filter_map(GuardNode::parsed_peer_id)
+            .filter(|peer_id| available.contains(peer_id))
+            .collect()
+    }
+
+    // Koruma düğümüne ulaşılamadığını kaydet
+    pub fn mark_unreachable(&mut self, peer_id: &PeerId) -> bool {
+        let now = unix_now();
+
+        match self.guard_mut(peer_id) {
+            Some(guard) if guard.unreachable_since.is_none() => {
+                guard.unreachable_since = Some(now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Koruma düğümüne yeniden ulaşıldığını kaydet
+    pub fn mark_reachable(&mut self, peer_id: &PeerId) -> bool {
+        match self.guard_mut(peer_id) {
+            Some(guard) if guard.unreachable_since.is_some() => {
+                guard.unreachable_since = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Tüm koruma düğümlerini unut
+    pub fn reset(&mut self) {
+        self.guards.clear();
+    }
+
+    pub fn is_guard(&self, peer_id: &PeerId) -> bool {
+        let peer_id = peer_id.to_string();
+        self.guards.iter().any(|guard| guard.peer_id == peer_id)
+    }
+
+    fn guard_mut(&mut self, peer_id: &PeerId) -> Option<&mut GuardNode> {
+        let peer_id = peer_id.to_string();
+        self.guards.iter_mut().find(|guard| guard.peer_id == peer_id)
+    }
+
+    // Döndürme zamanlarının aynı ana denk gelmemesi için süreye rastgele pay ekle
+    fn new_guard(&self, peer_id: PeerId, now: u64) -> GuardNode {
+        let rotation = self.rotation_period.as_secs();
+        let jitter = thread_rng().gen_range(0..=rotation / 2);
+
+        GuardNode {
+            peer_id: peer_id.to_string(),
+            selected_at: now,
+            expires_at: now + rotation + jitter,
+            unreachable_since: None,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(unreachable_timeout: Duration) -> GuardManager {
+        let path = std::env::temp_dir().join(format!("kuantum-guards-{}.json", uuid::Uuid::new_v4()));
+        GuardManager::load(path, 2, Duration::from_secs(3600), unreachable_timeout).unwrap()
+    }
+
+    #[test]
+    fn unreachable_guard_is_not_usable() {
+        let peers: Vec<PeerId> = (0..2).map(|_| PeerId::random()).collect();
+        let mut guards = manager(Duration::from_secs(3600));
+        assert!(guards.refresh(&peers));
+
+        assert!(guards.mark_unreachable(&peers[0]));
+        assert!(!guards.mark_unreachable(&peers[0]));
+        assert_eq!(guards.usable_guards(&peers), vec![peers[1]]);
+
+        assert!(guards.mark_unreachable(&peers[1]));
+        assert!(guards.choose_guard(&peers).is_err());
+
+        assert!(guards.mark_reachable(&peers[0]));
+        assert_eq!(guards.choose_guard(&peers).unwrap(), peers[0]);
+    }
+
+    #[test]
+    fn lost_guard_is_replaced() {
+        let peers: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+        let mut guards = manager(Duration::ZERO);
+        guards.refresh(&peers[..2]);
+
+        guards.mark_unreachable(&peers[0]);
+        assert!(guards.refresh(&peers[1..]));
+
+        assert!(!guards.is_guard(&peers[0]));
+        assert!(guards.is_guard(&peers[1]) && guards.is_guard(&peers[2]));
+    }
+
+    #[test]
+    fn guard_not_in_available_set_is_skipped() {
+        let peers: Vec<PeerId> = (0..2).map(|_| PeerId::random()).collect();
+        let mut guards = manager(Duration::from_secs(3600));
+        guards.refresh(&peers);
+
+        assert_eq!(guards.usable_guards(&peers[1..]), vec![peers[1]]);
+    }
+}
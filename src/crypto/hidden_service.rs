@@ -0,0 +1,531 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use rand::{CryptoRng, RngCore};
+use ring::rand as ringrand;
+use ring::signature::{self, Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::onion_routing::{self, RelayKeyPair, RouteHop};
+use super::reply_block::ReplyBlock;
+
+// Servis adreslerinin uzantısı
+pub const SERVICE_ADDRESS_SUFFIX: &str = ".kuantum";
+// Düğüm saatleri arasında kabul edilen fark, bu kadar ileri tarihli tanımlayıcılar da kabul edilir
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+// Rendezvous bağlantısını tanımlayan rastgele çerez
+pub type RendezvousCookie = [u8; 16];
+
+// Açık anahtardan servis adresi türet
+// Adres anahtarın kendisini içerdiğinden tanımlayıcı kendi kendini doğrular
+pub fn service_address(public_key: &[u8]) -> String {
+    format!("{}{}", hex::encode(public_key), SERVICE_ADDRESS_SUFFIX)
+}
+
+// Servis adresinden açık anahtarı çıkar
+pub fn parse_service_address(address: &str) -> Result<Vec<u8>> {
+    let encoded = address.strip_suffix(SERVICE_ADDRESS_SUFFIX)
+        .ok_or_else(|| anyhow!("Geçersiz servis adresi: {}", address))?;
+    let public_key = hex::decode(encoded)
+        .map_err(|_| anyhow!("Geçersiz servis adresi: {}", address))?;
+
+    if public_key.len() != 32 {
+        return Err(anyhow!("Geçersiz servis adresi: {}", address));
+    }
+
+    Ok(public_key)
+}
+
+// Servisin tanıtım noktası
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntroPoint {
+    pub peer_id: Vec<u8>,
+    pub relay_key: [u8; 32],
+}
+
+impl IntroPoint {
+    pub fn route_hop(&self) -> Result<RouteHop> {
+        let peer_id = PeerId::from_bytes(&self.peer_id)
+            .map_err(|_| anyhow!("Geçersiz tanıtım noktası"))?;
+
+        Ok(RouteHop::new(peer_id, self.relay_key))
+    }
+}
+
+// Servis tarafından imzalanmış tanımlayıcı
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDescriptor {
+    // Servisin Ed25519 açık anahtarı
+    pub public_key: Vec<u8>,
+    // Tanıtım mesajlarının şifrelendiği X25519 anahtarı
+    pub intro_key: [u8; 32],
+    pub intro_points: Vec<IntroPoint>,
+    pub published_at: u64,
+    pub signature: Vec<u8>,
+}
+
+impl ServiceDescriptor {
+    // Servis adresini döndür
+    pub fn address(&self) -> String {
+        service_address(&self.public_key)
+    }
+
+    // İmzayı servisin açık anahtarı ile doğrula
+    pub fn verify(&self) -> Result<()> {
+        let signed = descriptor_signed_bytes(&self.public_key, &self.intro_key, &self.intro_points, self.published_at)?;
+
+        signature::UnparsedPublicKey::new(&signature::ED25519, &self.public_key)
+            .verify(&signed, &self.signature)
+            .map_err(|_| anyhow!("Tanımlayıcı imzası geçersiz: {}", self.address()))
+    }
+}
+
+fn descriptor_signed_bytes(
+    public_key: &[u8],
+    intro_key: &[u8; 32],
+    intro_points: &[IntroPoint],
+    published_at: u64,
+) -> Result<Vec<u8>> {
+    let mut signed = b"kuantum-descriptor".to_vec();
+    signed.extend_from_slice(&bincode::serialize(&(public_key, intro_key, intro_points, published_at))?);
+
+    Ok(signed)
+}
+
+fn intro_signed_bytes(intro_peer: &PeerId) -> Vec<u8> {
+    let mut signed = b"kuantum-intro".to_vec();
+    signed.extend_from_slice(&intro_peer.to_bytes());
+    signed
+}
+
+// İstemcinin servise tanıtım noktası üzerinden ilettiği bilgiler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Introduction {
+    pub rendezvous_point: Vec<u8>,
+    pub rendezvous_key: [u8; 32],
+    pub cookie: RendezvousCookie,
+    // Uçtan uca şifreleme için istemcinin seçtiği oturum anahtarı
+    pub session_key: [u8; 32],
+}
+
+impl Introduction {
+    pub fn rendezvous_hop(&self) -> Result<RouteHop> {
+        let peer_id = PeerId::from_bytes(&self.rendezvous_point)
+            .map_err(|_| anyhow!("Geçersiz rendezvous noktası"))?;
+
+        Ok(RouteHop::new(peer_id, self.rendezvous_key))
+    }
+}
+
+// Ağda barındırılan gizli servis
+pub struct HiddenService {
+    // Diske yazılabilmesi için imza anahtarının PKCS#8 kodlaması da tutulur
+    signing_pkcs8: Vec<u8>,
+    signing_key: Ed25519KeyPair,
+    intro_keys: RelayKeyPair,
+    pub intro_points: Vec<IntroPoint>,
+}
+
+impl HiddenService {
+    // Yeni bir servis kimliği oluştur
+    // Tanıtım anahtarları verilen RNG'den üretilir; ring dışarıdan RNG kabul etmediğinden
+    // imza anahtarı her zaman işletim sisteminden alınır
+    pub fn new<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Result<Self> {
+        let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&ringrand::SystemRandom::new())?;
+
+        Self::from_keys(pkcs8_bytes.as_ref().to_vec(), RelayKeyPair::generate(rng))
+    }
+
+    // Kayıtlı anahtarlarla servisi yeniden oluştur, adres değişmez
+    fn from_keys(signing_pkcs8: Vec<u8>, intro_keys: RelayKeyPair) -> Result<Self> {
+        let signing_key = Ed25519KeyPair::from_pkcs8(&signing_pkcs8)?;
+
+        Ok(Self {
+            signing_pkcs8,
+            signing_key,
+            intro_keys,
+            intro_points: Vec::new(),
+        })
+    }
+
+    // Servisin adresi
+    pub fn address(&self) -> String {
+        service_address(self.signing_key.public_key().as_ref())
+    }
+
+    // Mevcut tanıtım noktaları ile imzalı tanımlayıcı oluştur
+    pub fn descriptor(&self) -> Result<ServiceDescriptor> {
+        let public_key = self.signing_key.public_key().as_ref().to_vec();
+        let intro_key = self.intro_keys.public_bytes();
+        let published_at = unix_now();
+
+        let signed = descriptor_signed_bytes(&public_key, &intro_key, &self.intro_points, published_at)?;
+        let signature = self.signing_key.sign(&signed).as_ref().to_vec();
+
+        Ok(ServiceDescriptor {
+            public_key,
+            intro_key,
+            intro_points: self.intro_points.clone(),
+            published_at,
+            signature,
+        })
+    }
+
+    // Tanıtım noktasına kayıt mesajı oluştur
+    pub fn establish_intro(&self, intro_peer: &PeerId, reply_blocks: Vec<ReplyBlock>) -> ServiceCell {
+        ServiceCell::EstablishIntro {
+            public_key: self.signing_key.public_key().as_ref().to_vec(),
+            signature: self.signing_key.sign(&intro_signed_bytes(intro_peer)).as_ref().to_vec(),
+            reply_blocks,
+        }
+    }
+
+    // İstemcinin şifreli tanıtım mesajını aç
+    pub fn open_introduction(&self, sealed: &[u8]) -> Result<Introduction> {
+        let plaintext = self.intro_keys.open_sealed(sealed)?;
+
+        bincode::deserialize(&plaintext).map_err(|e| anyhow!("Tanıtım çözümleme hatası: {}", e))
+    }
+}
+
+// Tanıtım mesajını servisin tanıtım anahtarına şifrele
+pub fn seal_introduction<R: RngCore + CryptoRng + ?Sized>(
+    descriptor: &ServiceDescriptor,
+    introduction: &Introduction,
+    rng: &mut R,
+) -> Result<Vec<u8>> {
+    onion_routing::seal_to(&descriptor.intro_key, &bincode::serialize(introduction)?, rng)
+}
+
+// Tanıtım noktası kaydının servis tarafından imzalandığını doğrula
+pub fn verify_establish_intro(local_peer: &PeerId, public_key: &[u8], signature_bytes: &[u8]) -> Result<String> {
+    signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(&intro_signed_bytes(local_peer), signature_bytes)
+        .map_err(|_| anyhow!("Tanıtım noktası kaydı imzası geçersiz"))?;
+
+    Ok(service_address(public_key))
+}
+
+// Rendezvous bağlantısının tarafı
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RendezvousSide {
+    Client,
+    Service,
+}
+
+impl RendezvousSide {
+    pub fn other(self) -> Self {
+        match self {
+            RendezvousSide::Client => RendezvousSide::Service,
+            RendezvousSide::Service => RendezvousSide::Client,
+        }
+    }
+}
+
+// Gizli servis protokolü mesajları
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServiceCell {
+    // Servis bir tanıtım noktasına kaydolur ve ona ulaşmak için cevap blokları bırakır
+    EstablishIntro {
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+        reply_blocks: Vec<ReplyBlock>,
+    },
+    // İstemcinin servise şifreli tanıtımı, tanıtım noktası servise aktarır
+    Introduce {
+        address: String,
+        sealed: Vec<u8>,
+    },
+    // Rendezvous noktasına giden ve oradan karşı tarafa aktarılan mesaj
+    Rendezvous {
+        cookie: RendezvousCookie,
+        side: RendezvousSide,
+        reply_blocks: Vec<ReplyBlock>,
+        payload: Option<Vec<u8>>,
+    },
+}
+
+impl ServiceCell {
+    // Hücrenin taşıdığı cevap bloklarına yenilerini ekle
+    // Kayıt imzası yalnızca tanıtım noktasını kapsadığından bloklar imzadan sonra eklenebilir
+    pub fn attach_reply_blocks(&mut self, blocks: Vec<ReplyBlock>) {
+        match self {
+            ServiceCell::EstablishIntro { reply_blocks, .. } | ServiceCell::Rendezvous { reply_blocks, .. } => {
+                reply_blocks.extend(blocks);
+            }
+            ServiceCell::Introduce { .. } => {}
+        }
+    }
+}
+
+// Rendezvous noktasında iki tarafı birleştiren devre
+#[derive(Default)]
+pub struct RendezvousCircuit {
+    client_blocks: Vec<ReplyBlock>,
+    service_blocks: Vec<ReplyBlock>,
+    // Karşı tarafın cevap bloğu gelene kadar bekleyen mesajlar
+    pending: VecDeque<(RendezvousSide, Vec<u8>)>,
+}
+
+impl RendezvousCircuit {
+    // Bir taraftan gelen cevap bloklarını ve mesajı kaydet
+    // Karşı tarafa iletilebilecek mesajları blokları ile birlikte döndür
+    pub fn accept(
+        &mut self,
+        side: RendezvousSide,
+        reply_blocks: Vec<ReplyBlock>,
+        payload: Option<Vec<u8>>,
+    ) -> Vec<(ReplyBlock, RendezvousSide, Vec<u8>)> {
+        self.blocks_mut(side).extend(reply_blocks);
+        if let Some(payload) = payload {
+            self.pending.push_back((side, payload));
+        }
+
+        let mut ready = Vec::new();
+        let mut waiting = VecDeque::new();
+
+        while let Some((from, payload)) = self.pending.pop_front() {
+            match self.blocks_mut(from.other()).pop() {
+                Some(block) => ready.push((block, from, payload)),
+                None => waiting.push_back((from, payload)),
+            }
+        }
+
+        self.pending = waiting;
+        ready
+    }
+
+    fn blocks_mut(&mut self, side: RendezvousSide) -> &mut Vec<ReplyBlock> {
+        match side {
+            RendezvousSide::Client => &mut self.client_blocks,
+            RendezvousSide::Service => &mut self.service_blocks,
+        }
+    }
+}
+
+// Uç noktada kurulan gizli servis oturumu
+pub struct ServiceSession {
+    pub address: String,
+    pub side: RendezvousSide,
+    pub rendezvous: RouteHop,
+    pub session_key: [u8; 32],
+    pub established: bool,
+}
+
+impl ServiceSession {
+    // Rendezvous noktası üzerinden gönderilecek veriyi şifrele
+    pub fn seal<R: RngCore + CryptoRng + ?Sized>(&self, data: &[u8], rng: &mut R) -> Result<Vec<u8>> {
+        onion_routing::seal(&self.session_key, data, rng)
+    }
+
+    // Karşı taraftan gelen veriyi çöz
+    pub fn open(&self, data: &[u8]) -> Result<Vec<u8>> {
+        onion_routing::open(&self.session_key, data)
+    }
+}
+
+// Diske yazılan servis anahtarları
+#[derive(Serialize, Deserialize)]
+struct StoredService {
+    // Ed25519 imza anahtarının PKCS#8 kodlaması (hex)
+    signing_key: String,
+    // Tanıtım mesajlarını açan X25519 gizli anahtarı (hex)
+    intro_key: String,
+}
+
+// Barındırılan servislerin anahtarlarını diskte saklar, servis adresleri yeniden başlatmada korunur
+pub struct ServiceKeyStore {
+    path: PathBuf,
+    services: Vec<StoredService>,
+}
+
+impl ServiceKeyStore {
+    // Kayıtlı servis anahtarlarını yükle, dosya yoksa boş başla
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let services = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Servis anahtarı dosyası okunamadı ({}): {}", path.display(), e))?,
+            Err(_) => Vec::new(),
+        };
+
+        Ok(Self { path, services })
+    }
+
+    // Servis anahtarlarını diske yaz
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&self.services)?)?;
+
+        Ok(())
+    }
+
+    // Kayıtlı anahtarlardan servisleri oluştur
+    pub fn services(&self) -> Result<Vec<HiddenService>> {
+        self.services.iter()
+            .map(|stored| {
+                let signing_pkcs8 = hex::decode(&stored.signing_key)
+                    .map_err(|_| anyhow!("Geçersiz servis imza anahtarı"))?;
+                let intro_secret: [u8; 32] = hex::decode(&stored.intro_key).ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| anyhow!("Geçersiz servis tanıtım anahtarı"))?;
+
+                HiddenService::from_keys(signing_pkcs8, RelayKeyPair::from_secret_bytes(intro_secret))
+            })
+            .collect()
+    }
+
+    // Yeni servisin anahtarlarını kaydet
+    pub fn add(&mut self, service: &HiddenService) -> Result<()> {
+        self.services.push(StoredService {
+            signing_key: hex::encode(&service.signing_pkcs8),
+            intro_key: hex::encode(service.intro_keys.secret_bytes()),
+        });
+
+        self.save()
+    }
+}
+
+// Doğrulanmış servis tanımlayıcılarının önbelleği
+// Geçerlilik süresini aşan tanımlayıcılar reddedilir, eski tanıtım noktaları tekrar yayılamaz
+pub struct DescriptorCache {
+    descriptors: HashMap<String, ServiceDescriptor>,
+    validity: Duration,
+}
+
+impl DescriptorCache {
+    pub fn new(validity: Duration) -> Self {
+        Self {
+            descriptors: HashMap::new(),
+            validity,
+        }
+    }
+
+    // Tanımlayıcıyı doğrula ve daha yeniyse kaydet
+    pub fn insert(&mut self, descriptor: ServiceDescriptor) -> Result<bool> {
+        let now = unix_now();
+        if descriptor.published_at > now + MAX_CLOCK_SKEW.as_secs() {
+            return Err(anyhow!("Tanımlayıcının yayın zamanı ileri tarihli: {}", descriptor.address()));
+        }
+        if self.is_expired(&descriptor, now) {
+            return Err(anyhow!("Tanımlayıcının geçerlilik süresi dolmuş: {}", descriptor.address()));
+        }
+
+        descriptor.verify()?;
+
+        let address = descriptor.address();
+        let is_newer = self.descriptors.get(&address)
+            .map(|known| known.published_at < descriptor.published_at)
+            .unwrap_or(true);
+
+        if is_newer {
+            self.descriptors.insert(address, descriptor);
+        }
+
+        Ok(is_newer)
+    }
+
+    // Adres için geçerli tanımlayıcıyı bul
+    pub fn get(&self, address: &str) -> Result<&ServiceDescriptor> {
+        parse_service_address(address)?;
+
+        self.descriptors.get(address)
+            .filter(|descriptor| !self.is_expired(descriptor, unix_now()))
+            .ok_or_else(|| anyhow!("Servis tanımlayıcısı bulunamadı: {}", address))
+    }
+
+    // Süresi dolmuş tanımlayıcıları temizle
+    pub fn prune_expired(&mut self) {
+        let now = unix_now();
+        let validity = self.validity.as_secs();
+        self.descriptors.retain(|_, descriptor| descriptor.published_at.saturating_add(validity) >= now);
+    }
+
+    fn is_expired(&self, descriptor: &ServiceDescriptor, now: u64) -> bool {
+        descriptor.published_at.saturating_add(self.validity.as_secs()) < now
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn signed_descriptor(service: &HiddenService, published_at: u64) -> ServiceDescriptor {
+        let public_key = service.signing_key.public_key().as_ref().to_vec();
+        let intro_key = service.intro_keys.public_bytes();
+        let signed = descriptor_signed_bytes(&public_key, &intro_key, &[], published_at).unwrap();
+
+        ServiceDescriptor {
+            signature: service.signing_key.sign(&signed).as_ref().to_vec(),
+            public_key,
+            intro_key,
+            intro_points: Vec::new(),
+            published_at,
+        }
+    }
+
+    #[test]
+    fn service_keys_survive_reload() {
+        let path = std::env::temp_dir().join(format!("kuantum-services-{}.json", uuid::Uuid::new_v4()));
+        let service = HiddenService::new(&mut thread_rng()).unwrap();
+
+        let mut store = ServiceKeyStore::load(&path).unwrap();
+        store.add(&service).unwrap();
+
+        let loaded = ServiceKeyStore::load(&path).unwrap().services().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].address(), service.address());
+        assert_eq!(loaded[0].intro_keys.public_bytes(), service.intro_keys.public_bytes());
+        loaded[0].descriptor().unwrap().verify().unwrap();
+    }
+
+    #[test]
+    fn descriptor_cache_keeps_newest() {
+        let service = HiddenService::new(&mut thread_rng()).unwrap();
+        let mut cache = DescriptorCache::new(Duration::from_secs(3600));
+        let now = unix_now();
+
+        assert!(cache.insert(signed_descriptor(&service, now - 10)).unwrap());
+        assert!(!cache.insert(signed_descriptor(&service, now - 20)).unwrap());
+        assert!(cache.insert(signed_descriptor(&service, now)).unwrap());
+        assert_eq!(cache.get(&service.address()).unwrap().published_at, now);
+    }
+
+    #[test]
+    fn descriptor_cache_rejects_stale_and_future() {
+        let service = HiddenService::new(&mut thread_rng()).unwrap();
+        let mut cache = DescriptorCache::new(Duration::from_secs(3600));
+        let now = unix_now();
+
+        assert!(cache.insert(signed_descriptor(&service, now - 7200)).is_err());
+        assert!(cache.insert(signed_descriptor(&service, now + 3600)).is_err());
+        assert!(cache.get(&service.address()).is_err());
+    }
+
+    #[test]
+    fn descriptor_cache_rejects_bad_signature() {
+        let service = HiddenService::new(&mut thread_rng()).unwrap();
+        let mut cache = DescriptorCache::new(Duration::from_secs(3600));
+        let mut descriptor = signed_descriptor(&service, unix_now());
+        descriptor.published_at -= 1;
+
+        assert!(cache.insert(descriptor).is_err());
+    }
+}
@@ -0,0 +1,600 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashMap;
+
+use super::hidden_service::{
+    self, DescriptorCache, HiddenService, IntroPoint, Introduction, RendezvousCircuit, RendezvousCookie,
+    RendezvousSide, ServiceCell, ServiceDescriptor, ServiceKeyStore, ServiceSession,
+};
+use super::onion_routing::RouteHop;
+use super::reply_block::ReplyBlock;
+use super::BoxedRng;
+
+// Gizli servis başına tanıtım noktası sayısı
+const INTRO_POINT_COUNT: usize = 3;
+// Her tanıtım noktasına bırakılan cevap bloğu sayısı
+const INTRO_REPLY_BLOCKS: usize = 3;
+// İstemcinin rendezvous noktasına bıraktığı cevap bloğu sayısı
+const RENDEZVOUS_REPLY_BLOCKS: usize = 2;
+
+// Yöneticinin ağa gönderilmek üzere bıraktığı hücreler
+// Soğan paketleri ve cevap blokları düğüm tarafından oluşturulur, yönetici ağa erişmez
+pub enum ServiceOutput {
+    // Soğan rotası üzerinden düğüme gidecek hücre
+    // Gönderen, hücreye kendisine dönen `reply_blocks` kadar cevap bloğu ekler
+    Send { to: RouteHop, cell: ServiceCell, reply_blocks: usize },
+    // Karşı tarafın cevap bloğuyla gidecek hücre
+    Reply { reply_block: ReplyBlock, cell: ServiceCell },
+    // Ağa yayılacak servis tanımlayıcısı
+    Publish(ServiceDescriptor),
+}
+
+// Gizli servis oturumlarında gerçekleşen olaylar
+pub enum ServiceEvent {
+    // Barındırılan servise yeni bir istemci bağlandı
+    Connected { address: String, session: String },
+    // İstemcinin servise bağlantısı kuruldu
+    Established { address: String, session: String },
+    // Karşı taraftan mesaj alındı
+    Message { session: String, data: Vec<u8> },
+}
+
+// Barındırılan servisleri, tanıtım ve rendezvous noktası rollerini ve uç oturumlarını yönetir
+// Gelen hücreleri işler, gönderilecek hücreleri çıktı kuyruğuna ekler
+pub struct HiddenServiceManager {
+    local_peer_id: PeerId,
+    hosted: HashMap<String, HiddenService>,
+    keys: ServiceKeyStore,
+    descriptors: DescriptorCache,
+    intro_registrations: HashMap<String, Vec<ReplyBlock>>,
+    rendezvous_circuits: HashMap<RendezvousCookie, RendezvousCircuit>,
+    sessions: HashMap<RendezvousCookie, ServiceSession>,
+    rng: BoxedRng,
+    outputs: Vec<ServiceOutput>,
+}
+
+impl HiddenServiceManager {
+    // Kayıtlı servisleri anahtar deposundan yükle, tanımlayıcıları ilk yayında oluşturulur
+    pub fn load(local_peer_id: PeerId, keys: ServiceKeyStore, descriptors: DescriptorCache, rng: BoxedRng) -> Result<Self> {
+        let hosted = keys.services()?
+            .into_iter()
+            .map(|service| (service.address(), service))
+            .collect();
+
+        Ok(Self {
+            local_peer_id,
+            hosted,
+            keys,
+            descriptors,
+            intro_registrations: HashMap::new(),
+            rendezvous_circuits: HashMap::new(),
+            sessions: HashMap::new(),
+            rng,
+            outputs: Vec::new(),
+        })
+    }
+
+    // Barındırılan servislerin adresleri
+    pub fn addresses(&self) -> Vec<String> {
+        self.hosted.keys().cloned().collect()
+    }
+
+    // Gönderilmeyi bekleyen hücreleri al
+    pub fn take_outputs(&mut self) -> Vec<ServiceOutput> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    // Yeni bir gizli servis oluştur, yayınlanması çağırana kalır
+    pub fn host(&mut self) -> Result<String> {
+        let service = HiddenService::new(&mut *self.rng)?;
+        let address = service.address();
+
+        // Anahtarlar kaydedilir, servis yeniden başlatmada aynı adresle yayınlanır
+        self.keys.add(&service)?;
+        self.hosted.insert(address.clone(), service);
+
+        Ok(address)
+    }
+
+    // Servisin tanıtım noktalarını duyurulan röleler arasından yenile ve tanımlayıcısını yayınla
+    pub fn publish(&mut self, address: &str, relays: &HashMap<PeerId, [u8; 32]>) -> Result<()> {
+        let service = self.hosted.get_mut(address)
+            .ok_or_else(|| anyhow!("Gizli servis bulunamadı: {}", address))?;
+
+        // Artık duyurulmayan tanıtım noktalarını bırak ve eksikleri tamamla
+        let mut intro_points = service.intro_points.clone();
+        intro_points.retain(|intro| {
+            PeerId::from_bytes(&intro.peer_id)
+                .map(|peer_id| relays.contains_key(&peer_id))
+                .unwrap_or(false)
+        });
+
+        let mut candidates: Vec<PeerId> = relays.keys()
+            .filter(|peer_id| !intro_points.iter().any(|intro| intro.peer_id == peer_id.to_bytes()))
+            .cloned()
+            .collect();
+        candidates.shuffle(&mut *self.rng);
+
+        for peer_id in candidates.into_iter().take(INTRO_POINT_COUNT.saturating_sub(intro_points.len())) {
+            intro_points.push(IntroPoint {
+                peer_id: peer_id.to_bytes(),
+                relay_key: relays[&peer_id],
+            });
+        }
+
+        if intro_points.is_empty() {
+            return Err(anyhow!("Tanıtım noktası olabilecek düğüm yok"));
+        }
+
+        // Her tanıtım noktasına servise ulaşmak için yeni cevap blokları bırak
+        let mut outputs = Vec::new();
+        for intro in &intro_points {
+            let intro_hop = intro.route_hop()?;
+            let cell = service.establish_intro(&intro_hop.peer_id, Vec::new());
+            outputs.push(ServiceOutput::Send { to: intro_hop, cell, reply_blocks: INTRO_REPLY_BLOCKS });
+        }
+
+        service.intro_points = intro_points;
+        let descriptor = service.descriptor()?;
+        self.descriptors.insert(descriptor.clone())?;
+
+        self.outputs.extend(outputs);
+        self.outputs.push(ServiceOutput::Publish(descriptor));
+
+        Ok(())
+    }
+
+    // Ağdan gelen tanımlayıcıyı doğrula ve daha yeniyse kaydet
+    pub fn insert_descriptor(&mut self, descriptor: ServiceDescriptor) -> Result<bool> {
+        self.descriptors.insert(descriptor)
+    }
+
+    // Süresi dolmuş tanımlayıcıları temizle
+    pub fn prune_expired(&mut self) {
+        self.descriptors.prune_expired();
+    }
+
+    // Gizli servise verilen rendezvous noktası üzerinden bağlan, oturum tutamacını döndür
+    pub fn connect(&mut self, address: &str, rendezvous: RouteHop) -> Result<String> {
+        let descriptor = self.descriptors.get(address)?.clone();
+        let intro = descriptor.intro_points.choose(&mut *self.rng)
+            .ok_or_else(|| anyhow!("Servisin tanıtım noktası yok: {}", address))?
+            .route_hop()?;
+
+        let cookie: RendezvousCookie = self.rng.gen();
+        let session_key: [u8; 32] = self.rng.gen();
+
+        let introduction = Introduction {
+            rendezvous_point: rendezvous.peer_id.to_bytes(),
+            rendezvous_key: rendezvous.relay_key,
+            cookie,
+            session_key,
+        };
+        let sealed = hidden_service::seal_introduction(&descriptor, &introduction, &mut *self.rng)?;
+
+        // Önce rendezvous noktasına devreyi kur, ardından tanıtım noktası üzerinden servise rendezvous bilgisini ilet
+        self.outputs.push(ServiceOutput::Send {
+            to: rendezvous.clone(),
+            cell: ServiceCell::Rendezvous {
+                cookie,
+                side: RendezvousSide::Client,
+                reply_blocks: Vec::new(),
+                payload: None,
+            },
+            reply_blocks: RENDEZVOUS_REPLY_BLOCKS,
+        });
+        self.outputs.push(ServiceOutput::Send {
+            to: intro,
+            cell: ServiceCell::Introduce { address: address.to_string(), sealed },
+            reply_blocks: 0,
+        });
+
+        self.sessions.insert(cookie, ServiceSession {
+            address: address.to_string(),
+            side: RendezvousSide::Client,
+            rendezvous,
+            session_key,
+            established: false,
+        });
+
+        Ok(session_handle(&cookie))
+    }
+
+    // Kurulu oturum üzerinden karşı tarafa veri gönder
+    pub fn send_data(&mut self, handle: &str, data: &[u8]) -> Result<()> {
+        let cookie = *self.sessions.keys()
+            .find(|cookie| session_handle(cookie) == handle)
+            .ok_or_else(|| anyhow!("Gizli servis oturumu bulunamadı: {}", handle))?;
+
+        self.send_rendezvous(cookie, Some(data))
+    }
+
+    // Rendezvous noktasına veri ve yeni cevap bloğu gönder
+    fn send_rendezvous(&mut self, cookie: RendezvousCookie, data: Option<&[u8]>) -> Result<()> {
+        let session = self.sessions.get(&cookie)
+            .ok_or_else(|| anyhow!("Gizli servis oturumu bulunamadı"))?;
+
+        let cell = ServiceCell::Rendezvous {
+            cookie,
+            side: session.side,
+            reply_blocks: Vec::new(),
+            payload: data.map(|data| session.seal(data, &mut *self.rng)).transpose()?,
+        };
+        self.outputs.push(ServiceOutput::Send { to: session.rendezvous.clone(), cell, reply_blocks: 1 });
+
+        Ok(())
+    }
+
+    // Gizli servis protokolü mesajlarını işle
+    pub fn handle_cell(&mut self, cell: ServiceCell) -> Result<Vec<ServiceEvent>> {
+        let mut events = Vec::new();
+
+        match cell {
+            ServiceCell::EstablishIntro { public_key, signature, reply_blocks } => {
+                // Tanıtım noktası rolü: servisin cevap bloklarını sakla
+                let address = hidden_service::verify_establish_intro(&self.local_peer_id, &public_key, &signature)?;
+                self.intro_registrations.insert(address, reply_blocks);
+            }
+            ServiceCell::Introduce { address, sealed } => {
+                if let Some(service) = self.hosted.get(&address) {
+                    // Servis rolü: rendezvous noktasına devre kur
+                    let introduction = service.open_introduction(&sealed)?;
+                    let rendezvous = introduction.rendezvous_hop()?;
+
+                    self.sessions.insert(introduction.cookie, ServiceSession {
+                        address: address.clone(),
+                        side: RendezvousSide::Service,
+                        rendezvous,
+                        session_key: introduction.session_key,
+                        established: true,
+                    });
+                    events.push(ServiceEvent::Connected { address, session: session_handle(&introduction.cookie) });
+
+                    self.send_rendezvous(introduction.cookie, Some(&[]))?;
+                } else {
+                    // Tanıtım noktası rolü: tanıtımı servisin cevap bloğu ile aktar
+                    let reply_block = self.intro_registrations.get_mut(&address)
+                        .and_then(|blocks| blocks.pop())
+                        .ok_or_else(|| anyhow!("Servis için cevap bloğu kalmadı: {}", address))?;
+
+                    self.outputs.push(ServiceOutput::Reply {
+                        reply_block,
+                        cell: ServiceCell::Introduce { address, sealed },
+                    });
+                }
+            }
+            ServiceCell::Rendezvous { cookie, side, reply_blocks, payload } => {
+                let is_endpoint = self.sessions.get(&cookie)
+                    .map(|session| session.side != side)
+                    .unwrap_or(false);
+
+                if is_endpoint {
+                    self.receive_rendezvous_data(cookie, payload, &mut events)?;
+                } else {
+                    // Rendezvous noktası rolü: mesajı karşı tarafın cevap bloğu ile aktar
+                    let ready = self.rendezvous_circuits.entry(cookie)
+                        .or_default()
+                        .accept(side, reply_blocks, payload);
+
+                    for (reply_block, from, payload) in ready {
+                        self.outputs.push(ServiceOutput::Reply {
+                            reply_block,
+                            cell: ServiceCell::Rendezvous {
+                                cookie,
+                                side: from,
+                                reply_blocks: Vec::new(),
+                                payload: Some(payload),
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    // Karşı taraftan rendezvous üzerinden gelen veriyi çöz
+    fn receive_rendezvous_data(
+        &mut self,
+        cookie: RendezvousCookie,
+        payload: Option<Vec<u8>>,
+        events: &mut Vec<ServiceEvent>,
+    ) -> Result<()> {
+        let session = self.sessions.get_mut(&cookie)
+            .ok_or_else(|| anyhow!("Gizli servis oturumu bulunamadı"))?;
+        let data = match payload {
+            Some(payload) => session.open(&payload)?,
+            None => return Ok(()),
+        };
+
+        if !session.established {
+            session.established = true;
+            events.push(ServiceEvent::Established { address: session.address.clone(), session: session_handle(&cookie) });
+        }
+        if !data.is_empty() {
+            events.push(ServiceEvent::Message { session: session_handle(&cookie), data });
+        }
+
+        // Rendezvous noktasında kullanılan cevap bloğunun yerine yenisini bırak
+        self.send_rendezvous(cookie, None)
+    }
+}
+
+// Konsolda gösterilen kısa oturum tutamacı
+fn session_handle(cookie: &RendezvousCookie) -> String {
+    hex::encode(&cookie[..4])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::onion_routing::RelayKeyPair;
+    use crate::crypto::reply_block::ReplyBlockStore;
+    use rand::thread_rng;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    const SERVICE: usize = 0;
+    const CLIENT: usize = 1;
+    const INTRO: usize = 2;
+    const RENDEZVOUS: usize = 3;
+
+    // Servis, istemci, tanıtım ve rendezvous düğümlerinden oluşan ağ
+    // Hücreler soğan paketine sarılmadan taşınır, cevap bloklarının hedefi bloğun ilk düğümünden bulunur
+    struct TestNetwork {
+        nodes: Vec<HiddenServiceManager>,
+        hops: Vec<RouteHop>,
+        key_paths: Vec<PathBuf>,
+        reply_blocks: ReplyBlockStore,
+        // Düğümlerde gerçekleşen olaylar
+        events: Vec<(usize, ServiceEvent)>,
+    }
+
+    impl TestNetwork {
+        fn new() -> Self {
+            let mut network = Self {
+                nodes: Vec::new(),
+                hops: Vec::new(),
+                key_paths: Vec::new(),
+                reply_blocks: ReplyBlockStore::new(Duration::from_secs(600)),
+                events: Vec::new(),
+            };
+
+            for _ in 0..4 {
+                let hop = RouteHop::new(PeerId::random(), RelayKeyPair::generate(&mut thread_rng()).public_bytes());
+                let path = std::env::temp_dir().join(format!("kuantum-services-{}.json", uuid::Uuid::new_v4()));
+                let keys = ServiceKeyStore::load(&path).unwrap();
+                let descriptors = DescriptorCache::new(Duration::from_secs(3600));
+
+                network.nodes.push(HiddenServiceManager::load(hop.peer_id, keys, descriptors, super::super::os_rng()).unwrap());
+                network.hops.push(hop);
+                network.key_paths.push(path);
+            }
+
+            network
+        }
+
+        fn relays(&self, nodes: &[usize]) -> HashMap<PeerId, [u8; 32]> {
+            nodes.iter().map(|&node| (self.hops[node].peer_id, self.hops[node].relay_key)).collect()
+        }
+
+        fn node_of(&self, peer_id: &PeerId) -> usize {
+            self.hops.iter().position(|hop| hop.peer_id == *peer_id).unwrap()
+        }
+
+        // Servisi verilen röleler arasından seçilen tanıtım noktalarıyla yayınla
+        fn publish(&mut self, address: &str, relays: &[usize]) -> Result<()> {
+            let relays = self.relays(relays);
+            self.nodes[SERVICE].publish(address, &relays)
+        }
+
+        // Yayınlanan tanımlayıcı
+        fn published_descriptor(&mut self) -> ServiceDescriptor {
+            match self.nodes[SERVICE].take_outputs().pop() {
+                Some(ServiceOutput::Publish(descriptor)) => descriptor,
+                _ => panic!("tanımlayıcı yayınlanmadı"),
+            }
+        }
+
+        // Servisi barındır, tanıtım noktasına kaydet ve tanımlayıcıyı istemciye ulaştır
+        fn host_service(&mut self) -> String {
+            let address = self.nodes[SERVICE].host().unwrap();
+            self.publish(&address, &[INTRO]).unwrap();
+            self.deliver().unwrap();
+            address
+        }
+
+        // Bekleyen hücreleri hedef düğümlere taşı
+        fn deliver(&mut self) -> Result<()> {
+            loop {
+                let mut pending = Vec::new();
+                for (node, manager) in self.nodes.iter_mut().enumerate() {
+                    pending.extend(manager.take_outputs().into_iter().map(|output| (node, output)));
+                }
+                if pending.is_empty() {
+                    return Ok(());
+                }
+
+                for (from, output) in pending {
+                    match output {
+                        ServiceOutput::Send { to, mut cell, reply_blocks } => {
+                            let blocks = (0..reply_blocks)
+                                .map(|_| self.reply_blocks.create(std::slice::from_ref(&self.hops[from]), &mut thread_rng()))
+                                .collect::<Result<Vec<_>>>()?;
+                            cell.attach_reply_blocks(blocks);
+                            self.handle(self.node_of(&to.peer_id), cell)?;
+                        }
+                        ServiceOutput::Reply { reply_block, cell } => {
+                            let (first_hop, _) = reply_block.attach_reply(&[], &mut thread_rng())?;
+                            self.handle(self.node_of(&first_hop), cell)?;
+                        }
+                        ServiceOutput::Publish(descriptor) => {
+                            for (node, manager) in self.nodes.iter_mut().enumerate() {
+                                if node != from {
+                                    manager.insert_descriptor(descriptor.clone())?;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        fn handle(&mut self, node: usize, cell: ServiceCell) -> Result<()> {
+            let events = self.nodes[node].handle_cell(cell)?;
+            self.events.extend(events.into_iter().map(|event| (node, event)));
+            Ok(())
+        }
+
+        fn messages(&self, node: usize) -> Vec<Vec<u8>> {
+            self.events.iter()
+                .filter_map(|(at, event)| match event {
+                    ServiceEvent::Message { data, .. } if *at == node => Some(data.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    impl Drop for TestNetwork {
+        fn drop(&mut self) {
+            for path in &self.key_paths {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    #[test]
+    fn client_and_service_exchange_messages_through_rendezvous() {
+        let mut network = TestNetwork::new();
+        let address = network.host_service();
+
+        let rendezvous = network.hops[RENDEZVOUS].clone();
+        let session = network.nodes[CLIENT].connect(&address, rendezvous).unwrap();
+        network.deliver().unwrap();
+
+        assert!(network.events.iter().any(|(node, event)| *node == SERVICE
+            && matches!(event, ServiceEvent::Connected { address: a, session: s } if *a == address && *s == session)));
+        assert!(network.events.iter().any(|(node, event)| *node == CLIENT
+            && matches!(event, ServiceEvent::Established { address: a, session: s } if *a == address && *s == session)));
+
+        // Her mesaj rendezvous noktasında kullanılan bloğun yerine yenisini bırakır, oturum tükenmez
+        for round in 0..5u8 {
+            network.nodes[CLIENT].send_data(&session, &[b'c', round]).unwrap();
+            network.deliver().unwrap();
+            network.nodes[SERVICE].send_data(&session, &[b's', round]).unwrap();
+            network.deliver().unwrap();
+        }
+
+        let expected = |tag: u8| (0..5u8).map(|round| vec![tag, round]).collect::<Vec<_>>();
+        assert_eq!(network.messages(SERVICE), expected(b'c'));
+        assert_eq!(network.messages(CLIENT), expected(b's'));
+        assert!(network.messages(RENDEZVOUS).is_empty());
+    }
+
+    #[test]
+    fn rendezvous_point_sees_only_sealed_payloads() {
+        let mut network = TestNetwork::new();
+        let address = network.host_service();
+
+        let rendezvous = network.hops[RENDEZVOUS].clone();
+        let session = network.nodes[CLIENT].connect(&address, rendezvous).unwrap();
+        network.deliver().unwrap();
+        network.nodes[CLIENT].send_data(&session, b"gizli mesaj").unwrap();
+
+        // İstemcinin gönderdiği hücre rendezvous noktasına şifreli ulaşır
+        let outputs = network.nodes[CLIENT].take_outputs();
+        assert!(matches!(&outputs[..], [ServiceOutput::Send {
+            to,
+            cell: ServiceCell::Rendezvous { payload: Some(payload), side: RendezvousSide::Client, .. },
+            reply_blocks: 1,
+        }] if to.peer_id == network.hops[RENDEZVOUS].peer_id
+            && !payload.windows(b"gizli mesaj".len()).any(|window| window == b"gizli mesaj")));
+    }
+
+    #[test]
+    fn publish_requires_intro_points() {
+        let mut network = TestNetwork::new();
+        let address = network.nodes[SERVICE].host().unwrap();
+
+        assert!(network.publish(&address, &[]).is_err());
+        assert!(network.nodes[SERVICE].take_outputs().is_empty());
+        assert!(network.publish("bilinmeyen.kuantum", &[INTRO]).is_err());
+    }
+
+    #[test]
+    fn publish_keeps_announced_intro_points() {
+        let mut network = TestNetwork::new();
+        let address = network.nodes[SERVICE].host().unwrap();
+
+        network.publish(&address, &[INTRO]).unwrap();
+        network.nodes[SERVICE].take_outputs();
+
+        // Hâlâ duyurulan tanıtım noktası korunur, eksikler yeni rölelerden tamamlanır
+        network.publish(&address, &[INTRO, RENDEZVOUS, CLIENT]).unwrap();
+        let descriptor = network.published_descriptor();
+
+        assert_eq!(descriptor.intro_points.len(), 3);
+        assert_eq!(descriptor.intro_points[0].peer_id, network.hops[INTRO].peer_id.to_bytes());
+
+        // Duyurulmayan tanıtım noktaları bırakılır
+        network.publish(&address, &[CLIENT]).unwrap();
+        let descriptor = network.published_descriptor();
+
+        assert_eq!(descriptor.intro_points.len(), 1);
+        assert_eq!(descriptor.intro_points[0].peer_id, network.hops[CLIENT].peer_id.to_bytes());
+    }
+
+    #[test]
+    fn intro_registration_is_bound_to_intro_point() {
+        let mut network = TestNetwork::new();
+        let address = network.nodes[SERVICE].host().unwrap();
+        network.publish(&address, &[INTRO]).unwrap();
+
+        // Tanıtım noktasına imzalanan kayıt başka bir düğümde geçersizdir
+        let cell = network.nodes[SERVICE].take_outputs().into_iter()
+            .find_map(|output| match output {
+                ServiceOutput::Send { cell, .. } => Some(cell),
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(network.nodes[RENDEZVOUS].handle_cell(cell.clone()).is_err());
+        assert!(network.nodes[INTRO].handle_cell(cell).is_ok());
+    }
+
+    #[test]
+    fn intro_point_forwards_only_while_reply_blocks_last() {
+        let mut network = TestNetwork::new();
+        let address = network.host_service();
+
+        for _ in 0..INTRO_REPLY_BLOCKS {
+            let rendezvous = network.hops[RENDEZVOUS].clone();
+            network.nodes[CLIENT].connect(&address, rendezvous).unwrap();
+            network.deliver().unwrap();
+        }
+
+        // Servis tanımlayıcıyı yenileyene kadar tanıtım noktası yeni tanıtım aktaramaz
+        let rendezvous = network.hops[RENDEZVOUS].clone();
+        network.nodes[CLIENT].connect(&address, rendezvous).unwrap();
+        assert!(network.deliver().is_err());
+
+        let connected = network.events.iter()
+            .filter(|(_, event)| matches!(event, ServiceEvent::Connected { .. }))
+            .count();
+        assert_eq!(connected, INTRO_REPLY_BLOCKS);
+    }
+
+    #[test]
+    fn unknown_sessions_and_descriptors_are_rejected() {
+        let mut network = TestNetwork::new();
+        let rendezvous = network.hops[RENDEZVOUS].clone();
+        let unknown = hidden_service::service_address(&[7u8; 32]);
+
+        assert!(network.nodes[CLIENT].connect(&unknown, rendezvous).is_err());
+        assert!(network.nodes[CLIENT].send_data("00000000", b"veri").is_err());
+        assert!(network.nodes[CLIENT].take_outputs().is_empty());
+    }
+}
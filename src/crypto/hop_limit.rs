@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Doğrulanabilir atlama sayacı
+// Başlatıcı rastgele bir tohumdan limit uzunluğunda bir özet zinciri kurar ve zincirin sonunu
+// (çapa) pakete koyar. Her düğüm belirteci bir kez daha özetler. Özet geri alınamadığından
+// aracı bir düğüm atlama sayısını azaltamaz ya da limiti uzatamaz.
+// Sayacı baştan kuran bir düğüm çapayı değiştirmek zorundadır; çapa uç noktalara mühürlü içerikte
+// ve yol üzerindeki düğümlerin yol kaydında tutulduğundan değişiklik fark edilir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HopToken {
+    limit: u32,
+    anchor: [u8; 32],
+    token: [u8; 32],
+}
+
+impl HopToken {
+    // En fazla `limit` atlamaya izin veren yeni bir sayaç oluştur
+    pub fn new<R: Rng + ?Sized>(limit: u32, rng: &mut R) -> Self {
+        let seed: [u8; 32] = rng.gen();
+
+        Self {
+            limit,
+            anchor: hash_chain(seed, limit),
+            token: seed,
+        }
+    }
+
+    // Paketin şimdiye kadar aldığı atlama sayısını doğrula
+    // Sayaç yerel üst sınırı aşıyorsa ya da zincir tutmuyorsa hata döner
+    pub fn verify(&self, hop_count: u32, max_hops: u32) -> Result<()> {
+        if self.limit > max_hops {
+            return Err(anyhow!("Atlama limiti çok yüksek: {} > {}", self.limit, max_hops));
+        }
+        if hop_count > self.limit {
+            return Err(anyhow!("Atlama limiti aşıldı: {} > {}", hop_count, self.limit));
+        }
+        if hash_chain(self.token, self.limit - hop_count) != self.anchor {
+            return Err(anyhow!("Atlama sayacı doğrulanamadı"));
+        }
+
+        Ok(())
+    }
+
+    // Zincirin sonu, sayaç yeniden kurulmadıkça yol boyunca değişmez
+    pub fn anchor(&self) -> [u8; 32] {
+        self.anchor
+    }
+
+    // Paket bir sonraki düğüme iletilmeden önce sayacı ilerlet
+    pub fn advance(&mut self) {
+        self.token = hash_chain(self.token, 1);
+    }
+}
+
+fn hash_chain(mut value: [u8; 32], steps: u32) -> [u8; 32] {
+    for _ in 0..steps {
+        let mut input = b"kuantum-hop".to_vec();
+        input.extend_from_slice(&value);
+        value.copy_from_slice(digest::digest(&digest::SHA256, &input).as_ref());
+    }
+
+    value
+}
+
+// Yakın zamanda görülen paketlerin özetleri
+// Anahtar paketin atlamalar boyunca değişmeyen bir kimliğidir (soğan başlığı, Crowds yol kimliği);
+// aynı anahtarın ikinci kez gelmesi bir döngü ya da tekrar gönderim demektir
+pub struct SeenPacketCache {
+    seen: HashMap<[u8; 32], Instant>,
+    lifetime: Duration,
+}
+
+impl SeenPacketCache {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            seen: HashMap::new(),
+            lifetime,
+        }
+    }
+
+    // Paketi kaydet, daha önce görülmüşse false döner
+    pub fn insert(&mut self, packet: &[u8]) -> bool {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(digest::digest(&digest::SHA256, packet).as_ref());
+
+        let lifetime = self.lifetime;
+        match self.seen.get(&key) {
+            Some(seen_at) if seen_at.elapsed() <= lifetime => false,
+            _ => {
+                self.seen.insert(key, Instant::now());
+                true
+            }
+        }
+    }
+
+    // Süresi dolan kayıtları temizle
+    pub fn prune_expired(&mut self) {
+        let lifetime = self.lifetime;
+        self.seen.retain(|_, seen_at| seen_at.elapsed() <= lifetime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::seeded_rng;
+
+    #[test]
+    fn token_verifies_each_hop_up_to_limit() {
+        let mut token = HopToken::new(4, &mut *seeded_rng(1));
+
+        for hop_count in 0..=4 {
+            token.verify(hop_count, 5).unwrap();
+            assert!(token.verify(hop_count + 1, 5).is_err());
+            token.advance();
+        }
+        assert!(token.verify(5, 5).is_err());
+    }
+
+    #[test]
+    fn hop_count_cannot_be_lowered() {
+        let mut token = HopToken::new(4, &mut *seeded_rng(2));
+        token.advance();
+        token.advance();
+
+        assert!(token.verify(1, 5).is_err());
+        assert!(token.verify(0, 5).is_err());
+        token.verify(2, 5).unwrap();
+    }
+
+    #[test]
+    fn limit_above_local_maximum_is_rejected() {
+        let token = HopToken::new(10, &mut *seeded_rng(3));
+
+        assert!(token.verify(0, 5).is_err());
+    }
+
+    #[test]
+    fn reset_token_changes_anchor() {
+        let mut rng = seeded_rng(4);
+        let original = HopToken::new(4, &mut *rng);
+        let reset = HopToken::new(4, &mut *rng);
+
+        // Yeniden kurulan sayaç kendi içinde tutarlıdır, ancak çapası farklıdır
+        reset.verify(0, 5).unwrap();
+        assert_ne!(original.anchor(), reset.anchor());
+    }
+
+    #[test]
+    fn seen_cache_detects_repeats() {
+        let mut cache = SeenPacketCache::new(Duration::from_secs(60));
+
+        assert!(cache.insert(b"paket"));
+        assert!(!cache.insert(b"paket"));
+        assert!(cache.insert(b"diger"));
+    }
+
+    #[test]
+    fn seen_cache_forgets_after_lifetime() {
+        let mut cache = SeenPacketCache::new(Duration::ZERO);
+
+        cache.insert(b"paket");
+        std::thread::sleep(Duration::from_millis(5));
+        cache.prune_expired();
+        assert!(cache.insert(b"paket"));
+    }
+}
@@ -1,13 +1,64 @@
 use anyhow::{anyhow, Result};
-use rand::{rngs::OsRng, RngCore, Rng, seq::SliceRandom};
+use rand::{rngs::{OsRng, StdRng}, CryptoRng, RngCore, Rng, SeedableRng, seq::SliceRandom};
 use ring::{aead, rand as ringrand};
 use ring::rand::SecureRandom;
 use std::vec::Vec;
 
 pub mod fake_traffic;
+pub mod fragment;
+pub mod guard_nodes;
+pub mod hidden_service;
+pub mod hidden_service_manager;
+pub mod hop_limit;
 pub mod anon_protocol;
+pub mod bootstrap;
 pub mod chaotic_routing;
+pub mod circuit;
+pub mod circuit_manager;
+pub mod connection_manager;
+pub mod cover_traffic;
+pub mod direct;
+pub mod flow_control;
 pub mod multi_layer;
+pub mod multipath;
+pub mod node_identity;
+pub mod onion_routing;
+pub mod peer_store;
+pub mod pubsub;
+pub mod rate_limit;
+pub mod reputation;
+pub mod reply_block;
+pub mod route_health;
+pub mod stream_mux;
+pub mod traffic_model;
+pub mod traffic_schedule;
+
+// Yönlendirme ve sahte trafik bileşenlerinin kullandığı rastgele sayı kaynağı
+// Üretimde işletim sisteminden tohumlanan CSPRNG, testlerde sabit tohumlu RNG verilir
+pub trait KuantumRng: RngCore + CryptoRng + Send {}
+
+impl<T: RngCore + CryptoRng + Send> KuantumRng for T {}
+
+pub type BoxedRng = Box<dyn KuantumRng>;
+
+// İşletim sisteminden tohumlanan CSPRNG
+pub fn os_rng() -> BoxedRng {
+    Box::new(StdRng::from_entropy())
+}
+
+// Aynı tohumla aynı diziyi üreten RNG, senaryoları tekrar oynatmak için
+pub fn seeded_rng(seed: u64) -> BoxedRng {
+    Box::new(StdRng::seed_from_u64(seed))
+}
+
+// Verilen kaynaktan tohumlanan bağımsız bir RNG türet
+// Her bileşen kendi dizisini kullanır, birinin tükettiği sayılar diğerlerinin kararlarını kaydırmaz
+pub fn derive_rng(rng: &mut BoxedRng) -> BoxedRng {
+    let mut seed = <StdRng as SeedableRng>::Seed::default();
+    rng.fill_bytes(&mut seed);
+
+    Box::new(StdRng::from_seed(seed))
+}
 
 // Şifreleme katmanlarını tanımla
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,16 +81,14 @@ pub struct EncryptedPacket {
 }
 
 // Rastgele bir yönlendirme yolu oluştur
-pub fn generate_random_route(peer_ids: &[String], length: usize) -> Vec<String> {
-    let mut rng = rand::thread_rng();
-    
+pub fn generate_random_route<R: Rng + ?Sized>(peer_ids: &[String], length: usize, rng: &mut R) -> Vec<String> {
     // En az bir düğüm varsa
     if !peer_ids.is_empty() {
         let mut route = Vec::with_capacity(length);
         
         for _ in 0..length {
             // Rastgele bir peer ID seç
-            let peer = peer_ids.choose(&mut rng)
+            let peer = peer_ids.choose(rng)
                 .expect("Peer ID listesi boş olamaz")
                 .clone();
             
@@ -144,9 +193,8 @@ fn decrypt_chacha20_poly1305(encrypted_data: &[u8], nonce_bytes: &[u8]) -> Resul
 }
 
 // Bir paketi birden fazla katmanda şifrele ve rota ekle
-pub fn create_onion_packet(data: &[u8], peer_ids: &[String], layer_count: usize) -> Result<EncryptedPacket> {
-    let mut rng = rand::thread_rng();
-    
+// Katman ve rota seçimi verilen RNG ile yapılır, anahtarlar her zaman sistem CSPRNG'sinden gelir
+pub fn create_onion_packet<R: Rng + ?Sized>(data: &[u8], peer_ids: &[String], layer_count: usize, rng: &mut R) -> Result<EncryptedPacket> {
     // Kullanılacak şifreleme katmanları
     let layers: Vec<EncryptionLayer> = (0..layer_count)
         .map(|_| {
@@ -160,7 +208,7 @@ pub fn create_onion_packet(data: &[u8], peer_ids: &[String], layer_count: usize)
     
     // Kaotik bir rota oluştur
     let route_length = 3.max(rng.gen_range(3..7)); // En az 3, en fazla 6 düğüm
-    let route = generate_random_route(peer_ids, route_length);
+    let route = generate_random_route(peer_ids, route_length, rng);
     
     // Veriyi şifrele
     let (encrypted_data, nonces) = multi_layer_encrypt(data, &layers)?;
@@ -171,4 +219,25 @@ pub fn create_onion_packet(data: &[u8], peer_ids: &[String], layer_count: usize)
         layers,
         route,
     })
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_rngs_are_reproducible_and_independent() {
+        let mut first_root = seeded_rng(42);
+        let mut second_root = seeded_rng(42);
+
+        let mut a = derive_rng(&mut first_root);
+        let mut b = derive_rng(&mut first_root);
+        let mut a_again = derive_rng(&mut second_root);
+
+        let a_values: Vec<u64> = (0..4).map(|_| a.next_u64()).collect();
+        let b_values: Vec<u64> = (0..4).map(|_| b.next_u64()).collect();
+        let a_again_values: Vec<u64> = (0..4).map(|_| a_again.next_u64()).collect();
+
+        assert_eq!(a_values, a_again_values);
+        assert_ne!(a_values, b_values);
+    }
+}
@@ -84,6 +84,12 @@ impl EncryptionLayer {
     }
 }
 
+impl Default for EncryptionLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Çok katmanlı şifreleme sistemi
 // Verilerin birden fazla katman ile şifrelenmesini sağlar
 #[derive(Debug)]
@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Result};
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Çok yollu iletim kimliği
+pub type MultipathId = [u8; 16];
+
+// Silme kodlamalı bir parça
+// Aynı mesajın herhangi k parçası yükü yeniden oluşturmaya yeter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Share {
+    pub message_id: MultipathId,
+    pub index: u8,
+    pub required: u8,
+    pub total: u8,
+    // Birleştirilen yükün SHA-256 özeti, bozuk ya da sahte parçalar birleştirmeden sonra fark edilir
+    pub payload_hash: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+fn payload_hash(payload: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(digest::digest(&digest::SHA256, payload).as_ref());
+    hash
+}
+
+// Yükü n parçaya böl, herhangi k parçası yeterli olsun
+pub fn split_into_shares(message_id: MultipathId, payload: &[u8], required: u8, total: u8) -> Result<Vec<Share>> {
+    if required == 0 || required > total {
+        return Err(anyhow!("Geçersiz parça sayısı: {}/{}", required, total));
+    }
+
+    // Uzunluk öneki ile eşit boyutlu veri parçaları oluştur
+    let mut data = (payload.len() as u32).to_be_bytes().to_vec();
+    data.extend_from_slice(payload);
+    let shard_len = data.len().div_ceil(required as usize);
+    data.resize(shard_len * required as usize, 0);
+
+    let mut shards: Vec<Vec<u8>> = data.chunks(shard_len).map(|chunk| chunk.to_vec()).collect();
+    shards.resize(total as usize, vec![0u8; shard_len]);
+
+    // Eşlik parçaları yalnızca k < n ise gerekir
+    if required < total {
+        let codec = ReedSolomon::new(required as usize, (total - required) as usize)
+            .map_err(|e| anyhow!("Silme kodlama hatası: {:?}", e))?;
+        codec.encode(&mut shards)
+            .map_err(|e| anyhow!("Silme kodlama hatası: {:?}", e))?;
+    }
+
+    let payload_hash = payload_hash(payload);
+
+    Ok(shards.into_iter()
+        .enumerate()
+        .map(|(index, data)| Share {
+            message_id,
+            index: index as u8,
+            required,
+            total,
+            payload_hash,
+            data,
+        })
+        .collect())
+}
+
+// Bir mesaj için toplanan parçalar
+struct PendingShares {
+    required: u8,
+    payload_hash: [u8; 32],
+    shards: Vec<Option<Vec<u8>>>,
+    created_at: Instant,
+}
+
+// Gelen parçaları toplayıp k parça tamamlanınca yükü yeniden oluşturur
+pub struct ShareAssembler {
+    pending: HashMap<MultipathId, PendingShares>,
+    // Tamamlanan mesajlar, geç gelen parçaları yok saymak için
+    completed: HashMap<MultipathId, Instant>,
+    lifetime: Duration,
+}
+
+impl ShareAssembler {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            completed: HashMap::new(),
+            lifetime,
+        }
+    }
+
+    // Parçayı ekle, yük yeniden oluşturulabildiğinde döndür
+    pub fn accept(&mut self, share: Share) -> Result<Option<Vec<u8>>> {
+        if self.completed.contains_key(&share.message_id) {
+            return Ok(None);
+        }
+        if share.required == 0 || share.required > share.total || share.index >= share.total {
+            return Err(anyhow!("Geçersiz parça başlığı"));
+        }
+
+        let pending = self.pending.entry(share.message_id).or_insert_with(|| PendingShares {
+            required: share.required,
+            payload_hash: share.payload_hash,
+            shards: vec![None; share.total as usize],
+            created_at: Instant::now(),
+        });
+
+        if pending.shards.len() != share.total as usize
+            || pending.required != share.required
+            || pending.payload_hash != share.payload_hash
+        {
+            return Err(anyhow!("Parça başlığı önceki parçalarla uyuşmuyor"));
+        }
+
+        pending.shards[share.index as usize] = Some(share.data);
+
+        let received = pending.shards.iter().filter(|shard| shard.is_some()).count();
+        if received < pending.required as usize {
+            return Ok(None);
+        }
+
+        // Birleştirme başarısız olursa kayıt atılır, mesaj tamamlanmış sayılmaz ve sonraki parçalarla yeniden denenebilir
+        let pending = self.pending.remove(&share.message_id)
+            .ok_or_else(|| anyhow!("Parça kaydı bulunamadı"))?;
+        let payload = reconstruct(pending)?;
+
+        self.completed.insert(share.message_id, Instant::now());
+
+        Ok(Some(payload))
+    }
+
+    // Süresi dolan kayıtları temizle
+    pub fn prune_expired(&mut self) {
+        let lifetime = self.lifetime;
+        self.pending.retain(|_, pending| pending.created_at.elapsed() <= lifetime);
+        self.completed.retain(|_, completed_at| completed_at.elapsed() <= lifetime);
+    }
+}
+
+// Toplanan parçalardan yükü yeniden oluştur ve özetini doğrula
+fn reconstruct(mut pending: PendingShares) -> Result<Vec<u8>> {
+    let required = pending.required as usize;
+    if required < pending.shards.len() {
+        let codec = ReedSolomon::new(required, pending.shards.len() - required)
+            .map_err(|e| anyhow!("Silme kodlama hatası: {:?}", e))?;
+        codec.reconstruct_data(&mut pending.shards)
+            .map_err(|e| anyhow!("Parçalar birleştirilemedi: {:?}", e))?;
+    }
+
+    let data: Vec<u8> = pending.shards.into_iter()
+        .take(required)
+        .flatten()
+        .flatten()
+        .collect();
+
+    if data.len() < 4 {
+        return Err(anyhow!("Birleştirilen yük çok kısa"));
+    }
+
+    let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let payload = data.get(4..4 + length)
+        .ok_or_else(|| anyhow!("Birleştirilen yükün uzunluğu geçersiz"))?;
+
+    if payload_hash(payload) != pending.payload_hash {
+        return Err(anyhow!("Birleştirilen yükün özeti uyuşmuyor"));
+    }
+
+    Ok(payload.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &[u8] = b"silme kodlamali cok yollu mesaj";
+
+    #[test]
+    fn any_k_shares_reconstruct_payload() {
+        let shares = split_into_shares([1; 16], PAYLOAD, 2, 4).unwrap();
+
+        for first in 0..shares.len() {
+            for second in first + 1..shares.len() {
+                let mut assembler = ShareAssembler::new(Duration::from_secs(60));
+                assert!(assembler.accept(shares[first].clone()).unwrap().is_none());
+                assert_eq!(assembler.accept(shares[second].clone()).unwrap().unwrap(), PAYLOAD);
+            }
+        }
+    }
+
+    #[test]
+    fn late_shares_are_ignored() {
+        let shares = split_into_shares([2; 16], PAYLOAD, 2, 3).unwrap();
+        let mut assembler = ShareAssembler::new(Duration::from_secs(60));
+
+        assembler.accept(shares[0].clone()).unwrap();
+        assert!(assembler.accept(shares[1].clone()).unwrap().is_some());
+        assert!(assembler.accept(shares[2].clone()).unwrap().is_none());
+    }
+
+    #[test]
+    fn corrupted_share_does_not_complete_message() {
+        let shares = split_into_shares([3; 16], PAYLOAD, 2, 4).unwrap();
+        let mut assembler = ShareAssembler::new(Duration::from_secs(60));
+
+        let mut corrupted = shares[0].clone();
+        corrupted.data[5] ^= 0xff;
+        assembler.accept(corrupted).unwrap();
+        assert!(assembler.accept(shares[1].clone()).is_err());
+
+        // Mesaj tamamlanmış sayılmadığından kalan sağlam parçalar yükü oluşturur
+        assembler.accept(shares[2].clone()).unwrap();
+        assert_eq!(assembler.accept(shares[3].clone()).unwrap().unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn mismatched_share_header_is_rejected() {
+        let shares = split_into_shares([4; 16], PAYLOAD, 2, 4).unwrap();
+        let other = split_into_shares([4; 16], b"baska", 2, 4).unwrap();
+        let mut assembler = ShareAssembler::new(Duration::from_secs(60));
+
+        assembler.accept(shares[0].clone()).unwrap();
+        assert!(assembler.accept(other[1].clone()).is_err());
+
+        let mut invalid = shares[1].clone();
+        invalid.index = 4;
+        assert!(assembler.accept(invalid).is_err());
+    }
+
+    #[test]
+    fn invalid_share_counts_are_rejected() {
+        assert!(split_into_shares([5; 16], PAYLOAD, 0, 2).is_err());
+        assert!(split_into_shares([5; 16], PAYLOAD, 3, 2).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use libp2p::identity::{self, ed25519};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+// Diskte saklanan düğüm kimliği
+#[derive(Serialize, Deserialize)]
+struct SavedIdentity {
+    // Ed25519 gizli anahtarı, hex
+    secret_key: String,
+}
+
+// Kayıtlı düğüm anahtarını yükle, dosya yoksa yenisini oluşturup kaydet
+// Peer ID yeniden başlatmalarda değişmez; koruma düğümü, önyükleme ve düğüm kayıtlarındaki
+// eşler bu düğümü aynı kimlikle tanımaya devam eder. Bozuk dosya yeni kimlikle ezilmez, hata döner
+pub fn load_or_generate(path: &Path) -> Result<identity::Keypair> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let saved: SavedIdentity = serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Düğüm kimliği dosyası okunamadı ({}): {}", path.display(), e))?;
+            let secret = hex::decode(&saved.secret_key)
+                .ok()
+                .and_then(|bytes| ed25519::SecretKey::from_bytes(bytes).ok())
+                .ok_or_else(|| anyhow!("Düğüm kimliği dosyasında geçersiz anahtar: {}", path.display()))?;
+
+            Ok(identity::Keypair::Ed25519(secret.into()))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            let keypair = ed25519::Keypair::generate();
+            save(path, &keypair)?;
+
+            Ok(identity::Keypair::Ed25519(keypair))
+        }
+        Err(e) => Err(anyhow!("Düğüm kimliği dosyası okunamadı ({}): {}", path.display(), e)),
+    }
+}
+
+// Gizli anahtarı yalnızca sahibinin okuyabileceği bir dosyaya yaz
+fn save(path: &Path, keypair: &ed25519::Keypair) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let saved = SavedIdentity { secret_key: hex::encode(keypair.secret().as_ref()) };
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(serde_json::to_string_pretty(&saved)?.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::PeerId;
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("kuantum-identity-{}/identity.json", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn identity_survives_restart() {
+        let path = temp_path();
+
+        let first = PeerId::from(load_or_generate(&path).unwrap().public());
+        let second = PeerId::from(load_or_generate(&path).unwrap().public());
+        assert_eq!(first, second);
+
+        let other = PeerId::from(load_or_generate(&temp_path()).unwrap().public());
+        assert_ne!(first, other);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn identity_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_path();
+        load_or_generate(&path).unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn corrupt_identity_is_not_replaced() {
+        let path = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{"secret_key": "zz"}"#).unwrap();
+
+        assert!(load_or_generate(&path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"secret_key": "zz"}"#);
+    }
+}
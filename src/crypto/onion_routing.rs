@@ -0,0 +1,556 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use ring::{aead, hkdf};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+// Her şifreli katmanın başına eklenen nonce uzunluğu
+const NONCE_LEN: usize = 12;
+// Poly1305 doğrulama etiketinin uzunluğu
+const TAG_LEN: usize = 16;
+// Başlıkta yer ayrılan en fazla düğüm sayısı, rotalar bundan uzun olamaz
+pub const MAX_ROUTE_HOPS: usize = 8;
+// Bir düğümün yönlendirme bilgisi için ayrılan şifresiz alan
+const SLOT_CONTENT_SIZE: usize = 128;
+// Geçici açık anahtar, şifreli yönlendirme bilgisi ve etiket
+const SLOT_SIZE: usize = 32 + SLOT_CONTENT_SIZE + TAG_LEN;
+// Tüm paketlerin başlığı bu boyuttadır, düğüm rotadaki yerini başlık boyundan çıkaramaz
+pub const HEADER_SIZE: usize = MAX_ROUTE_HOPS * SLOT_SIZE;
+// Tüm paketlerin yükü bu boyuttadır, ileri ve geri yönde her düğümde boyut değişmez
+pub const PAYLOAD_SIZE: usize = 8 * 1024;
+// Uzunluk öneki boyutu
+const LENGTH_PREFIX: usize = 4;
+// Tek bir pakete sığan en büyük yük
+pub const PAYLOAD_CAPACITY: usize = PAYLOAD_SIZE - NONCE_LEN - TAG_LEN - LENGTH_PREFIX;
+
+// Düğümün yönlendirme anahtar çifti
+// Soğan katmanları bu anahtarın açık kısmı ile türetilen anahtarlarla şifrelenir
+pub struct RelayKeyPair {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl RelayKeyPair {
+    // Verilen RNG'den yeni bir X25519 anahtar çifti oluştur
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        Self::from_secret_bytes(ephemeral_secret(rng).to_bytes())
+    }
+
+    // Kayıtlı gizli anahtardan anahtar çiftini oluştur
+    pub fn from_secret_bytes(secret: [u8; 32]) -> Self {
+        let secret = StaticSecret::from(secret);
+        let public = PublicKey::from(&secret);
+
+        Self { secret, public }
+    }
+
+    // Diske yazmak için gizli anahtar
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret.to_bytes()
+    }
+
+    // Diğer düğümlere duyurulacak açık anahtar
+    pub fn public_bytes(&self) -> [u8; 32] {
+        self.public.to_bytes()
+    }
+
+    // Bu düğüme adreslenmiş paketin en dış katmanını çöz
+    // Başlığın ilk dilimi bu düğüme aittir; kalan dilimler kaydırılır ve sona rastgele görünen bir dilim eklenir.
+    // Yük her iki yönde de aynı anahtar akışıyla dönüştürülür, düğüm paketin yönünü ve rotadaki yerini göremez
+    pub fn peel(&self, packet: &OnionPacket) -> Result<PeeledPacket> {
+        if packet.header.len() != HEADER_SIZE || packet.payload.len() != PAYLOAD_SIZE {
+            return Err(anyhow!("Geçersiz paket boyutu: {}+{} byte", packet.header.len(), packet.payload.len()));
+        }
+
+        let mut ephemeral_public = [0u8; 32];
+        ephemeral_public.copy_from_slice(&packet.header[..32]);
+        let shared = self.secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+        let keys = HopKeys::derive(shared.as_bytes())?;
+
+        let content = open_slot(&keys.header, &packet.header[32..SLOT_SIZE])?;
+        let command: RoutingCommand = bincode::deserialize(&content)
+            .map_err(|e| anyhow!("Katman çözümleme hatası: {}", e))?;
+
+        let mut payload = packet.payload.clone();
+        apply_keystream(&keys.payload, &mut payload)?;
+
+        match command {
+            RoutingCommand::Forward { next_hop, delay_ms } => {
+                let next_hop = PeerId::from_bytes(&next_hop)
+                    .map_err(|_| anyhow!("Geçersiz sonraki düğüm"))?;
+
+                let mut header = packet.header[SLOT_SIZE..].to_vec();
+                header.resize(HEADER_SIZE, 0);
+                apply_keystream(&keys.shift, &mut header)?;
+
+                Ok(PeeledPacket::Forward {
+                    next_hop,
+                    delay: Duration::from_millis(delay_ms),
+                    packet: OnionPacket { header, payload },
+                })
+            }
+            // İleri yönlü paketin yükü son düğümün anahtarıyla doğrulanır
+            RoutingCommand::Deliver { reply_id: None } => Ok(PeeledPacket::Deliver {
+                reply_id: None,
+                payload: unpad(&open(&keys.end, &payload)?)?,
+            }),
+            // Cevabın kalan katmanlarını yalnızca cevap bloğunun sahibi açabilir
+            RoutingCommand::Deliver { reply_id } => Ok(PeeledPacket::Deliver { reply_id, payload }),
+        }
+    }
+
+    // seal_to ile bu anahtara şifrelenmiş veriyi çöz
+    pub fn open_sealed(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let layer: SealedLayer = bincode::deserialize(sealed)
+            .map_err(|e| anyhow!("Mühürlü veri çözümleme hatası: {}", e))?;
+
+        let shared = self.secret.diffie_hellman(&PublicKey::from(layer.ephemeral_public));
+        let keys = HopKeys::derive(shared.as_bytes())?;
+
+        open(&keys.end, &layer.ciphertext)
+    }
+}
+
+// Rota üzerindeki bir düğüm ve yönlendirme anahtarı
+#[derive(Debug, Clone)]
+pub struct RouteHop {
+    pub peer_id: PeerId,
+    pub relay_key: [u8; 32],
+    // Düğümün paketi bir sonraki düğüme iletmeden önce bekleteceği süre
+    pub delay: Duration,
+}
+
+impl RouteHop {
+    pub fn new(peer_id: PeerId, relay_key: [u8; 32]) -> Self {
+        Self {
+            peer_id,
+            relay_key,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+// Ağda taşınan soğan paketi
+// Başlık HEADER_SIZE, yük PAYLOAD_SIZE byte'tır; boyutlar rota boyunca değişmez
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnionPacket {
+    // Düğüm başına sabit boyutlu dilimlerden oluşan yönlendirme başlığı
+    pub header: Vec<u8>,
+    // Her düğümde anahtar akışıyla dönüştürülen yük
+    pub payload: Vec<u8>,
+}
+
+// Bir katman çözüldükten sonraki sonuç
+#[derive(Debug)]
+pub enum PeeledPacket {
+    // Paket belirtilen süre bekletildikten sonra bir sonraki düğüme iletilmeli
+    Forward { next_hop: PeerId, delay: Duration, packet: OnionPacket },
+    // Paket bu düğüme ait
+    // İleri yönde yük dolgusu kaldırılmış olarak, cevaplarda ReplyBlockStore::open_reply için olduğu gibi döner
+    Deliver { reply_id: Option<String>, payload: Vec<u8> },
+}
+
+// Katmanı çözen düğümün yapacağı iş
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum RoutingCommand {
+    Forward { next_hop: Vec<u8>, delay_ms: u64 },
+    Deliver { reply_id: Option<String> },
+}
+
+// Tek bir alıcıya mühürlenmiş veri
+#[derive(Serialize, Deserialize)]
+struct SealedLayer {
+    ephemeral_public: [u8; 32],
+    ciphertext: Vec<u8>,
+}
+
+// Paylaşılan sırdan türetilen düğüm anahtarları
+pub(crate) struct HopKeys {
+    // Düğümün başlık dilimini doğrular ve çözer
+    header: [u8; 32],
+    // Kaydırılan başlığı yeniden şifreleyen anahtar akışı
+    shift: [u8; 32],
+    // Yükü dönüştüren anahtar akışı
+    pub(crate) payload: [u8; 32],
+    // İleri yönlü paketin son düğümde doğrulanan iç katmanı
+    end: [u8; 32],
+}
+
+impl HopKeys {
+    fn derive(shared_secret: &[u8]) -> Result<Self> {
+        let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, b"kuantum-onion-v2");
+        let prk = salt.extract(shared_secret);
+
+        Ok(Self {
+            header: expand_key(&prk, b"header")?,
+            shift: expand_key(&prk, b"shift")?,
+            payload: expand_key(&prk, b"payload")?,
+            end: expand_key(&prk, b"end")?,
+        })
+    }
+}
+
+fn expand_key(prk: &hkdf::Prk, info: &[u8]) -> Result<[u8; 32]> {
+    let info = [info];
+    let okm = prk.expand(&info, hkdf::HKDF_SHA256)
+        .map_err(|_| anyhow!("Anahtar türetme hatası"))?;
+
+    let mut key = [0u8; 32];
+    okm.fill(&mut key).map_err(|_| anyhow!("Anahtar türetme hatası"))?;
+
+    Ok(key)
+}
+
+// İleri yönlü soğan paketi oluştur, son düğüm yükü teslim alır
+// Yük PAYLOAD_CAPACITY'yi aşamaz; katmanların geçici anahtarları verilen RNG'den üretilir
+pub fn build_forward_packet<R: RngCore + CryptoRng + ?Sized>(route: &[RouteHop], payload: &[u8], rng: &mut R) -> Result<OnionPacket> {
+    let (header, hop_keys) = build_header(route, None, rng)?;
+
+    let last = hop_keys.last().ok_or_else(|| anyhow!("Rota boş olamaz"))?;
+    let mut payload = seal(&last.end, &pad(payload)?, rng)?;
+    for keys in &hop_keys {
+        apply_keystream(&keys.payload, &mut payload)?;
+    }
+
+    Ok(OnionPacket { header, payload })
+}
+
+// Rota için sabit boyutlu başlığı oluştur ve düğüm anahtarlarını rota sırasıyla döndür
+// Her düğüm ilk dilimi okur ve kalanları kendi anahtar akışıyla kaydırır. Dilimler, önceki düğümlerin
+// akışları önceden uygulanarak yerleştirilir; böylece her düğüm kendi dilimini başta bulur.
+// Kullanılmayan dilimler rastgele doldurulur, düğümler kalan düğüm sayısını göremez.
+pub(crate) fn build_header<R: RngCore + CryptoRng + ?Sized>(
+    route: &[RouteHop],
+    reply_id: Option<String>,
+    rng: &mut R,
+) -> Result<(Vec<u8>, Vec<HopKeys>)> {
+    if route.is_empty() {
+        return Err(anyhow!("Rota boş olamaz"));
+    }
+    if route.len() > MAX_ROUTE_HOPS {
+        return Err(anyhow!("Rota en fazla {} düğüm içerebilir: {}", MAX_ROUTE_HOPS, route.len()));
+    }
+
+    let mut header = vec![0u8; HEADER_SIZE];
+    rng.fill_bytes(&mut header[route.len() * SLOT_SIZE..]);
+
+    let mut hop_keys: Vec<HopKeys> = Vec::with_capacity(route.len());
+    let mut shift_streams: Vec<Vec<u8>> = Vec::with_capacity(route.len());
+
+    for (index, hop) in route.iter().enumerate() {
+        let command = match route.get(index + 1) {
+            Some(next) => RoutingCommand::Forward {
+                next_hop: next.peer_id.to_bytes(),
+                delay_ms: hop.delay.as_millis() as u64,
+            },
+            None => RoutingCommand::Deliver { reply_id: reply_id.clone() },
+        };
+
+        let mut content = bincode::serialize(&command)?;
+        if content.len() > SLOT_CONTENT_SIZE {
+            return Err(anyhow!("Yönlendirme bilgisi başlık dilimine sığmıyor"));
+        }
+        content.resize(SLOT_CONTENT_SIZE, 0);
+
+        // Her dilim için ayrı bir geçici anahtar kullan
+        let ephemeral = ephemeral_secret(rng);
+        let shared = ephemeral.diffie_hellman(&PublicKey::from(hop.relay_key));
+        let keys = HopKeys::derive(shared.as_bytes())?;
+
+        let mut slot = PublicKey::from(&ephemeral).to_bytes().to_vec();
+        slot.extend(seal_slot(&keys.header, &content)?);
+
+        // Önceki düğümler dilimi kaydırırken kendi akışlarını uygulayacak, bunlar önceden geri alınır
+        for (earlier, stream) in shift_streams.iter().enumerate() {
+            let offset = (index - 1 - earlier) * SLOT_SIZE;
+            xor_into(&mut slot, &stream[offset..offset + SLOT_SIZE]);
+        }
+        header[index * SLOT_SIZE..(index + 1) * SLOT_SIZE].copy_from_slice(&slot);
+
+        let mut stream = vec![0u8; HEADER_SIZE];
+        apply_keystream(&keys.shift, &mut stream)?;
+        shift_streams.push(stream);
+        hop_keys.push(keys);
+    }
+
+    Ok((header, hop_keys))
+}
+
+// Veriyi yalnızca verilen yönlendirme anahtarının sahibinin açabileceği şekilde şifrele
+pub fn seal_to<R: RngCore + CryptoRng + ?Sized>(relay_key: &[u8; 32], data: &[u8], rng: &mut R) -> Result<Vec<u8>> {
+    let ephemeral = ephemeral_secret(rng);
+    let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(*relay_key));
+    let keys = HopKeys::derive(shared.as_bytes())?;
+
+    Ok(bincode::serialize(&SealedLayer {
+        ephemeral_public,
+        ciphertext: seal(&keys.end, data, rng)?,
+    })?)
+}
+
+// Yükü uzunluk önekiyle şifreli yük boyutuna doldur
+pub(crate) fn pad(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() > PAYLOAD_CAPACITY {
+        return Err(anyhow!("Yük tek pakete sığmıyor: {} byte (en fazla {})", data.len(), PAYLOAD_CAPACITY));
+    }
+
+    let mut padded = Vec::with_capacity(PAYLOAD_SIZE - NONCE_LEN - TAG_LEN);
+    padded.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    padded.extend_from_slice(data);
+    padded.resize(PAYLOAD_SIZE - NONCE_LEN - TAG_LEN, 0);
+
+    Ok(padded)
+}
+
+// Dolguyu kaldır ve asıl yükü döndür
+pub(crate) fn unpad(padded: &[u8]) -> Result<Vec<u8>> {
+    if padded.len() != PAYLOAD_SIZE - NONCE_LEN - TAG_LEN {
+        return Err(anyhow!("Geçersiz dolgulu yük boyutu: {} byte", padded.len()));
+    }
+
+    let mut prefix = [0u8; LENGTH_PREFIX];
+    prefix.copy_from_slice(&padded[..LENGTH_PREFIX]);
+    let length = u32::from_le_bytes(prefix) as usize;
+
+    padded.get(LENGTH_PREFIX..LENGTH_PREFIX + length)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| anyhow!("Dolgulu yükün uzunluk öneki geçersiz: {}", length))
+}
+
+// Veriyi ChaCha20 anahtar akışıyla dönüştür, aynı çağrı dönüşümü geri alır
+// Anahtarlar her paket için geçici anahtardan türetildiğinden sabit nonce anahtar akışını tekrarlamaz
+pub(crate) fn apply_keystream(key: &[u8; 32], data: &mut [u8]) -> Result<()> {
+    // Etiket kullanılmaz, yükün bütünlüğü son katmanda doğrulanır
+    let _tag = fixed_nonce_key(key)?
+        .seal_in_place_separate_tag(aead::Nonce::assume_unique_for_key([0; NONCE_LEN]), aead::Aad::empty(), data)
+        .map_err(|_| anyhow!("Şifreleme hatası"))?;
+
+    Ok(())
+}
+
+fn xor_into(target: &mut [u8], stream: &[u8]) {
+    for (byte, key) in target.iter_mut().zip(stream) {
+        *byte ^= key;
+    }
+}
+
+// Başlık dilimini şifrele, dilim anahtarı yalnızca bir kez kullanılır
+fn seal_slot(key: &[u8; 32], content: &[u8]) -> Result<Vec<u8>> {
+    let mut in_out = content.to_vec();
+    fixed_nonce_key(key)?
+        .seal_in_place_append_tag(aead::Nonce::assume_unique_for_key([0; NONCE_LEN]), aead::Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Şifreleme hatası"))?;
+
+    Ok(in_out)
+}
+
+fn open_slot(key: &[u8; 32], slot: &[u8]) -> Result<Vec<u8>> {
+    let mut in_out = slot.to_vec();
+    let plaintext = fixed_nonce_key(key)?
+        .open_in_place(aead::Nonce::assume_unique_for_key([0; NONCE_LEN]), aead::Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Başlık dilimi çözülemedi"))?;
+
+    Ok(plaintext.to_vec())
+}
+
+fn fixed_nonce_key(key: &[u8; 32]) -> Result<aead::LessSafeKey> {
+    let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, key)
+        .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
+
+    Ok(aead::LessSafeKey::new(unbound_key))
+}
+
+// ChaCha20-Poly1305 ile şifrele
+// Her çağrıda verilen RNG'den nonce üretilir ve şifreli verinin başına eklenir,
+// aynı anahtar tekrar kullanılsa da (ör. tekrar gönderilen cevap bloğu) anahtar akışı tekrarlanmaz
+pub(crate) fn seal<R: RngCore + CryptoRng + ?Sized>(key: &[u8; 32], data: &[u8], rng: &mut R) -> Result<Vec<u8>> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce_bytes);
+    let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, key)
+        .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
+    let key = aead::LessSafeKey::new(unbound_key);
+
+    let mut in_out = data.to_vec();
+    key.seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce_bytes), aead::Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Şifreleme hatası"))?;
+
+    let mut result = Vec::with_capacity(nonce_bytes.len() + in_out.len());
+    result.extend_from_slice(&nonce_bytes);
+    result.extend_from_slice(&in_out);
+
+    Ok(result)
+}
+
+// seal ile şifrelenmiş veriyi çöz
+pub(crate) fn open(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!("Geçersiz şifrelenmiş veri"));
+    }
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    nonce_bytes.copy_from_slice(&data[..NONCE_LEN]);
+
+    let unbound_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, key)
+        .map_err(|_| anyhow!("Anahtar oluşturma hatası"))?;
+    let key = aead::LessSafeKey::new(unbound_key);
+
+    let mut in_out = data[NONCE_LEN..].to_vec();
+    let plaintext = key.open_in_place(aead::Nonce::assume_unique_for_key(nonce_bytes), aead::Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Şifre çözme hatası"))?;
+
+    Ok(plaintext.to_vec())
+}
+
+// Tek katmanlık geçici X25519 anahtarı
+fn ephemeral_secret<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> StaticSecret {
+    let mut secret = [0u8; 32];
+    rng.fill_bytes(&mut secret);
+
+    StaticSecret::from(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn route(relays: &[RelayKeyPair]) -> Vec<RouteHop> {
+        relays.iter()
+            .map(|relay| RouteHop::new(PeerId::random(), relay.public_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn forward_packet_peels_layer_by_layer() {
+        let relays: Vec<RelayKeyPair> = (0..3).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let route = route(&relays);
+        let mut packet = build_forward_packet(&route, b"merhaba", &mut thread_rng()).unwrap();
+
+        for (index, relay) in relays.iter().enumerate() {
+            match relay.peel(&packet).unwrap() {
+                PeeledPacket::Forward { next_hop, packet: inner, .. } => {
+                    assert_eq!(next_hop, route[index + 1].peer_id);
+                    packet = inner;
+                }
+                PeeledPacket::Deliver { reply_id, payload } => {
+                    assert_eq!(index, relays.len() - 1);
+                    assert!(reply_id.is_none());
+                    assert_eq!(payload, b"merhaba");
+                    return;
+                }
+            }
+        }
+        panic!("Paket son düğümde teslim edilmedi");
+    }
+
+    #[test]
+    fn packet_size_is_constant_along_route() {
+        let relays: Vec<RelayKeyPair> = (0..4).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let route = route(&relays);
+        let short = build_forward_packet(&route[2..], b"x", &mut thread_rng()).unwrap();
+        let mut packet = build_forward_packet(&route, &[1; PAYLOAD_CAPACITY], &mut thread_rng()).unwrap();
+
+        // Rota uzunluğu ve yük boyutu paket boyutuna yansımaz
+        assert_eq!(short.header.len(), HEADER_SIZE);
+        assert_eq!(short.payload.len(), PAYLOAD_SIZE);
+
+        for relay in &relays {
+            assert_eq!(packet.header.len(), HEADER_SIZE);
+            assert_eq!(packet.payload.len(), PAYLOAD_SIZE);
+
+            match relay.peel(&packet).unwrap() {
+                PeeledPacket::Forward { packet: inner, .. } => packet = inner,
+                PeeledPacket::Deliver { payload, .. } => {
+                    assert_eq!(payload, vec![1; PAYLOAD_CAPACITY]);
+                    return;
+                }
+            }
+        }
+        panic!("Paket son düğümde teslim edilmedi");
+    }
+
+    #[test]
+    fn oversized_payload_and_route_are_rejected() {
+        let relays: Vec<RelayKeyPair> = (0..MAX_ROUTE_HOPS + 1).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let route = route(&relays);
+
+        assert!(build_forward_packet(&route[..1], &[0; PAYLOAD_CAPACITY + 1], &mut thread_rng()).is_err());
+        assert!(build_forward_packet(&route[..MAX_ROUTE_HOPS], b"veri", &mut thread_rng()).is_ok());
+        assert!(build_forward_packet(&route, b"veri", &mut thread_rng()).is_err());
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected_at_destination() {
+        let relays: Vec<RelayKeyPair> = (0..2).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let packet = build_forward_packet(&route(&relays), b"veri", &mut thread_rng()).unwrap();
+
+        let mut inner = match relays[0].peel(&packet).unwrap() {
+            PeeledPacket::Forward { packet, .. } => packet,
+            PeeledPacket::Deliver { .. } => panic!("Paket erken teslim edildi"),
+        };
+        inner.payload[100] ^= 1;
+        assert!(relays[1].peel(&inner).is_err());
+    }
+
+    #[test]
+    fn peel_rejects_wrong_relay() {
+        let relays: Vec<RelayKeyPair> = (0..2).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let packet = build_forward_packet(&route(&relays), b"veri", &mut thread_rng()).unwrap();
+
+        assert!(relays[1].peel(&packet).is_err());
+    }
+
+    #[test]
+    fn seal_uses_fresh_nonce_per_call() {
+        let key = [7u8; 32];
+        let first = seal(&key, b"ayni veri", &mut thread_rng()).unwrap();
+        let second = seal(&key, b"ayni veri", &mut thread_rng()).unwrap();
+
+        assert_ne!(first[..NONCE_LEN], second[..NONCE_LEN]);
+        assert_ne!(first, second);
+        assert_eq!(open(&key, &first).unwrap(), b"ayni veri");
+        assert_eq!(open(&key, &second).unwrap(), b"ayni veri");
+    }
+
+    #[test]
+    fn open_rejects_tampered_ciphertext() {
+        let key = [1u8; 32];
+        let mut sealed = seal(&key, b"veri", &mut thread_rng()).unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+
+        assert!(open(&key, &sealed).is_err());
+        assert!(open(&key, &sealed[..NONCE_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn seeded_runs_produce_identical_packets() {
+        // Eş kimlikleri iki çalıştırmada ortaktır, geri kalan her şey tohumdan üretilir
+        let peers: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+        let build = |seed| {
+            let mut rng = crate::crypto::seeded_rng(seed);
+            let relays: Vec<RelayKeyPair> = (0..3).map(|_| RelayKeyPair::generate(&mut *rng)).collect();
+            let route: Vec<RouteHop> = peers.iter()
+                .zip(&relays)
+                .map(|(peer_id, relay)| RouteHop::new(*peer_id, relay.public_bytes()))
+                .collect();
+            let packet = build_forward_packet(&route, b"veri", &mut *rng).unwrap();
+            let sealed = seal_to(&relays[0].public_bytes(), b"gizli", &mut *rng).unwrap();
+            (relays.iter().map(RelayKeyPair::public_bytes).collect::<Vec<_>>(), packet.header, packet.payload, sealed)
+        };
+
+        assert_eq!(build(5), build(5));
+        assert_ne!(build(5), build(6));
+    }
+
+    #[test]
+    fn seal_to_round_trip() {
+        let relay = RelayKeyPair::generate(&mut thread_rng());
+        let sealed = seal_to(&relay.public_bytes(), b"gizli", &mut thread_rng()).unwrap();
+
+        assert_eq!(relay.open_sealed(&sealed).unwrap(), b"gizli");
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Bir düğümün ağdaki rolleri
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PeerRole {
+    // Yönlendirme anahtarını duyurmuş aracı düğüm
+    Relay,
+    // Koruma düğümü olarak seçilmiş
+    Guard,
+    // Önyükleme düğümü olarak verilmiş
+    Bootstrap,
+}
+
+// Bir düğüm hakkında saklanan bilgiler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerRecord {
+    // En son görülen adres başta
+    pub addresses: Vec<String>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    pub roles: BTreeSet<PeerRole>,
+    pub reputation: f64,
+    pub protocols: Vec<String>,
+}
+
+// Düğüm kimliğine göre tekilleştirilmiş, diskte saklanan düğüm kaydı
+// Yeniden başlatmada bilinen düğümlere tekrar bağlanmak için kullanılır
+pub struct PeerStore {
+    path: PathBuf,
+    peers: HashMap<PeerId, PeerRecord>,
+    max_peers: usize,
+    max_addresses: usize,
+    dirty: bool,
+}
+
+impl PeerStore {
+    // Kayıtlı düğümleri yükle, dosya yoksa boş başla
+    pub fn load(path: impl Into<PathBuf>, max_peers: usize, max_addresses: usize) -> Result<Self> {
+        let path = path.into();
+        let saved: BTreeMap<String, PeerRecord> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Düğüm kayıt dosyası okunamadı ({}): {}", path.display(), e))?,
+            Err(_) => BTreeMap::new(),
+        };
+
+        // Geçersiz kimlikli kayıtlar atlanır, bir sonraki kayıtta dosyadan da düşer
+        let peers = saved.into_iter()
+            .filter_map(|(peer_id, record)| Some((peer_id.parse().ok()?, record)))
+            .collect();
+
+        Ok(Self {
+            path,
+            peers,
+            max_peers,
+            max_addresses,
+            dirty: false,
+        })
+    }
+
+    // Değişiklik varsa düğümleri diske yaz
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let saved: BTreeMap<String, &PeerRecord> = self.peers.iter()
+            .map(|(peer_id, record)| (peer_id.to_string(), record))
+            .collect();
+        fs::write(&self.path, serde_json::to_string_pretty(&saved)?)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+
+    // Düğümü görüldü olarak işaretle, adres verilirse listenin başına alınır
+    // Düğüm ilk kez görüldüyse true döner
+    pub fn observe(&mut self, peer_id: PeerId, address: Option<&Multiaddr>) -> bool {
+        let now = unix_now();
+        let is_new = !self.peers.contains_key(&peer_id);
+        let record = self.peers.entry(peer_id).or_insert_with(|| PeerRecord {
+            addresses: Vec::new(),
+            first_seen: now,
+            last_seen: now,
+            roles: BTreeSet::new(),
+            reputation: 0.0,
+            protocols: Vec::new(),
+        });
+        record.last_seen = now;
+
+        if let Some(address) = address {
+            let address = address.to_string();
+            record.addresses.retain(|known| *known != address);
+            record.addresses.insert(0, address);
+            record.addresses.truncate(self.max_addresses);
+        }
+        self.dirty = true;
+
+        if is_new {
+            self.evict_oldest();
+        }
+
+        is_new
+    }
+
+    pub fn add_role(&mut self, peer_id: &PeerId, role: PeerRole) {
+        if let Some(record) = self.peers.get_mut(peer_id) {
+            self.dirty |= record.roles.insert(role);
+        }
+    }
+
+    // Rolü verilen düğümler dışındakilerden kaldır
+    pub fn retain_role(&mut self, role: PeerRole, peers: &[PeerId]) {
+        for (peer_id, record) in self.peers.iter_mut() {
+            if !peers.contains(peer_id) && record.roles.remove(&role) {
+                self.dirty = true;
+            }
+        }
+        for peer_id in peers {
+            self.add_role(peer_id, role);
+        }
+    }
+
+    pub fn set_protocols(&mut self, peer_id: &PeerId, protocols: Vec<String>) {
+        if let Some(record) = self.peers.get_mut(peer_id) {
+            if record.protocols != protocols {
+                record.protocols = protocols;
+                self.dirty = true;
+            }
+        }
+    }
+
+    pub fn set_reputation(&mut self, peer_id: &PeerId, score: f64) {
+        if let Some(record) = self.peers.get_mut(peer_id) {
+            if (record.reputation - score).abs() >= 0.01 {
+                record.reputation = score;
+                self.dirty = true;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    // Saklanan puanlar, açılışta puan kaydını tohumlamak için
+    pub fn reputations(&self) -> Vec<(PeerId, f64)> {
+        self.peers.iter()
+            .filter(|(_, record)| record.reputation != 0.0)
+            .map(|(peer_id, record)| (*peer_id, record.reputation))
+            .collect()
+    }
+
+    // Adresi bilinen düğümler, en son görülen başta
+    pub fn dial_candidates(&self) -> Vec<(PeerId, Vec<Multiaddr>)> {
+        let mut candidates: Vec<_> = self.peers.iter()
+            .map(|(peer_id, record)| {
+                let addresses: Vec<Multiaddr> = record.addresses.iter()
+                    .filter_map(|address| address.parse().ok())
+                    .collect();
+                (record.last_seen, *peer_id, addresses)
+            })
+            .filter(|(_, _, addresses)| !addresses.is_empty())
+            .collect();
+        candidates.sort_by_key(|(last_seen, _, _)| Reverse(*last_seen));
+
+        candidates.into_iter().map(|(_, peer_id, addresses)| (peer_id, addresses)).collect()
+    }
+
+    // Kapasite aşıldıysa en uzun süredir görülmeyen düğümü unut
+    fn evict_oldest(&mut self) {
+        while self.peers.len() > self.max_peers {
+            let oldest = self.peers.iter()
+                .min_by_key(|(_, record)| record.last_seen)
+                .map(|(peer_id, _)| *peer_id);
+            match oldest {
+                Some(peer_id) => self.peers.remove(&peer_id),
+                None => break,
+            };
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("kuantum-peers-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    fn address(port: u16) -> Multiaddr {
+        format!("/ip4/127.0.0.1/tcp/{}", port).parse().unwrap()
+    }
+
+    #[test]
+    fn peers_are_deduplicated_with_latest_address_first() {
+        let mut store = PeerStore::load(temp_path(), 10, 2).unwrap();
+        let peer_id = PeerId::random();
+
+        assert!(store.observe(peer_id, Some(&address(1))));
+        assert!(!store.observe(peer_id, Some(&address(2))));
+        assert!(!store.observe(peer_id, Some(&address(1))));
+        store.observe(peer_id, Some(&address(3)));
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.dial_candidates(), vec![(peer_id, vec![address(3), address(1)])]);
+    }
+
+    #[test]
+    fn peers_without_addresses_are_not_dial_candidates() {
+        let mut store = PeerStore::load(temp_path(), 10, 2).unwrap();
+        store.observe(PeerId::random(), None);
+
+        assert!(!store.is_empty());
+        assert!(store.dial_candidates().is_empty());
+    }
+
+    #[test]
+    fn oldest_peer_is_evicted_at_capacity() {
+        let mut store = PeerStore::load(temp_path(), 2, 2).unwrap();
+        let peers: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+
+        store.observe(peers[0], Some(&address(1)));
+        store.observe(peers[1], Some(&address(2)));
+        // Aynı saniyede görülen kayıtlar da ayırt edilsin diye ilk kaydı eskit
+        store.peers.get_mut(&peers[0]).unwrap().last_seen -= 10;
+        store.observe(peers[2], Some(&address(3)));
+
+        assert_eq!(store.len(), 2);
+        assert!(store.dial_candidates().iter().all(|(peer_id, _)| *peer_id != peers[0]));
+    }
+
+    #[test]
+    fn roles_are_moved_to_current_peers() {
+        let mut store = PeerStore::load(temp_path(), 10, 2).unwrap();
+        let peers: Vec<PeerId> = (0..2).map(|_| PeerId::random()).collect();
+        for peer_id in &peers {
+            store.observe(*peer_id, None);
+        }
+
+        store.retain_role(PeerRole::Guard, &peers[..1]);
+        store.retain_role(PeerRole::Guard, &peers[1..]);
+
+        assert!(!store.peers[&peers[0]].roles.contains(&PeerRole::Guard));
+        assert!(store.peers[&peers[1]].roles.contains(&PeerRole::Guard));
+    }
+
+    #[test]
+    fn records_survive_save_and_load() {
+        let path = temp_path();
+        let peer_id = PeerId::random();
+        let mut store = PeerStore::load(path.clone(), 10, 2).unwrap();
+        store.observe(peer_id, Some(&address(1)));
+        store.add_role(&peer_id, PeerRole::Relay);
+        store.set_reputation(&peer_id, -12.5);
+        store.set_protocols(&peer_id, vec!["/kuantum/direct/1.0.0".to_string()]);
+        store.save().unwrap();
+
+        let reloaded = PeerStore::load(path, 10, 2).unwrap();
+        assert_eq!(reloaded.reputations(), vec![(peer_id, -12.5)]);
+        assert_eq!(reloaded.dial_candidates(), vec![(peer_id, vec![address(1)])]);
+        let record = &reloaded.peers[&peer_id];
+        assert!(record.roles.contains(&PeerRole::Relay));
+        assert_eq!(record.protocols, vec!["/kuantum/direct/1.0.0".to_string()]);
+    }
+
+    #[test]
+    fn unchanged_store_is_not_written() {
+        let path = temp_path();
+        let mut store = PeerStore::load(path.clone(), 10, 2).unwrap();
+        store.save().unwrap();
+        assert!(!path.exists());
+
+        let peer_id = PeerId::random();
+        store.observe(peer_id, None);
+        store.save().unwrap();
+        assert!(path.exists());
+
+        // Küçük puan değişiklikleri kaydı kirletmez
+        store.set_reputation(&peer_id, 0.001);
+        assert!(!store.dirty);
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use libp2p::gossipsub::{
+    GossipsubConfig, GossipsubConfigBuilder, GossipsubMessage, IdentTopic, MessageId, PeerScoreParams,
+    PeerScoreThresholds, TopicScoreParams, ValidationMode,
+};
+use ring::digest;
+use std::fmt;
+use std::time::Duration;
+
+use super::reputation::PeerEvent;
+
+// Tüm düğümlerin abone olduğu konu
+pub const TOPIC: &str = "kuantum-network";
+// Devre hücreleri cevap bloklarıyla birlikte birkaç KB'a ulaşabilir, parçalamaya gerek kalmasın
+const MAX_TRANSMIT_SIZE: usize = 64 * 1024;
+// Yönlendirme anahtarı duyuru aralığından kısa tutulur ki periyodik duyurular tekrar sayılmasın
+const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(20);
+// Geçersiz paket başına ceza ve cezanın saniyelik sönümü (yarılanma süresi yaklaşık 70 sn)
+const INVALID_MESSAGE_WEIGHT: f64 = -10.0;
+const INVALID_MESSAGE_DECAY: f64 = 0.99;
+
+// Yayın katmanı
+// Floodsub eski düğümlerle uyumluluk için tutulur; iki mod birbirine mesaj iletemez
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PubsubMode {
+    Gossipsub,
+    Floodsub,
+}
+
+// Mesaj kimliği yayıncı ve içerikten türetilir
+// Aynı içerik farklı yollardan ya da tekrar yayınlandığında bir kez işlenir
+fn message_id(message: &GossipsubMessage) -> MessageId {
+    let mut input = message.source.map(|source| source.to_bytes()).unwrap_or_default();
+    input.extend_from_slice(&message.data);
+
+    MessageId::from(hex::encode(digest::digest(&digest::SHA256, &input)))
+}
+
+// Küçük ve seyrek trafikli ağ için mesh ayarları
+// Mesajlar imzalı olmalı, her mesaj uygulama tarafından doğrulandıktan sonra iletilir
+pub fn gossipsub_config() -> Result<GossipsubConfig> {
+    GossipsubConfigBuilder::default()
+        .mesh_n(4)
+        .mesh_n_low(2)
+        .mesh_n_high(8)
+        .mesh_outbound_min(1)
+        .gossip_lazy(4)
+        .heartbeat_interval(Duration::from_secs(1))
+        .max_transmit_size(MAX_TRANSMIT_SIZE)
+        .duplicate_cache_time(DUPLICATE_CACHE_TIME)
+        .validation_mode(ValidationMode::Strict)
+        .validate_messages()
+        .message_id_fn(message_id)
+        .build()
+        .map_err(|e| anyhow!("Gossipsub yapılandırma hatası: {}", e))
+}
+
+// Geçersiz paket gönderen eşleri cezalandıran puanlama parametreleri
+// Seyrek trafikte sessiz eşler cezalandırılmasın diye teslimat oranı cezaları kapalıdır.
+// Olumlu puanlar sınırlı tutulur ki birkaç geçersiz paket uzun süredir bağlı bir eşi de eşiğin altına indirsin.
+pub fn peer_score_params(topic: &IdentTopic) -> (PeerScoreParams, PeerScoreThresholds) {
+    let topic_params = TopicScoreParams {
+        topic_weight: 1.0,
+        // Mesh'te geçen her dakika için 0.6 puan, en fazla 36
+        time_in_mesh_weight: 0.01,
+        time_in_mesh_quantum: Duration::from_secs(1),
+        time_in_mesh_cap: 3600.0,
+        // İlk kez iletilen mesaj başına 0.5 puan, en fazla 50
+        first_message_deliveries_weight: 0.5,
+        first_message_deliveries_cap: 100.0,
+        mesh_message_deliveries_weight: 0.0,
+        mesh_failure_penalty_weight: 0.0,
+        invalid_message_deliveries_weight: INVALID_MESSAGE_WEIGHT,
+        invalid_message_deliveries_decay: INVALID_MESSAGE_DECAY,
+        ..TopicScoreParams::default()
+    };
+
+    let mut params = PeerScoreParams::default();
+    params.topics.insert(topic.hash(), topic_params);
+
+    (params, PeerScoreThresholds::default())
+}
+
+// Gönderen eşin cezalandırılmasını gerektiren paket hatası
+// Yalnızca eşin kendi hatası olan paketler (bozuk çerçeve, sahte sayaç, kendi oluşturduğu paketin
+// çözülemeyen dış katmanı) bu hatayla ve ihlal türüyle işaretlenir; iletilen paketlerin iç hataları düz hata döner
+#[derive(Debug)]
+pub struct InvalidPacket {
+    event: PeerEvent,
+    reason: String,
+}
+
+impl fmt::Display for InvalidPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for InvalidPacket {}
+
+pub fn invalid_packet(event: PeerEvent, reason: impl fmt::Display) -> anyhow::Error {
+    anyhow::Error::new(InvalidPacket { event, reason: reason.to_string() })
+}
+
+// Hata geçersiz paketten kaynaklandıysa ihlal türü
+pub fn invalid_packet_event(error: &anyhow::Error) -> Option<PeerEvent> {
+    error.downcast_ref::<InvalidPacket>().map(|invalid| invalid.event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_invalid_packet_errors_carry_a_penalty() {
+        let invalid = invalid_packet(PeerEvent::DecryptionFailed, "çözülemedi");
+        assert_eq!(invalid_packet_event(&invalid), Some(PeerEvent::DecryptionFailed));
+        assert_eq!(invalid.to_string(), "çözülemedi");
+
+        // Bağlam eklenmiş hatada da ihlal türü korunur
+        let wrapped = invalid.context("çerçeve");
+        assert_eq!(invalid_packet_event(&wrapped), Some(PeerEvent::DecryptionFailed));
+
+        assert_eq!(invalid_packet_event(&anyhow!("iç katman hatası")), None);
+    }
+}
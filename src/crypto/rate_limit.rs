@@ -0,0 +1,301 @@
+use libp2p::PeerId;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Kovaların dolu haldeyken karşılayabildiği ani yük, saniye cinsinden hız katı
+const BURST_SECONDS: f64 = 2.0;
+
+// Mesajların geldiği protokol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Pubsub,
+    Direct,
+}
+
+// Uygulanan sınırlar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Limit {
+    PubsubMessages,
+    PubsubBytes,
+    DirectMessages,
+    DirectBytes,
+    CircuitCreations,
+    // Tüm eşler için ortak şifre çözme denemesi bütçesi
+    CryptoOperations,
+    // Birleştirme ve karıştırma için bellekte tutulan paketlerin toplam boyutu
+    CryptoMemory,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Limit::PubsubMessages => "yayın mesajı/sn",
+            Limit::PubsubBytes => "yayın byte/sn",
+            Limit::DirectMessages => "doğrudan mesaj/sn",
+            Limit::DirectBytes => "doğrudan byte/sn",
+            Limit::CircuitCreations => "devre oluşturma/dk",
+            Limit::CryptoOperations => "şifre çözme/sn",
+            Limit::CryptoMemory => "şifreleme belleği",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Bir protokol için eş başına hız sınırı
+#[derive(Debug, Clone, Copy)]
+pub struct ProtocolLimits {
+    pub messages_per_sec: f64,
+    pub bytes_per_sec: f64,
+}
+
+// Tüm sınırların ayarları
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    pub pubsub: ProtocolLimits,
+    pub direct: ProtocolLimits,
+    pub circuits_per_min: f64,
+    pub crypto_ops_per_sec: f64,
+    pub crypto_memory_bytes: usize,
+}
+
+// Sınır aşıldığında dönen hata
+// `first` yalnızca sınır o eş için yeni devreye girdiyse true olur, günlük kaydı bir kez yazılsın diye
+#[derive(Debug)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub peer: Option<PeerId>,
+    pub first: bool,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.peer {
+            Some(peer) => write!(f, "Hız sınırı aşıldı ({}), peer: {}", self.limit, peer),
+            None => write!(f, "Kota aşıldı ({})", self.limit),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+// Token kovası, saniyede `rate` jeton dolar, en fazla `capacity` jeton birikir
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    updated_at: Instant,
+    // Son istek reddedildi mi
+    limited: bool,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        let capacity = rate * BURST_SECONDS;
+        Self {
+            capacity,
+            rate,
+            tokens: capacity,
+            updated_at: Instant::now(),
+            limited: false,
+        }
+    }
+
+    // Jeton al, yetmezse sınır bu istekle devreye girdiyse Err(true) döner
+    fn take(&mut self, amount: f64) -> Result<(), bool> {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated_at = now;
+
+        // Kovadan büyük istekler de dolu kovayla geçebilsin
+        let amount = amount.min(self.capacity);
+        if self.tokens < amount {
+            let first = !self.limited;
+            self.limited = true;
+            return Err(first);
+        }
+
+        self.tokens -= amount;
+        self.limited = false;
+
+        Ok(())
+    }
+}
+
+// Bir eşin kovaları
+struct PeerBuckets {
+    buckets: HashMap<Limit, TokenBucket>,
+    last_seen: Instant,
+}
+
+// Durum raporunda gösterilen sınır istatistikleri
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitReport {
+    // Sınır başına reddedilen istek sayısı
+    pub drops: BTreeMap<String, u64>,
+    // Şu anda sınırlanan eşler
+    pub limited_peers: Vec<String>,
+}
+
+// Eş başına ve protokol başına token kovalarıyla hız sınırlayıcı
+// Şifreleme işleri için tüm eşlerin paylaştığı işlem ve bellek kotaları da burada tutulur
+pub struct RateLimiter {
+    limits: RateLimits,
+    peers: HashMap<PeerId, PeerBuckets>,
+    crypto_ops: TokenBucket,
+    crypto_memory_limited: bool,
+    drops: BTreeMap<Limit, u64>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            peers: HashMap::new(),
+            crypto_ops: TokenBucket::new(limits.crypto_ops_per_sec),
+            crypto_memory_limited: false,
+            drops: BTreeMap::new(),
+        }
+    }
+
+    // Eşten gelen mesajı mesaj ve byte kovalarından düş
+    pub fn check_message(&mut self, peer_id: PeerId, protocol: Protocol, size: usize) -> Result<(), LimitExceeded> {
+        let (limits, message_limit, byte_limit) = match protocol {
+            Protocol::Pubsub => (self.limits.pubsub, Limit::PubsubMessages, Limit::PubsubBytes),
+            Protocol::Direct => (self.limits.direct, Limit::DirectMessages, Limit::DirectBytes),
+        };
+
+        self.take(peer_id, message_limit, limits.messages_per_sec, 1.0)?;
+        self.take(peer_id, byte_limit, limits.bytes_per_sec, size as f64)
+    }
+
+    // Eşin getirdiği yeni devreyi say
+    pub fn check_circuit(&mut self, peer_id: PeerId) -> Result<(), LimitExceeded> {
+        self.take(peer_id, Limit::CircuitCreations, self.limits.circuits_per_min / 60.0, 1.0)
+    }
+
+    // Şifre çözme denemesi için ortak bütçeden düş
+    pub fn check_crypto(&mut self) -> Result<(), LimitExceeded> {
+        self.crypto_ops.take(1.0).map_err(|first| self.exceeded(Limit::CryptoOperations, None, first))
+    }
+
+    // Bellekte tutulan paketlere `additional` byte daha eklenebilir mi
+    pub fn check_memory(&mut self, in_use: usize, additional: usize) -> Result<(), LimitExceeded> {
+        if in_use + additional <= self.limits.crypto_memory_bytes {
+            self.crypto_memory_limited = false;
+            return Ok(());
+        }
+
+        let first = !self.crypto_memory_limited;
+        self.crypto_memory_limited = true;
+        Err(self.exceeded(Limit::CryptoMemory, None, first))
+    }
+
+    fn take(&mut self, peer_id: PeerId, limit: Limit, rate: f64, amount: f64) -> Result<(), LimitExceeded> {
+        let peer = self.peers.entry(peer_id).or_insert_with(|| PeerBuckets {
+            buckets: HashMap::new(),
+            last_seen: Instant::now(),
+        });
+        peer.last_seen = Instant::now();
+
+        let result = peer.buckets.entry(limit)
+            .or_insert_with(|| TokenBucket::new(rate))
+            .take(amount);
+
+        result.map_err(|first| self.exceeded(limit, Some(peer_id), first))
+    }
+
+    fn exceeded(&mut self, limit: Limit, peer: Option<PeerId>, first: bool) -> LimitExceeded {
+        *self.drops.entry(limit).or_insert(0) += 1;
+
+        LimitExceeded { limit, peer, first }
+    }
+
+    // Uzun süredir mesaj göndermeyen eşlerin kovalarını unut
+    pub fn prune_idle(&mut self, idle: Duration) {
+        self.peers.retain(|_, peer| peer.last_seen.elapsed() <= idle);
+    }
+
+    pub fn report(&self) -> RateLimitReport {
+        RateLimitReport {
+            drops: self.drops.iter().map(|(limit, count)| (limit.to_string(), *count)).collect(),
+            limited_peers: self.peers.iter()
+                .filter(|(_, peer)| peer.buckets.values().any(|bucket| bucket.limited))
+                .map(|(peer_id, _)| peer_id.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> RateLimits {
+        RateLimits {
+            pubsub: ProtocolLimits { messages_per_sec: 1.0, bytes_per_sec: 1000.0 },
+            direct: ProtocolLimits { messages_per_sec: 10.0, bytes_per_sec: 100.0 },
+            circuits_per_min: 60.0,
+            crypto_ops_per_sec: 1.0,
+            crypto_memory_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn token_bucket_allows_burst_then_refills() {
+        let mut bucket = TokenBucket::new(10.0);
+        assert!(bucket.take(10.0 * BURST_SECONDS).is_ok());
+
+        // Sınır ilk reddedilen istekte devreye girer, sonrakiler tekrar bildirilmez
+        assert_eq!(bucket.take(1.0), Err(true));
+        assert_eq!(bucket.take(1.0), Err(false));
+
+        bucket.updated_at -= Duration::from_secs(1);
+        assert!(bucket.take(5.0).is_ok());
+        assert!(!bucket.limited);
+    }
+
+    #[test]
+    fn oversized_request_passes_with_full_bucket() {
+        let mut bucket = TokenBucket::new(1.0);
+        assert!(bucket.take(1000.0).is_ok());
+        assert!(bucket.take(1.0).is_err());
+    }
+
+    #[test]
+    fn message_limits_are_per_peer() {
+        let mut limiter = RateLimiter::new(limits());
+        let (noisy, quiet) = (PeerId::random(), PeerId::random());
+
+        for _ in 0..BURST_SECONDS as usize {
+            assert!(limiter.check_message(noisy, Protocol::Pubsub, 10).is_ok());
+        }
+        let exceeded = limiter.check_message(noisy, Protocol::Pubsub, 10).unwrap_err();
+        assert_eq!(exceeded.limit, Limit::PubsubMessages);
+        assert_eq!(exceeded.peer, Some(noisy));
+        assert!(exceeded.first);
+        assert!(!limiter.check_message(noisy, Protocol::Pubsub, 10).unwrap_err().first);
+
+        assert!(limiter.check_message(quiet, Protocol::Pubsub, 10).is_ok());
+        assert_eq!(limiter.report().limited_peers, vec![noisy.to_string()]);
+
+        // Byte kovası mesaj kovasından ayrı tükenir
+        assert!(limiter.check_message(quiet, Protocol::Direct, 150).is_ok());
+        assert_eq!(limiter.check_message(quiet, Protocol::Direct, 150).unwrap_err().limit, Limit::DirectBytes);
+    }
+
+    #[test]
+    fn shared_quotas_are_not_attributed_to_peers() {
+        let mut limiter = RateLimiter::new(limits());
+
+        assert!(limiter.check_memory(60, 40).is_ok());
+        let exceeded = limiter.check_memory(60, 41).unwrap_err();
+        assert_eq!(exceeded.peer, None);
+        assert!(exceeded.first);
+
+        while limiter.check_crypto().is_ok() {}
+        assert_eq!(limiter.check_crypto().unwrap_err().limit, Limit::CryptoOperations);
+    }
+}
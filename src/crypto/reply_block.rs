@@ -0,0 +1,261 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use rand::{CryptoRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::{Builder, Variant, Version};
+
+use super::onion_routing::{self, OnionPacket, RouteHop};
+
+// Tek kullanımlık cevap bloğu (SURB)
+// Gönderen, kendisine dönen şifreli yolu önceden hazırlar ve mesaja ekler.
+// Alıcı göndereni öğrenmeden bu bloğu kullanarak tek bir cevap gönderebilir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyBlock {
+    pub id: String,
+    first_hop: Vec<u8>,
+    header: Vec<u8>,
+    payload_key: [u8; 32],
+}
+
+impl ReplyBlock {
+    // Cevabı bloğa ekle ve ağa gönderilecek paketi oluştur
+    // Blok tüketilir, aynı blok ikinci kez kullanılamaz; cevap PAYLOAD_CAPACITY'yi aşamaz
+    pub fn attach_reply<R: RngCore + CryptoRng + ?Sized>(self, reply: &[u8], rng: &mut R) -> Result<(PeerId, OnionPacket)> {
+        let first_hop = PeerId::from_bytes(&self.first_hop)
+            .map_err(|_| anyhow!("Geçersiz ilk düğüm"))?;
+
+        // Paket ileri yönlü paketlerle aynı boyuttadır, aradaki düğümler cevap olduğunu ayırt edemez
+        let packet = OnionPacket {
+            header: self.header,
+            payload: onion_routing::seal(&self.payload_key, &onion_routing::pad(reply)?, rng)?,
+        };
+
+        Ok((first_hop, packet))
+    }
+}
+
+// Soğan rotası üzerinden taşınan mesaj, isteğe bağlı cevap bloğu ile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnionMessage {
+    pub body: Vec<u8>,
+    pub reply_block: Option<ReplyBlock>,
+}
+
+// Gönderen tarafta cevabı açmak için saklanan bilgiler
+struct PendingReply {
+    // Dönüş rotasındaki düğümlerin yük anahtar akışları
+    hop_keys: Vec<[u8; 32]>,
+    payload_key: [u8; 32],
+    created_at: Instant,
+}
+
+// Oluşturulan cevap bloklarını takip eder
+pub struct ReplyBlockStore {
+    pending: HashMap<String, PendingReply>,
+    lifetime: Duration,
+}
+
+impl ReplyBlockStore {
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            lifetime,
+        }
+    }
+
+    // Dönüş rotası için yeni bir cevap bloğu oluştur
+    // Rotanın son düğümü bu düğüm olmalıdır; kimlik ve anahtarlar verilen RNG'den üretilir
+    pub fn create<R: RngCore + CryptoRng + ?Sized>(&mut self, return_route: &[RouteHop], rng: &mut R) -> Result<ReplyBlock> {
+        let first_hop = return_route.first()
+            .ok_or_else(|| anyhow!("Dönüş rotası boş olamaz"))?
+            .peer_id;
+
+        let id = Builder::from_bytes(rng.gen())
+            .set_variant(Variant::RFC4122)
+            .set_version(Version::Random)
+            .build()
+            .to_string();
+        let (header, hop_keys) = onion_routing::build_header(return_route, Some(id.clone()), rng)?;
+        let payload_key: [u8; 32] = rng.gen();
+
+        // Cevap yolundaki her düğüm, bu düğüm de dahil, yüke kendi anahtar akışını uygular
+        let hop_keys = hop_keys.iter().map(|keys| keys.payload).collect();
+        self.pending.insert(id.clone(), PendingReply {
+            hop_keys,
+            payload_key,
+            created_at: Instant::now(),
+        });
+
+        Ok(ReplyBlock {
+            id,
+            first_hop: first_hop.to_bytes(),
+            header,
+            payload_key,
+        })
+    }
+
+    // Gelen cevabın tüm katmanlarını aç ve dolgusunu kaldır
+    // Blok yalnızca cevap doğrulandıktan sonra kayıttan silinir; bozuk ya da sahte bir paket
+    // gerçek cevabın teslimini engelleyemez, tekrar gönderilen cevaplar reddedilir
+    pub fn open_reply(&mut self, reply_id: &str, payload: &[u8]) -> Result<Vec<u8>> {
+        let pending = self.pending.get(reply_id)
+            .ok_or_else(|| anyhow!("Bilinmeyen ya da kullanılmış cevap bloğu: {}", reply_id))?;
+
+        if pending.created_at.elapsed() > self.lifetime {
+            self.pending.remove(reply_id);
+            return Err(anyhow!("Cevap bloğunun süresi dolmuş: {}", reply_id));
+        }
+
+        // Düğümlerin anahtar akışlarını geri al
+        let mut current = payload.to_vec();
+        for key in &pending.hop_keys {
+            onion_routing::apply_keystream(key, &mut current)?;
+        }
+
+        let reply = onion_routing::unpad(&onion_routing::open(&pending.payload_key, &current)?)?;
+        self.pending.remove(reply_id);
+
+        Ok(reply)
+    }
+
+    // Süresi dolmuş blokları temizle
+    pub fn prune_expired(&mut self) {
+        let lifetime = self.lifetime;
+        self.pending.retain(|_, pending| pending.created_at.elapsed() <= lifetime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::onion_routing::{PeeledPacket, RelayKeyPair};
+    use rand::thread_rng;
+
+    // Cevabı dönüş rotası boyunca ilet ve son düğümde teslim edilen yükü döndür
+    fn deliver(relays: &[RelayKeyPair], mut packet: OnionPacket) -> (Option<String>, Vec<u8>) {
+        for relay in relays {
+            match relay.peel(&packet).unwrap() {
+                PeeledPacket::Forward { packet: inner, .. } => packet = inner,
+                PeeledPacket::Deliver { reply_id, payload } => return (reply_id, payload),
+            }
+        }
+        panic!("Cevap teslim edilmedi");
+    }
+
+    fn setup() -> (Vec<RelayKeyPair>, Vec<RouteHop>) {
+        let relays: Vec<RelayKeyPair> = (0..3).map(|_| RelayKeyPair::generate(&mut thread_rng())).collect();
+        let route = relays.iter()
+            .map(|relay| RouteHop::new(PeerId::random(), relay.public_bytes()))
+            .collect();
+        (relays, route)
+    }
+
+    #[test]
+    fn reply_round_trip() {
+        let (relays, route) = setup();
+        let mut store = ReplyBlockStore::new(Duration::from_secs(600));
+        let block = store.create(&route, &mut thread_rng()).unwrap();
+        let id = block.id.clone();
+
+        let (first_hop, packet) = block.attach_reply(b"cevap", &mut thread_rng()).unwrap();
+        assert_eq!(first_hop, route[0].peer_id);
+
+        let (reply_id, payload) = deliver(&relays, packet);
+        assert_eq!(reply_id.as_deref(), Some(id.as_str()));
+        assert_eq!(store.open_reply(&id, &payload).unwrap(), b"cevap");
+    }
+
+    #[test]
+    fn reply_block_is_single_use() {
+        let (relays, route) = setup();
+        let mut store = ReplyBlockStore::new(Duration::from_secs(600));
+        let block = store.create(&route, &mut thread_rng()).unwrap();
+        let id = block.id.clone();
+
+        // Blok kopyalanıp iki kez kullanılsa da ikinci cevap açılmaz
+        let (_, first) = block.clone().attach_reply(b"ilk", &mut thread_rng()).unwrap();
+        let (_, second) = block.attach_reply(b"ikinci", &mut thread_rng()).unwrap();
+        assert_ne!(first.payload, second.payload);
+
+        let (_, payload) = deliver(&relays, first);
+        assert_eq!(store.open_reply(&id, &payload).unwrap(), b"ilk");
+
+        let (_, payload) = deliver(&relays, second);
+        assert!(store.open_reply(&id, &payload).is_err());
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_blocks_and_replies() {
+        let (_, route) = setup();
+        let mut first_rng = crate::crypto::seeded_rng(9);
+        let mut second_rng = crate::crypto::seeded_rng(9);
+
+        let mut store = ReplyBlockStore::new(Duration::from_secs(600));
+        let first = store.create(&route, &mut *first_rng).unwrap();
+        let second = store.create(&route, &mut *second_rng).unwrap();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.payload_key, second.payload_key);
+
+        // Cevap nonce'ları da tohumdan üretilir, aynı bloğa eklenen aynı cevap aynı paketi verir
+        let (_, first) = first.attach_reply(b"cevap", &mut *first_rng).unwrap();
+        let (_, second) = second.attach_reply(b"cevap", &mut *second_rng).unwrap();
+        assert_eq!(first.header, second.header);
+        assert_eq!(first.payload, second.payload);
+    }
+
+    #[test]
+    fn forged_reply_does_not_consume_block() {
+        let (relays, route) = setup();
+        let mut store = ReplyBlockStore::new(Duration::from_secs(600));
+        let block = store.create(&route, &mut thread_rng()).unwrap();
+        let id = block.id.clone();
+
+        let (_, packet) = block.attach_reply(b"cevap", &mut thread_rng()).unwrap();
+        let (_, payload) = deliver(&relays, packet);
+
+        let mut forged = payload.clone();
+        forged[0] ^= 1;
+        assert!(store.open_reply(&id, &forged).is_err());
+        assert_eq!(store.open_reply(&id, &payload).unwrap(), b"cevap");
+    }
+
+    #[test]
+    fn reply_packets_match_forward_packet_size() {
+        let (relays, route) = setup();
+        let mut store = ReplyBlockStore::new(Duration::from_secs(600));
+        let block = store.create(&route, &mut thread_rng()).unwrap();
+        let (_, mut reply) = block.attach_reply(b"kisa", &mut thread_rng()).unwrap();
+        let mut forward = onion_routing::build_forward_packet(&route, &[7; 3000], &mut thread_rng()).unwrap();
+
+        // Her düğümde iki yönün paketleri aynı boyuttadır
+        for relay in &relays[..relays.len() - 1] {
+            assert_eq!(reply.header.len(), forward.header.len());
+            assert_eq!(reply.payload.len(), forward.payload.len());
+
+            reply = match relay.peel(&reply).unwrap() {
+                PeeledPacket::Forward { packet, .. } => packet,
+                PeeledPacket::Deliver { .. } => panic!("Cevap erken teslim edildi"),
+            };
+            forward = match relay.peel(&forward).unwrap() {
+                PeeledPacket::Forward { packet, .. } => packet,
+                PeeledPacket::Deliver { .. } => panic!("Paket erken teslim edildi"),
+            };
+        }
+    }
+
+    #[test]
+    fn expired_reply_is_rejected() {
+        let (relays, route) = setup();
+        let mut store = ReplyBlockStore::new(Duration::ZERO);
+        let block = store.create(&route, &mut thread_rng()).unwrap();
+        let id = block.id.clone();
+
+        std::thread::sleep(Duration::from_millis(5));
+        let (_, packet) = block.attach_reply(b"gec", &mut thread_rng()).unwrap();
+        let (_, payload) = deliver(&relays, packet);
+        assert!(store.open_reply(&id, &payload).is_err());
+    }
+}
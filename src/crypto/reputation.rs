@@ -0,0 +1,214 @@
+use libp2p::PeerId;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Olumlu puanın üst sınırı, uzun süre iyi davranan bir eş de birkaç ihlalle yasaklanabilsin
+const MAX_SCORE: f64 = 50.0;
+
+// Bir eşin puanını etkileyen olaylar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerEvent {
+    // Eşe gönderilen paket işlendi ve onaylandı
+    Relayed,
+    // Eşten gelen soğan paketi çözülemedi
+    DecryptionFailed,
+    // Eşe gönderilen paket zamanında onaylanmadı
+    Timeout,
+    // Eş geçersiz ya da yanlış adreslenmiş paket gönderdi
+    ProtocolViolation,
+    // Eş tekrarlanan ya da döngüye giren paket gönderdi
+    Spam,
+}
+
+impl PeerEvent {
+    fn weight(self) -> f64 {
+        match self {
+            PeerEvent::Relayed => 1.0,
+            PeerEvent::Timeout => -5.0,
+            PeerEvent::Spam => -5.0,
+            PeerEvent::DecryptionFailed => -10.0,
+            PeerEvent::ProtocolViolation => -20.0,
+        }
+    }
+}
+
+// Bir eşin puan durumu
+struct PeerRecord {
+    score: f64,
+    // Puanın son güncellendiği an, sönüm buna göre hesaplanır
+    updated_at: Instant,
+    banned_until: Option<Instant>,
+}
+
+// Durum raporunda gösterilen eş puanı
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerReputation {
+    pub peer_id: String,
+    pub score: f64,
+    pub banned: bool,
+}
+
+// Eşlerin davranışlarını puanlayan kayıt
+// Puanlar yarılanma süresiyle sıfıra doğru söner, eşiğin altına düşen eşler belirli bir süre yasaklanır
+pub struct ReputationStore {
+    peers: HashMap<PeerId, PeerRecord>,
+    half_life: Duration,
+    ban_threshold: f64,
+    ban_duration: Duration,
+}
+
+impl ReputationStore {
+    pub fn new(half_life: Duration, ban_threshold: f64, ban_duration: Duration) -> Self {
+        Self {
+            peers: HashMap::new(),
+            half_life,
+            ban_threshold,
+            ban_duration,
+        }
+    }
+
+    // Olayı kaydet, eş bu olayla yasaklandıysa true döner
+    pub fn record(&mut self, peer_id: PeerId, event: PeerEvent) -> bool {
+        let now = Instant::now();
+        let half_life = self.half_life;
+        let record = self.peers.entry(peer_id).or_insert_with(|| PeerRecord {
+            score: 0.0,
+            updated_at: now,
+            banned_until: None,
+        });
+
+        record.score = (decayed(record, now, half_life) + event.weight()).min(MAX_SCORE);
+        record.updated_at = now;
+
+        if record.score >= self.ban_threshold || record.banned_until.is_some_and(|until| until > now) {
+            return false;
+        }
+        record.banned_until = Some(now + self.ban_duration);
+
+        true
+    }
+
+    // Diskten yüklenen puanı geri yükle, sönüm yükleme anından başlar
+    pub fn restore(&mut self, peer_id: PeerId, score: f64) {
+        self.peers.insert(peer_id, PeerRecord {
+            score: score.min(MAX_SCORE),
+            updated_at: Instant::now(),
+            banned_until: None,
+        });
+    }
+
+    // Sönüm uygulanmış güncel puan, hiç olay kaydedilmemiş eşler için 0
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.peers.get(peer_id)
+            .map(|record| decayed(record, Instant::now(), self.half_life))
+            .unwrap_or(0.0)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers.get(peer_id)
+            .and_then(|record| record.banned_until)
+            .is_some_and(|until| until > Instant::now())
+    }
+
+    // Yönlendiriciye verilecek puanlar, yasaklı eşler -∞ olarak bildirilir
+    pub fn route_scores(&self) -> HashMap<PeerId, f64> {
+        self.peers.keys()
+            .map(|peer_id| {
+                let score = if self.is_banned(peer_id) { f64::NEG_INFINITY } else { self.score(peer_id) };
+                (*peer_id, score)
+            })
+            .collect()
+    }
+
+    // Yasağı dolan eşleri döndür, puanları sıfırdan başlar
+    // Sıfıra yakın sönmüş kayıtlar da unutulur
+    pub fn expire(&mut self) -> Vec<PeerId> {
+        let now = Instant::now();
+        let half_life = self.half_life;
+        let mut unbanned = Vec::new();
+
+        self.peers.retain(|peer_id, record| {
+            if record.banned_until.is_some_and(|until| until <= now) {
+                unbanned.push(*peer_id);
+                return false;
+            }
+            record.banned_until.is_some() || decayed(record, now, half_life).abs() >= 0.01
+        });
+
+        unbanned
+    }
+
+    pub fn report(&self) -> Vec<PeerReputation> {
+        self.peers.keys()
+            .map(|peer_id| PeerReputation {
+                peer_id: peer_id.to_string(),
+                score: self.score(peer_id),
+                banned: self.is_banned(peer_id),
+            })
+            .collect()
+    }
+}
+
+fn decayed(record: &PeerRecord, now: Instant, half_life: Duration) -> f64 {
+    let elapsed = now.saturating_duration_since(record.updated_at).as_secs_f64();
+
+    record.score * 0.5f64.powf(elapsed / half_life.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF_LIFE: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn score_halves_after_half_life() {
+        let now = Instant::now();
+        let record = PeerRecord {
+            score: -40.0,
+            updated_at: now.checked_sub(HALF_LIFE).unwrap(),
+            banned_until: None,
+        };
+
+        assert!((decayed(&record, now, HALF_LIFE) + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn positive_score_is_capped() {
+        let mut store = ReputationStore::new(HALF_LIFE, -50.0, Duration::from_secs(3600));
+        let peer_id = PeerId::random();
+        for _ in 0..100 {
+            store.record(peer_id, PeerEvent::Relayed);
+        }
+
+        assert!(store.score(&peer_id) <= MAX_SCORE);
+    }
+
+    #[test]
+    fn peer_is_banned_once_below_threshold() {
+        let mut store = ReputationStore::new(HALF_LIFE, -50.0, Duration::from_secs(3600));
+        let peer_id = PeerId::random();
+
+        assert!(!store.record(peer_id, PeerEvent::ProtocolViolation));
+        assert!(!store.record(peer_id, PeerEvent::ProtocolViolation));
+        assert!(store.record(peer_id, PeerEvent::ProtocolViolation));
+        assert!(store.is_banned(&peer_id));
+        assert_eq!(store.route_scores()[&peer_id], f64::NEG_INFINITY);
+
+        // Yasaklı eş yeniden yasaklandı sayılmaz
+        assert!(!store.record(peer_id, PeerEvent::Spam));
+    }
+
+    #[test]
+    fn expired_ban_resets_the_peer() {
+        let mut store = ReputationStore::new(HALF_LIFE, -5.0, Duration::from_millis(1));
+        let peer_id = PeerId::random();
+        assert!(store.record(peer_id, PeerEvent::DecryptionFailed));
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(store.expire(), vec![peer_id]);
+        assert!(!store.is_banned(&peer_id));
+        assert_eq!(store.score(&peer_id), 0.0);
+    }
+}
@@ -0,0 +1,361 @@
+use libp2p::PeerId;
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Aktif rota kimliği
+pub type RouteId = u64;
+
+// Yoklama paketi kimliği
+pub type ProbeId = [u8; 16];
+
+// Bir rotanın yoklama istatistikleri
+#[derive(Debug, Clone, Default)]
+pub struct RouteStats {
+    pub probes_sent: u64,
+    pub probes_succeeded: u64,
+    pub probes_failed: u64,
+    pub consecutive_failures: u32,
+    pub last_latency: Option<Duration>,
+    // Üstel hareketli ortalama gecikme
+    pub average_latency: Option<Duration>,
+}
+
+impl RouteStats {
+    fn record_success(&mut self, latency: Duration) {
+        self.probes_succeeded += 1;
+        self.consecutive_failures = 0;
+        self.last_latency = Some(latency);
+        self.average_latency = Some(match self.average_latency {
+            Some(average) => average.mul_f64(0.8) + latency.mul_f64(0.2),
+            None => latency,
+        });
+    }
+
+    fn record_failure(&mut self) {
+        self.probes_failed += 1;
+        self.consecutive_failures += 1;
+    }
+
+    // Başarılı yoklama oranı
+    pub fn success_rate(&self) -> f64 {
+        let finished = self.probes_succeeded + self.probes_failed;
+        if finished == 0 {
+            return 1.0;
+        }
+
+        self.probes_succeeded as f64 / finished as f64
+    }
+}
+
+// Mesaj göndermek için kullanılan ve düzenli olarak yoklanan rota
+#[derive(Debug, Clone)]
+pub struct ActiveRoute {
+    pub id: RouteId,
+    // Hedeften önceki düğümler, ilki koruma düğümüdür
+    pub hops: Vec<PeerId>,
+    pub stats: RouteStats,
+    pub created_at: Instant,
+}
+
+// Durum raporunda gösterilen rota sağlığı
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteHealth {
+    pub id: RouteId,
+    pub hops: Vec<String>,
+    pub probes_sent: u64,
+    pub probes_succeeded: u64,
+    pub probes_failed: u64,
+    pub consecutive_failures: u32,
+    pub success_rate: f64,
+    pub last_latency_ms: Option<u128>,
+    pub average_latency_ms: Option<u128>,
+    pub age_secs: u64,
+}
+
+// Yoklamanın cevabı için beklenecek süre
+// Temel süreye her atlama için ağ payı ve gönderenin rotaya atadığı mixnet gecikmeleri eklenir;
+// gecikmeleri gönderen seçtiğinden mixnet modunda sağlıklı rotalar zaman aşımına düşmez
+pub fn probe_timeout(base: Duration, per_hop: Duration, hop_delays: &[Duration]) -> Duration {
+    hop_delays.iter().fold(base, |timeout, delay| timeout + per_hop + *delay)
+}
+
+// Bekleyen bir yoklama
+struct OutstandingProbe {
+    route_id: RouteId,
+    sent_at: Instant,
+    timeout: Duration,
+}
+
+// Aktif rotaları yoklayan ve başarısız olanları işaretleyen izleyici
+pub struct RouteHealthMonitor {
+    routes: HashMap<RouteId, ActiveRoute>,
+    outstanding: HashMap<ProbeId, OutstandingProbe>,
+    next_route_id: RouteId,
+    max_consecutive_failures: u32,
+}
+
+impl RouteHealthMonitor {
+    pub fn new(max_consecutive_failures: u32) -> Self {
+        Self {
+            routes: HashMap::new(),
+            outstanding: HashMap::new(),
+            next_route_id: 1,
+            max_consecutive_failures,
+        }
+    }
+
+    // Yeni bir aktif rota ekle
+    pub fn add_route(&mut self, hops: Vec<PeerId>) -> RouteId {
+        let id = self.next_route_id;
+        self.next_route_id += 1;
+
+        self.routes.insert(id, ActiveRoute {
+            id,
+            hops,
+            stats: RouteStats::default(),
+            created_at: Instant::now(),
+        });
+
+        id
+    }
+
+    pub fn route(&self, id: RouteId) -> Option<&ActiveRoute> {
+        self.routes.get(&id)
+    }
+
+    pub fn route_count(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn route_ids(&self) -> Vec<RouteId> {
+        self.routes.keys().cloned().collect()
+    }
+
+    // Hedefi içermeyen sağlıklı rotalardan birini seç
+    // Trafik tek bir rotada toplanmasın diye seçim başarı oranıyla ağırlıklı rastgele yapılır
+    pub fn choose_route<R: Rng + ?Sized>(&self, destination: &PeerId, rng: &mut R) -> Option<&ActiveRoute> {
+        let mut healthy: Vec<&ActiveRoute> = self.routes.values()
+            .filter(|route| route.stats.consecutive_failures == 0)
+            .filter(|route| !route.hops.contains(destination))
+            .collect();
+        // Aynı tohumla aynı rota seçilsin diye sıra sabitlenir
+        healthy.sort_by_key(|route| route.id);
+
+        healthy.choose_weighted(rng, |route| route.stats.success_rate().max(f64::EPSILON))
+            .ok()
+            .copied()
+    }
+
+    // Gönderilen yoklamayı kaydet
+    pub fn start_probe(&mut self, route_id: RouteId, probe_id: ProbeId, timeout: Duration) {
+        if let Some(route) = self.routes.get_mut(&route_id) {
+            route.stats.probes_sent += 1;
+            self.outstanding.insert(probe_id, OutstandingProbe { route_id, sent_at: Instant::now(), timeout });
+        }
+    }
+
+    // Geri dönen yoklamayı kaydet ve gecikmeyi döndür
+    pub fn record_probe_reply(&mut self, probe_id: &ProbeId) -> Option<(RouteId, Duration)> {
+        let probe = self.outstanding.remove(probe_id)?;
+        let latency = probe.sent_at.elapsed();
+
+        self.routes.get_mut(&probe.route_id)?.stats.record_success(latency);
+
+        Some((probe.route_id, latency))
+    }
+
+    // Zaman aşımına uğrayan yoklamaları başarısız say
+    pub fn expire_probes(&mut self) {
+        let expired: Vec<ProbeId> = self.outstanding.iter()
+            .filter(|(_, probe)| probe.sent_at.elapsed() > probe.timeout)
+            .map(|(probe_id, _)| *probe_id)
+            .collect();
+
+        for probe_id in expired {
+            if let Some(probe) = self.outstanding.remove(&probe_id) {
+                if let Some(route) = self.routes.get_mut(&probe.route_id) {
+                    route.stats.record_failure();
+                }
+            }
+        }
+    }
+
+    // Art arda çok fazla yoklaması başarısız olan rotaları çıkar
+    pub fn remove_failed_routes(&mut self) -> Vec<ActiveRoute> {
+        let max_failures = self.max_consecutive_failures;
+        let failed: Vec<RouteId> = self.routes.values()
+            .filter(|route| route.stats.consecutive_failures >= max_failures)
+            .map(|route| route.id)
+            .collect();
+
+        failed.into_iter()
+            .filter_map(|route_id| self.remove_route(route_id))
+            .collect()
+    }
+
+    // Artık kullanılmaması gereken rotaları çıkar (yasaklanan düğüm, değişen koruma düğümü)
+    pub fn remove_routes_where<F: Fn(&ActiveRoute) -> bool>(&mut self, unusable: F) -> Vec<ActiveRoute> {
+        let removed: Vec<RouteId> = self.routes.values()
+            .filter(|route| unusable(route))
+            .map(|route| route.id)
+            .collect();
+
+        removed.into_iter()
+            .filter_map(|route_id| self.remove_route(route_id))
+            .collect()
+    }
+
+    // Rotayı ve bekleyen yoklamalarını çıkar
+    pub fn remove_route(&mut self, route_id: RouteId) -> Option<ActiveRoute> {
+        self.outstanding.retain(|_, probe| probe.route_id != route_id);
+        self.routes.remove(&route_id)
+    }
+
+    // Durum raporu için rota sağlığı
+    pub fn report(&self) -> Vec<RouteHealth> {
+        let mut report: Vec<RouteHealth> = self.routes.values()
+            .map(|route| RouteHealth {
+                id: route.id,
+                hops: route.hops.iter().map(|peer_id| peer_id.to_string()).collect(),
+                probes_sent: route.stats.probes_sent,
+                probes_succeeded: route.stats.probes_succeeded,
+                probes_failed: route.stats.probes_failed,
+                consecutive_failures: route.stats.consecutive_failures,
+                success_rate: route.stats.success_rate(),
+                last_latency_ms: route.stats.last_latency.map(|latency| latency.as_millis()),
+                average_latency_ms: route.stats.average_latency.map(|latency| latency.as_millis()),
+                age_secs: route.created_at.elapsed().as_secs(),
+            })
+            .collect();
+
+        report.sort_by_key(|route| route.id);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::seeded_rng;
+
+    const LONG: Duration = Duration::from_secs(3600);
+
+    fn monitor_with_routes(count: usize) -> (RouteHealthMonitor, Vec<RouteId>) {
+        let mut monitor = RouteHealthMonitor::new(3);
+        let ids = (0..count)
+            .map(|_| monitor.add_route(vec![PeerId::random(), PeerId::random()]))
+            .collect();
+
+        (monitor, ids)
+    }
+
+    fn fail_probe(monitor: &mut RouteHealthMonitor, route_id: RouteId, probe_id: ProbeId) {
+        monitor.start_probe(route_id, probe_id, Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(2));
+        monitor.expire_probes();
+    }
+
+    #[test]
+    fn stats_track_success_rate_and_latency() {
+        let mut stats = RouteStats::default();
+        assert_eq!(stats.success_rate(), 1.0);
+
+        stats.record_success(Duration::from_millis(100));
+        stats.record_success(Duration::from_millis(200));
+        stats.record_failure();
+
+        assert!((stats.success_rate() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.consecutive_failures, 1);
+        assert_eq!(stats.last_latency, Some(Duration::from_millis(200)));
+        assert_eq!(stats.average_latency, Some(Duration::from_millis(120)));
+
+        stats.record_success(Duration::from_millis(120));
+        assert_eq!(stats.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn probes_expire_after_their_own_timeout() {
+        let (mut monitor, ids) = monitor_with_routes(1);
+
+        monitor.start_probe(ids[0], [1; 16], LONG);
+        fail_probe(&mut monitor, ids[0], [2; 16]);
+
+        let stats = &monitor.route(ids[0]).unwrap().stats;
+        assert_eq!(stats.probes_sent, 2);
+        assert_eq!(stats.probes_failed, 1);
+
+        // Uzun süreli yoklama hâlâ bekliyor, cevabı başarı sayılır
+        assert_eq!(monitor.record_probe_reply(&[1; 16]).map(|(id, _)| id), Some(ids[0]));
+        assert!(monitor.record_probe_reply(&[2; 16]).is_none());
+        assert_eq!(monitor.route(ids[0]).unwrap().stats.probes_succeeded, 1);
+    }
+
+    #[test]
+    fn probe_timeout_grows_with_hops_and_mix_delays() {
+        let base = Duration::from_secs(10);
+        let per_hop = Duration::from_secs(2);
+
+        assert_eq!(probe_timeout(base, per_hop, &[]), base);
+        assert_eq!(probe_timeout(base, per_hop, &[Duration::ZERO; 4]), Duration::from_secs(18));
+        assert_eq!(
+            probe_timeout(base, per_hop, &[Duration::from_secs(60), Duration::from_secs(30), Duration::ZERO]),
+            Duration::from_secs(106)
+        );
+    }
+
+    #[test]
+    fn failing_route_is_skipped_and_then_removed() {
+        let (mut monitor, ids) = monitor_with_routes(2);
+        let mut rng = seeded_rng(1);
+        let destination = PeerId::random();
+
+        fail_probe(&mut monitor, ids[0], [1; 16]);
+        for _ in 0..20 {
+            assert_eq!(monitor.choose_route(&destination, &mut *rng).unwrap().id, ids[1]);
+        }
+        assert!(monitor.remove_failed_routes().is_empty());
+
+        fail_probe(&mut monitor, ids[0], [2; 16]);
+        fail_probe(&mut monitor, ids[0], [3; 16]);
+        let removed = monitor.remove_failed_routes();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, ids[0]);
+        assert_eq!(monitor.route_ids(), vec![ids[1]]);
+    }
+
+    #[test]
+    fn route_through_destination_is_not_chosen() {
+        let (monitor, ids) = monitor_with_routes(1);
+        let destination = monitor.route(ids[0]).unwrap().hops[1];
+
+        assert!(monitor.choose_route(&destination, &mut *seeded_rng(2)).is_none());
+    }
+
+    #[test]
+    fn traffic_is_spread_across_healthy_routes() {
+        let (monitor, ids) = monitor_with_routes(3);
+        let mut rng = seeded_rng(3);
+        let destination = PeerId::random();
+
+        let mut counts: HashMap<RouteId, usize> = HashMap::new();
+        for _ in 0..300 {
+            *counts.entry(monitor.choose_route(&destination, &mut *rng).unwrap().id).or_default() += 1;
+        }
+
+        assert!(ids.iter().all(|id| counts.get(id).copied().unwrap_or(0) > 50));
+    }
+
+    #[test]
+    fn unusable_routes_are_removed_with_their_probes() {
+        let (mut monitor, ids) = monitor_with_routes(2);
+        let banned = monitor.route(ids[0]).unwrap().hops[0];
+        monitor.start_probe(ids[0], [1; 16], LONG);
+
+        let removed = monitor.remove_routes_where(|route| route.hops.contains(&banned));
+        assert_eq!(removed.len(), 1);
+        assert_eq!(monitor.route_ids(), vec![ids[1]]);
+        assert!(monitor.record_probe_reply(&[1; 16]).is_none());
+    }
+}
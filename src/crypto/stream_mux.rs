@@ -0,0 +1,761 @@
+use anyhow::{anyhow, Result};
+use futures::channel::{mpsc, oneshot};
+use futures::{SinkExt, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::circuit::CircuitId;
+use super::flow_control::{Priority, SendWindow};
+
+// Devre içindeki mantıksal akış kimliği
+pub type StreamId = u32;
+
+// Bir yazma çağrısında akışa alınan en fazla veri
+pub const STREAM_CHUNK_SIZE: usize = 4096;
+// Uygulamalardan gelen komutlar için kanal kapasitesi
+const COMMAND_BUFFER: usize = 64;
+// Akış başına SENDME gelmeden gönderilebilecek en fazla veri çerçevesi
+// Devre penceresi tüm akışları birlikte sınırlar, akış penceresi tek bir akışın devreyi doldurmasını önler
+pub const STREAM_WINDOW: u32 = 50;
+// Uygulama her bu kadar veri çerçevesini okuduğunda bir akış SENDME'si gönderilir
+pub const STREAM_SENDME_INCREMENT: u32 = 10;
+
+// Devre hücrelerinin taşıdığı çerçeveler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StreamFrame {
+    // Akışa bağlı olmayan devre mesajı
+    Message { priority: Priority, body: Vec<u8> },
+    // Hedef verilirse çıkış düğümü akışı bu TCP adresine bağlar
+    Open { stream_id: StreamId, target: Option<String> },
+    Data { stream_id: StreamId, data: Vec<u8> },
+    Close { stream_id: StreamId },
+    // Alıcı akışın gönderim penceresini STREAM_SENDME_INCREMENT çerçeve açar
+    Sendme { stream_id: StreamId },
+    // Veri taşımaz, hedefe yalnızca yeni cevap blokları ulaştırır
+    Refill,
+    // Çıkış düğümünün hedefe bağlanma sonucu, hedefli akışlarda veriden önce gönderilir
+    Connected { stream_id: StreamId, result: ConnectResult },
+}
+
+// Çıkış düğümünün akışın hedefine bağlanma sonucu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectResult {
+    Succeeded,
+    // Çıkış politikası ya da kapalı çıkış düğümü hedefi reddetti
+    NotAllowed,
+    // Hedef çözümlenemedi ya da hedefe ulaşılamadı
+    HostUnreachable,
+    ConnectionRefused,
+    Failed,
+}
+
+// Düğümdeki bir akışın anahtarı
+// Aynı devrenin iki ucu aynı düğümde olabileceğinden akışı açan taraf da anahtara dahildir
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamKey {
+    pub circuit_id: CircuitId,
+    pub stream_id: StreamId,
+    // Akışı bu düğüm açtıysa true
+    pub outbound: bool,
+}
+
+impl StreamKey {
+    pub fn handle(&self) -> String {
+        format!("{}:{}", hex::encode(&self.circuit_id[..4]), self.stream_id)
+    }
+}
+
+// Akış tutamaçlarından KuantumBehaviour'a giden komutlar
+#[derive(Debug)]
+pub enum StreamCommand {
+    Data { key: StreamKey, data: Vec<u8> },
+    Close { key: StreamKey },
+    // Karşı tarafa akış SENDME'si gönder
+    Sendme { key: StreamKey },
+    // Akışı açan tarafa hedefe bağlanma sonucunu bildir
+    Connected { key: StreamKey, result: ConnectResult },
+}
+
+// Akışın gönderim kredisi, tutamaç ile yönetici arasında paylaşılır
+// Kredi bittiğinde yazan taraf karşıdan SENDME gelene kadar bekler
+struct StreamCredit {
+    window: SendWindow,
+    // Kredi bekleyen yazma çağrısı
+    waker: Option<Waker>,
+    // Akış düğüm tarafında kapandı, bekleyen yazma hata ile döner
+    closed: bool,
+    // Akış veri kaybı nedeniyle kesildi, okuma akış sonu yerine hata döndürür
+    reset: bool,
+}
+
+impl StreamCredit {
+    fn new() -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self {
+            window: SendWindow::new(STREAM_WINDOW, STREAM_SENDME_INCREMENT),
+            waker: None,
+            closed: false,
+            reset: false,
+        }))
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// Yöneticinin bir akış için tuttuğu durum
+struct StreamEntry {
+    // Kapasite akış penceresi kadardır, pencereye uyan gönderen kanalı taşıramaz
+    sender: mpsc::Sender<Vec<u8>>,
+    credit: Arc<Mutex<StreamCredit>>,
+    // Bu düğümün açtığı hedefli akışta çıkış düğümünün bağlanma sonucunu bekleyen tutamaç
+    connect: Option<oneshot::Sender<ConnectResult>>,
+}
+
+impl StreamEntry {
+    // Bekleyen yazma çağrısını uyandır ve akışın kapandığını bildir
+    fn shutdown(&self) {
+        let mut credit = self.credit.lock().unwrap();
+        credit.closed = true;
+        credit.wake();
+    }
+
+    // Akışı kes, tutamaç okuma ve yazmada hata alır
+    fn reset(&self) {
+        self.credit.lock().unwrap().reset = true;
+        self.shutdown();
+    }
+}
+
+// Devre üzerindeki bir akış için uygulama tutamacı
+pub struct KuantumStream {
+    key: StreamKey,
+    target: Option<String>,
+    // Çıkış düğümünden bağlanma sonucu gelene kadar dolu
+    connect: Option<oneshot::Receiver<ConnectResult>>,
+    connect_result: ConnectResult,
+    incoming: mpsc::Receiver<Vec<u8>>,
+    read_buffer: Vec<u8>,
+    read_offset: usize,
+    commands: mpsc::Sender<StreamCommand>,
+    credit: Arc<Mutex<StreamCredit>>,
+    // Son akış SENDME'sinden sonra uygulamanın okuduğu veri çerçeveleri
+    consumed: u32,
+    // Komut kanalı dolu olduğu için henüz gönderilemeyen akış SENDME'leri
+    pending_sendmes: u32,
+    closed: bool,
+}
+
+impl KuantumStream {
+    pub fn key(&self) -> StreamKey {
+        self.key
+    }
+
+    pub fn handle(&self) -> String {
+        self.key.handle()
+    }
+
+    // Akışın bağlanmak istediği TCP hedefi ("host:port")
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    // Çıkış düğümünün hedefe bağlanma sonucunu bekle
+    // Hedefsiz akışlarda beklenecek bir sonuç yoktur; sonuç gelmeden kapanan akış başarısız sayılır
+    pub async fn connected(&mut self) -> ConnectResult {
+        if let Some(receiver) = self.connect.take() {
+            self.connect_result = receiver.await.unwrap_or(ConnectResult::Failed);
+        }
+
+        self.connect_result
+    }
+
+    // Çıkış düğümünde: hedefe bağlanma sonucunu akışı açan tarafa bildir
+    pub async fn report_connect(&mut self, result: ConnectResult) -> io::Result<()> {
+        let key = self.key;
+        self.commands.send(StreamCommand::Connected { key, result }).await
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Düğüm kapandı"))
+    }
+
+    // Okunan çerçeveler için biriken akış SENDME'lerini gönder
+    // Kanal doluysa bekleme kaydedilir, yer açılınca okuma yeniden denenir
+    fn flush_sendmes(&mut self, cx: &mut Context<'_>) {
+        while self.pending_sendmes > 0 {
+            match self.commands.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {
+                    let key = self.key;
+                    if self.commands.start_send(StreamCommand::Sendme { key }).is_err() {
+                        self.pending_sendmes = 0;
+                        return;
+                    }
+                    self.pending_sendmes -= 1;
+                }
+                // Düğüm kapandı, kredi verilecek kimse yok
+                Poll::Ready(Err(_)) => self.pending_sendmes = 0,
+                Poll::Pending => return,
+            }
+        }
+    }
+}
+
+impl AsyncRead for KuantumStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        self.flush_sendmes(cx);
+
+        if self.read_offset >= self.read_buffer.len() {
+            match Pin::new(&mut self.incoming).poll_next(cx) {
+                Poll::Ready(Some(data)) => {
+                    self.read_buffer = data;
+                    self.read_offset = 0;
+
+                    // Kredi yalnızca uygulama veriyi kanaldan aldıkça geri verilir
+                    self.consumed += 1;
+                    if self.consumed >= STREAM_SENDME_INCREMENT {
+                        self.consumed -= STREAM_SENDME_INCREMENT;
+                        self.pending_sendmes += 1;
+                        self.flush_sendmes(cx);
+                    }
+                }
+                // Akış kesildiyse eksik veri akış sonu gibi görünmemeli
+                Poll::Ready(None) if self.credit.lock().unwrap().reset => {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "Akış veri kaybı nedeniyle kesildi")));
+                }
+                // Karşı taraf akışı kapattı
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let available = &self.read_buffer[self.read_offset..];
+        let count = available.len().min(buf.remaining());
+        buf.put_slice(&available[..count]);
+        self.read_offset += count;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for KuantumStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if self.closed {
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::BrokenPipe, "Akış kapatıldı")));
+        }
+
+        // Akış penceresi dolduysa karşı taraftan SENDME gelene kadar bekle
+        {
+            let mut credit = self.credit.lock().unwrap();
+            if credit.closed {
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::BrokenPipe, "Akış kapatıldı")));
+            }
+            if !credit.window.can_send() {
+                credit.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        // Komut kanalı doluysa yazan taraf bekler
+        match self.commands.poll_ready(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(_)) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::BrokenPipe, "Düğüm kapandı"))),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        let count = buf.len().min(STREAM_CHUNK_SIZE);
+        let command = StreamCommand::Data { key: self.key, data: buf[..count].to_vec() };
+        self.commands.start_send(command)
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Düğüm kapandı"))?;
+        self.credit.lock().unwrap().window.on_send();
+
+        Poll::Ready(Ok(count))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.closed {
+            return Poll::Ready(Ok(()));
+        }
+
+        match self.commands.poll_ready(cx) {
+            Poll::Ready(Ok(())) => {
+                let key = self.key;
+                // Düğüm kapandıysa kapatılacak bir şey kalmamıştır
+                let _ = self.commands.start_send(StreamCommand::Close { key });
+                self.closed = true;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(_)) => {
+                self.closed = true;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for KuantumStream {
+    fn drop(&mut self) {
+        // Kanal doluysa kapanış kaybolmaz, yönetici bırakılan tutamaçları abandoned ile bulur
+        if !self.closed {
+            let _ = self.commands.try_send(StreamCommand::Close { key: self.key });
+        }
+    }
+}
+
+// Akışları kaydeden ve gelen verileri tutamaçlara dağıtan yönetici
+pub struct StreamManager {
+    streams: HashMap<StreamKey, StreamEntry>,
+    commands: mpsc::Sender<StreamCommand>,
+    accepted: mpsc::UnboundedSender<KuantumStream>,
+    next_stream_id: StreamId,
+}
+
+impl StreamManager {
+    // Yöneticiyle birlikte komut kanalının ve kabul edilen akışların alıcılarını döndür
+    pub fn new() -> (Self, mpsc::Receiver<StreamCommand>, mpsc::UnboundedReceiver<KuantumStream>) {
+        let (commands, command_receiver) = mpsc::channel(COMMAND_BUFFER);
+        let (accepted, accepted_receiver) = mpsc::unbounded();
+
+        let manager = Self {
+            streams: HashMap::new(),
+            commands,
+            accepted,
+            next_stream_id: 1,
+        };
+
+        (manager, command_receiver, accepted_receiver)
+    }
+
+    pub fn stream_count(&self) -> usize {
+        self.streams.len()
+    }
+
+    pub fn is_open(&self, key: &StreamKey) -> bool {
+        self.streams.contains_key(key)
+    }
+
+    // Devre üzerinde bu düğümden yeni bir akış aç
+    // Sayaç taştığında hâlâ açık olan akışların numaraları atlanır
+    pub fn open(&mut self, circuit_id: CircuitId, target: Option<String>) -> KuantumStream {
+        let key = loop {
+            let stream_id = self.next_stream_id;
+            self.next_stream_id = self.next_stream_id.checked_add(1).unwrap_or(1);
+
+            let key = StreamKey { circuit_id, stream_id, outbound: true };
+            if !self.streams.contains_key(&key) {
+                break key;
+            }
+        };
+
+        self.register(key, target)
+    }
+
+    // Karşı tarafın açtığı akışı kabul et ve uygulamaya ilet
+    pub fn accept(&mut self, circuit_id: CircuitId, stream_id: StreamId, target: Option<String>) -> Result<()> {
+        let key = StreamKey { circuit_id, stream_id, outbound: false };
+        if self.streams.contains_key(&key) {
+            return Err(anyhow!("Akış zaten açık: {}", key.handle()));
+        }
+
+        let stream = self.register(key, target);
+        self.accepted.unbounded_send(stream)
+            .map_err(|_| anyhow!("Akışları kabul eden uygulama yok"))
+    }
+
+    // Gelen veriyi akışın tutamacına ilet
+    pub fn deliver(&mut self, key: &StreamKey, data: Vec<u8>) -> Result<()> {
+        let entry = self.streams.get_mut(key)
+            .ok_or_else(|| anyhow!("Bilinmeyen akış: {}", key.handle()))?;
+
+        match entry.sender.try_send(data) {
+            Ok(()) => Ok(()),
+            // Tutamaç bırakılmış, akış kapanıyor
+            Err(e) if e.is_disconnected() => {
+                self.close(key);
+                Ok(())
+            }
+            // Kanal ancak gönderen SENDME beklemeden pencereyi aşarsa dolar
+            Err(_) => {
+                self.close(key);
+                Err(anyhow!("Gönderen akış penceresini aştı: {}", key.handle()))
+            }
+        }
+    }
+
+    // Karşı taraftan gelen akış SENDME'sini işle ve bekleyen yazmayı uyandır
+    pub fn on_sendme(&mut self, key: &StreamKey) -> Result<()> {
+        let entry = self.streams.get(key)
+            .ok_or_else(|| anyhow!("Bilinmeyen akış için SENDME: {}", key.handle()))?;
+
+        let mut credit = entry.credit.lock().unwrap();
+        if credit.window.available() + STREAM_SENDME_INCREMENT > STREAM_WINDOW {
+            return Err(anyhow!("Beklenmeyen akış SENDME'si: {}", key.handle()));
+        }
+        credit.window.on_sendme();
+        credit.wake();
+
+        Ok(())
+    }
+
+    // Çıkış düğümünden gelen bağlanma sonucunu akışın tutamacına ilet
+    // Sonuç yalnızca bu düğümün açtığı hedefli akışlara ve bir kez gelebilir
+    pub fn on_connected(&mut self, key: &StreamKey, result: ConnectResult) -> Result<()> {
+        let entry = self.streams.get_mut(key)
+            .ok_or_else(|| anyhow!("Bilinmeyen akış için bağlanma sonucu: {}", key.handle()))?;
+        let connect = entry.connect.take()
+            .ok_or_else(|| anyhow!("Beklenmeyen bağlanma sonucu: {}", key.handle()))?;
+
+        // Tutamaç bırakıldıysa sonucu bekleyen yok
+        let _ = connect.send(result);
+
+        Ok(())
+    }
+
+    // Akışı kapat, tutamaç okumada akış sonunu görür
+    pub fn close(&mut self, key: &StreamKey) -> bool {
+        match self.streams.remove(key) {
+            Some(entry) => {
+                entry.shutdown();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Tutamacı bırakılmış ama henüz kapatılmamış akışlar
+    // Bırakılırken komut kanalı dolu olan tutamaçların kapanışı buradan tamamlanır
+    pub fn abandoned(&self) -> Vec<StreamKey> {
+        self.streams.iter()
+            .filter(|(_, entry)| entry.sender.is_closed())
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    // Devrenin bu uçtaki tüm akışlarını kapat
+    pub fn close_circuit(&mut self, circuit_id: &CircuitId, outbound: bool) {
+        self.streams.retain(|key, entry| {
+            let keep = key.circuit_id != *circuit_id || key.outbound != outbound;
+            if !keep {
+                entry.shutdown();
+            }
+            keep
+        });
+    }
+
+    // Devrenin bu uçtaki tüm akışlarını kes
+    // Eksik hücre artık gelmeyecekse sonraki veriler akışa eklenemez, uygulama hata görür
+    pub fn reset_circuit(&mut self, circuit_id: &CircuitId, outbound: bool) {
+        self.streams.retain(|key, entry| {
+            let keep = key.circuit_id != *circuit_id || key.outbound != outbound;
+            if !keep {
+                entry.reset();
+            }
+            keep
+        });
+    }
+
+    fn register(&mut self, key: StreamKey, target: Option<String>) -> KuantumStream {
+        let (sender, incoming) = mpsc::channel(STREAM_WINDOW as usize);
+        let credit = StreamCredit::new();
+        let (connect, connect_receiver) = match target {
+            Some(_) if key.outbound => {
+                let (sender, receiver) = oneshot::channel();
+                (Some(sender), Some(receiver))
+            }
+            _ => (None, None),
+        };
+        self.streams.insert(key, StreamEntry { sender, credit: credit.clone(), connect });
+
+        KuantumStream {
+            key,
+            target,
+            connect: connect_receiver,
+            connect_result: ConnectResult::Succeeded,
+            incoming,
+            read_buffer: Vec::new(),
+            read_offset: 0,
+            commands: self.commands.clone(),
+            credit,
+            consumed: 0,
+            pending_sendmes: 0,
+            closed: false,
+        }
+    }
+}
+
+// Farklı gecikmelerle gelen hücreleri sıra numarasına göre dizen tampon
+pub struct ReorderBuffer<T> {
+    next_seq: u64,
+    pending: BTreeMap<u64, T>,
+    // İlk eksik hücrenin beklenmeye başlandığı zaman
+    gap_since: Option<Instant>,
+}
+
+impl<T> ReorderBuffer<T> {
+    pub fn new() -> Self {
+        Self {
+            next_seq: 0,
+            pending: BTreeMap::new(),
+            gap_since: None,
+        }
+    }
+
+    // Hücreyi ekle, sırası gelen hücreleri döndür
+    pub fn push(&mut self, seq: u64, item: T) -> Vec<T> {
+        if seq >= self.next_seq {
+            self.pending.insert(seq, item);
+        }
+
+        self.drain_ready()
+    }
+
+    // Eksik hücre çok uzun süredir gelmediyse true döner
+    // Sonraki hücreler boşluk atlanarak teslim edilemez, devrenin akışları kesilmelidir
+    pub fn has_stale_gap(&self, timeout: Duration) -> bool {
+        matches!(self.gap_since, Some(since) if since.elapsed() > timeout)
+    }
+
+    fn drain_ready(&mut self) -> Vec<T> {
+        let mut ready = Vec::new();
+        while let Some(item) = self.pending.remove(&self.next_seq) {
+            ready.push(item);
+            self.next_seq += 1;
+        }
+
+        self.gap_since = match (self.pending.is_empty(), self.gap_since) {
+            (true, _) => None,
+            (false, Some(since)) if ready.is_empty() => Some(since),
+            (false, _) => Some(Instant::now()),
+        };
+
+        ready
+    }
+}
+
+impl<T> Default for ReorderBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::timeout;
+
+    const CIRCUIT: CircuitId = [7; 16];
+    const WAIT: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn writer_waits_for_stream_sendme_after_window() {
+        let (mut manager, mut commands, _accepted) = StreamManager::new();
+        let mut stream = manager.open(CIRCUIT, None);
+        let key = stream.key();
+
+        for _ in 0..STREAM_WINDOW {
+            stream.write_all(b"veri").await.unwrap();
+        }
+        assert!(timeout(WAIT, stream.write_all(b"fazla")).await.is_err());
+
+        manager.on_sendme(&key).unwrap();
+        timeout(WAIT, stream.write_all(b"fazla")).await.unwrap().unwrap();
+
+        // Pencere kadar çerçeve ve SENDME sonrası bir çerçeve kuyruğa girer
+        let mut data_frames = 0;
+        while let Ok(Some(command)) = commands.try_next() {
+            if let StreamCommand::Data { .. } = command {
+                data_frames += 1;
+            }
+        }
+        assert_eq!(data_frames, STREAM_WINDOW + 1);
+    }
+
+    #[tokio::test]
+    async fn unexpected_stream_sendme_is_rejected() {
+        let (mut manager, _commands, _accepted) = StreamManager::new();
+        let stream = manager.open(CIRCUIT, None);
+
+        assert!(manager.on_sendme(&stream.key()).is_err());
+    }
+
+    #[tokio::test]
+    async fn close_wakes_blocked_writer() {
+        let (mut manager, _commands, _accepted) = StreamManager::new();
+        let mut stream = manager.open(CIRCUIT, None);
+        let key = stream.key();
+
+        for _ in 0..STREAM_WINDOW {
+            stream.write_all(b"veri").await.unwrap();
+        }
+        let writer = tokio::spawn(async move { stream.write_all(b"fazla").await });
+        tokio::time::sleep(WAIT).await;
+
+        assert!(manager.close(&key));
+        assert!(timeout(WAIT, writer).await.unwrap().unwrap().is_err());
+    }
+
+    fn count_sendmes(commands: &mut mpsc::Receiver<StreamCommand>) -> usize {
+        std::iter::from_fn(|| commands.try_next().ok().flatten())
+            .filter(|command| matches!(command, StreamCommand::Sendme { .. }))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn sendme_follows_application_reads() {
+        let (mut manager, mut commands, mut accepted) = StreamManager::new();
+        manager.accept(CIRCUIT, 1, None).unwrap();
+        let mut stream = accepted.next().await.unwrap();
+        let key = stream.key();
+
+        for _ in 0..STREAM_SENDME_INCREMENT * 2 {
+            manager.deliver(&key, b"ab".to_vec()).unwrap();
+        }
+        // Uygulama okumadıkça kredi geri verilmez
+        assert_eq!(count_sendmes(&mut commands), 0);
+
+        let mut buf = vec![0u8; 2 * STREAM_SENDME_INCREMENT as usize];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(count_sendmes(&mut commands), 1);
+
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(count_sendmes(&mut commands), 1);
+    }
+
+    #[tokio::test]
+    async fn delivery_beyond_window_is_rejected() {
+        let (mut manager, _commands, mut accepted) = StreamManager::new();
+        manager.accept(CIRCUIT, 1, None).unwrap();
+        let stream = accepted.next().await.unwrap();
+        let key = stream.key();
+
+        for _ in 0..STREAM_WINDOW {
+            manager.deliver(&key, b"ab".to_vec()).unwrap();
+        }
+        assert!((0..2).any(|_| manager.deliver(&key, b"ab".to_vec()).is_err()));
+        assert!(!manager.is_open(&key));
+    }
+
+    #[tokio::test]
+    async fn stream_ids_wrap_around_without_reusing_open_streams() {
+        let (mut manager, _commands, _accepted) = StreamManager::new();
+        let first = manager.open(CIRCUIT, None);
+        assert_eq!(first.key().stream_id, 1);
+
+        manager.next_stream_id = StreamId::MAX;
+        assert_eq!(manager.open(CIRCUIT, None).key().stream_id, StreamId::MAX);
+        // 1 hâlâ açık olduğundan atlanır
+        assert_eq!(manager.open(CIRCUIT, None).key().stream_id, 2);
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_is_closed_when_command_channel_is_full() {
+        let (mut manager, mut commands, _accepted) = StreamManager::new();
+        let mut streams: Vec<_> = (0..3).map(|_| manager.open(CIRCUIT, None)).collect();
+
+        // Komut kanalını doldur, yazması bekleyen tutamaç kanalda yer ayıramaz
+        let mut blocked = None;
+        'fill: for (index, stream) in streams.iter_mut().enumerate() {
+            for _ in 0..STREAM_WINDOW {
+                if timeout(WAIT, stream.write_all(b"veri")).await.is_err() {
+                    blocked = Some(index);
+                    break 'fill;
+                }
+            }
+        }
+
+        let stream = streams.remove(blocked.expect("komut kanalı dolmadı"));
+        let dropped = stream.key();
+        drop(stream);
+        let mut closes = 0;
+        while let Ok(Some(command)) = commands.try_next() {
+            if let StreamCommand::Close { .. } = command {
+                closes += 1;
+            }
+        }
+        assert_eq!(closes, 0);
+        assert_eq!(manager.abandoned(), vec![dropped]);
+    }
+
+    #[test]
+    fn reorder_buffer_releases_cells_in_sequence() {
+        let mut buffer = ReorderBuffer::new();
+        assert!(buffer.push(1, "b").is_empty());
+        assert!(buffer.push(2, "c").is_empty());
+        assert_eq!(buffer.push(0, "a"), vec!["a", "b", "c"]);
+
+        // Teslim edilmiş sıra numaraları yeniden kabul edilmez
+        assert!(buffer.push(1, "b").is_empty());
+        assert_eq!(buffer.push(3, "d"), vec!["d"]);
+    }
+
+    #[test]
+    fn reorder_buffer_never_skips_stale_gap() {
+        let mut buffer = ReorderBuffer::new();
+        assert!(!buffer.has_stale_gap(Duration::ZERO));
+        assert!(buffer.push(2, "c").is_empty());
+        assert!(buffer.push(3, "d").is_empty());
+        assert!(!buffer.has_stale_gap(Duration::from_secs(60)));
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(buffer.has_stale_gap(Duration::ZERO));
+
+        // Boşluktan sonraki hücreler eksik hücre gelmeden teslim edilmez
+        assert!(buffer.push(4, "e").is_empty());
+        assert_eq!(buffer.push(0, "a"), vec!["a"]);
+        assert_eq!(buffer.push(1, "b"), vec!["b", "c", "d", "e"]);
+        assert!(!buffer.has_stale_gap(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn reset_circuit_fails_reads_instead_of_ending_stream() {
+        let (mut manager, _commands, mut accepted) = StreamManager::new();
+        manager.accept(CIRCUIT, 1, None).unwrap();
+        let mut stream = accepted.next().await.unwrap();
+        let key = stream.key();
+
+        manager.deliver(&key, b"ab".to_vec()).unwrap();
+        manager.reset_circuit(&CIRCUIT, false);
+        assert!(!manager.is_open(&key));
+
+        // Kesilmeden önce teslim edilen veri okunur, ardından hata gelir
+        let mut buf = [0u8; 2];
+        stream.read_exact(&mut buf).await.unwrap();
+        let error = stream.read(&mut buf).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+        assert!(stream.write_all(b"veri").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn connect_result_is_delivered_once_to_targeted_streams() {
+        let (mut manager, _commands, _accepted) = StreamManager::new();
+        let mut stream = manager.open(CIRCUIT, Some("example.com:80".to_string()));
+        let key = stream.key();
+
+        manager.on_connected(&key, ConnectResult::ConnectionRefused).unwrap();
+        assert!(manager.on_connected(&key, ConnectResult::Succeeded).is_err());
+        assert_eq!(stream.connected().await, ConnectResult::ConnectionRefused);
+        assert_eq!(stream.connected().await, ConnectResult::ConnectionRefused);
+
+        // Hedefsiz akışlar sonuç beklemez
+        let mut untargeted = manager.open(CIRCUIT, None);
+        assert!(manager.on_connected(&untargeted.key(), ConnectResult::Succeeded).is_err());
+        assert_eq!(untargeted.connected().await, ConnectResult::Succeeded);
+
+        // Sonuç gelmeden kapanan akış başarısız sayılır
+        let mut closed = manager.open(CIRCUIT, Some("example.com:80".to_string()));
+        manager.close(&closed.key());
+        assert_eq!(timeout(WAIT, closed.connected()).await.unwrap(), ConnectResult::Failed);
+    }
+}
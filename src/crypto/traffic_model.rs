@@ -0,0 +1,345 @@
+use anyhow::{anyhow, Result};
+use rand::{seq::SliceRandom, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use super::fake_traffic::FakeHttpRequest;
+use super::traffic_schedule::{capped_delay, TrafficScheduler};
+
+// Örneklenen gövde ve cevap boyutlarının üst sınırı, örtü paketleri tek bir doğrudan teslim isteğine sığsın
+pub const MAX_BODY_SIZE: usize = 64 * 1024;
+
+// HAR dosyasının kullanılan alanları
+#[derive(Deserialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Deserialize)]
+struct HarLog {
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    request: HarRequest,
+    response: HarResponse,
+}
+
+// Bilinmeyen boyutlar HAR'da -1 olarak yazılır
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers_size: i64,
+    #[serde(default)]
+    body_size: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    #[serde(default)]
+    headers_size: i64,
+    #[serde(default)]
+    body_size: i64,
+    #[serde(default)]
+    content: HarContent,
+}
+
+#[derive(Deserialize, Default)]
+struct HarContent {
+    #[serde(default)]
+    size: i64,
+}
+
+// Kayıtlı bir isteğin modele giren bilgileri
+struct RecordedRequest {
+    // Kaydın başından itibaren saniye
+    time: f64,
+    request_size: usize,
+    response_size: usize,
+    // Yalnızca HAR kayıtlarında bulunur
+    method: Option<String>,
+    url: Option<String>,
+}
+
+// Durum raporunda gösterilen model özeti
+#[derive(Debug, Clone, Serialize)]
+pub struct TrafficModelSummary {
+    pub requests: usize,
+    pub mean_request_bytes: f64,
+    pub mean_response_bytes: f64,
+    pub mean_rate_per_second: f64,
+}
+
+// Kayıtlı trafikten çıkarılan deneysel model
+// Sahte istekler kayıttaki boyut ve aralıkların kendisinden örneklenir
+#[derive(Debug, Clone)]
+pub struct TrafficModel {
+    request_sizes: Vec<usize>,
+    response_sizes: Vec<usize>,
+    // Ardışık istekler arasındaki süreler (sn)
+    inter_arrivals: Vec<f64>,
+    // HAR kayıtlarındaki yöntem ve URL'ler, CSV kayıtlarında boştur
+    targets: Vec<(String, String)>,
+}
+
+impl TrafficModel {
+    // Kaydı dosya uzantısına göre HAR ya da CSV olarak oku
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Trafik kaydı okunamadı ({}): {}", path.display(), e))?;
+
+        let is_har = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("har"));
+        if is_har {
+            Self::from_har(&content)
+        } else {
+            Self::from_csv(&content)
+        }
+    }
+
+    // Tarayıcıların dışa aktardığı HAR kaydından model oluştur
+    pub fn from_har(content: &str) -> Result<Self> {
+        let har: Har = serde_json::from_str(content).map_err(|e| anyhow!("HAR dosyası okunamadı: {}", e))?;
+
+        let records = har.log.entries.into_iter()
+            .map(|entry| {
+                let time = parse_timestamp(&entry.started_date_time)
+                    .ok_or_else(|| anyhow!("Geçersiz HAR zamanı: {}", entry.started_date_time))?;
+                let response_body = entry.response.body_size.max(entry.response.content.size);
+
+                Ok(RecordedRequest {
+                    time,
+                    request_size: known_size(entry.request.headers_size) + known_size(entry.request.body_size),
+                    response_size: known_size(entry.response.headers_size) + known_size(response_body),
+                    method: Some(entry.request.method),
+                    url: Some(entry.request.url),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::from_records(records)
+    }
+
+    // `zaman,istek_boyutu,cevap_boyutu` satırlarından oluşan CSV kaydından model oluştur
+    // Zaman saniye cinsindendir; başlık satırı ve `#` ile başlayan satırlar atlanır
+    pub fn from_csv(content: &str) -> Result<Self> {
+        let mut records = Vec::new();
+        let mut header_checked = false;
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let parsed = match fields.as_slice() {
+                [time, request, response] => time.parse::<f64>().ok()
+                    .zip(request.parse::<usize>().ok())
+                    .zip(response.parse::<usize>().ok()),
+                _ => None,
+            };
+
+            // İlk veri satırı sayısal değilse başlıktır
+            let is_header = !header_checked;
+            header_checked = true;
+
+            match parsed {
+                Some(((time, request_size), response_size)) if time.is_finite() => records.push(RecordedRequest {
+                    time,
+                    request_size,
+                    response_size,
+                    method: None,
+                    url: None,
+                }),
+                _ if is_header => continue,
+                _ => return Err(anyhow!("Geçersiz CSV satırı {}: {}", index + 1, line)),
+            }
+        }
+
+        Self::from_records(records)
+    }
+
+    fn from_records(mut records: Vec<RecordedRequest>) -> Result<Self> {
+        if records.len() < 2 {
+            return Err(anyhow!("Trafik modeli için en az iki istek gerekli, kayıtta {} istek var", records.len()));
+        }
+
+        records.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let inter_arrivals: Vec<f64> = records.windows(2).map(|pair| pair[1].time - pair[0].time).collect();
+        if inter_arrivals.iter().sum::<f64>() <= 0.0 {
+            return Err(anyhow!("Kayıttaki tüm istekler aynı anda başlamış, aralıklar çıkarılamadı"));
+        }
+
+        Ok(Self {
+            request_sizes: records.iter().map(|record| record.request_size).collect(),
+            response_sizes: records.iter().map(|record| record.response_size).collect(),
+            inter_arrivals,
+            targets: records.into_iter()
+                .filter_map(|record| Some((record.method?, record.url?)))
+                .collect(),
+        })
+    }
+
+    // Kayıttaki ortalama istek hızı
+    pub fn mean_rate(&self) -> f64 {
+        self.inter_arrivals.len() as f64 / self.inter_arrivals.iter().sum::<f64>()
+    }
+
+    // Kayıttan rastgele bir istek seçip boyutlarıyla ve hedefiyle sahte istek oluştur
+    // İstek ve cevap boyutu aynı kayıttan alınır, aralarındaki ilişki korunur
+    pub fn sample_request<R: Rng + ?Sized>(&self, rng: &mut R) -> FakeHttpRequest {
+        let index = rng.gen_range(0..self.request_sizes.len());
+        let mut request = FakeHttpRequest::random(rng);
+
+        if let Some((method, url)) = self.targets.get(index) {
+            request.method = method.clone();
+            request.url = url.clone();
+        }
+
+        let size = self.request_sizes[index].min(MAX_BODY_SIZE);
+        request.data = (0..size).map(|_| rng.gen()).collect();
+        request.response_size = self.response_sizes[index].min(MAX_BODY_SIZE);
+
+        request
+    }
+
+    // Kayıttaki aralıklardan örnekleyen zamanlayıcı
+    pub fn scheduler(&self) -> Box<dyn TrafficScheduler> {
+        Box::new(EmpiricalSchedule {
+            inter_arrivals: self.inter_arrivals.clone(),
+            mean: 1.0 / self.mean_rate(),
+        })
+    }
+
+    pub fn summary(&self) -> TrafficModelSummary {
+        TrafficModelSummary {
+            requests: self.request_sizes.len(),
+            mean_request_bytes: mean(&self.request_sizes),
+            mean_response_bytes: mean(&self.response_sizes),
+            mean_rate_per_second: self.mean_rate(),
+        }
+    }
+}
+
+// Kayıttaki aralıkların dağılımını koruyan zamanlayıcı
+// Aralıklar ortalaması verilen hıza denk gelecek şekilde ölçeklenir; kaydın kendi temposu için üreteç hızı `mean_rate` olmalıdır
+struct EmpiricalSchedule {
+    inter_arrivals: Vec<f64>,
+    mean: f64,
+}
+
+impl TrafficScheduler for EmpiricalSchedule {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, rate_per_second: f64) -> Duration {
+        let gap = self.inter_arrivals.choose(rng).copied().unwrap_or(self.mean);
+
+        capped_delay(gap / self.mean / rate_per_second)
+    }
+
+    fn name(&self) -> &str {
+        "empirical"
+    }
+}
+
+fn known_size(size: i64) -> usize {
+    size.max(0) as usize
+}
+
+fn mean(values: &[usize]) -> f64 {
+    values.iter().sum::<usize>() as f64 / values.len().max(1) as f64
+}
+
+// HAR'daki ISO 8601 zamanını Unix saniyesine çevir: 2024-05-01T12:30:15.123Z ya da +03:00 ofsetli
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let (date, time) = value.split_once('T')?;
+
+    let mut date_parts = date.split('-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    // Saat dilimi soneki: Z, +hh:mm ya da -hh:mm
+    let (clock, offset_secs) = match time.find(['Z', 'z', '+', '-']) {
+        Some(position) => {
+            let (clock, zone) = time.split_at(position);
+            let offset = match zone.split_at(1) {
+                (_, "") => 0,
+                (sign, zone) => {
+                    let (hours, minutes) = zone.split_once(':').unwrap_or((zone, "0"));
+                    let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+                    if sign == "-" { -offset } else { offset }
+                }
+            };
+            (clock, offset)
+        }
+        None => (time, 0),
+    };
+
+    let mut clock_parts = clock.split(':');
+    let hours: i64 = clock_parts.next()?.parse().ok()?;
+    let minutes: i64 = clock_parts.next()?.parse().ok()?;
+    let seconds: f64 = clock_parts.next()?.parse().ok()?;
+
+    let days = days_from_civil(year, month, day);
+
+    Some((days * 86400 + hours * 3600 + minutes * 60 - offset_secs) as f64 + seconds)
+}
+
+// Gregoryen takvim tarihinden 1970-01-01'e göre gün sayısı
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::traffic_schedule::MAX_DELAY;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const RECORD: &str = "zaman,istek,cevap\n0,100,2000\n1,200,4000\n3,300,6000\n";
+
+    #[test]
+    fn empirical_schedule_replays_recorded_gaps() {
+        let model = TrafficModel::from_csv(RECORD).unwrap();
+        assert!((model.mean_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        let mut scheduler = model.scheduler();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            let delay = scheduler.next_delay(&mut rng, model.mean_rate()).as_secs_f64();
+            assert!((delay - 1.0).abs() < 1e-9 || (delay - 2.0).abs() < 1e-9, "{}", delay);
+        }
+    }
+
+    #[test]
+    fn empirical_schedule_caps_tiny_rates() {
+        let model = TrafficModel::from_csv(RECORD).unwrap();
+        let mut scheduler = model.scheduler();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(scheduler.next_delay(&mut rng, 1e-20), MAX_DELAY);
+    }
+
+    #[test]
+    fn csv_rejects_invalid_rows() {
+        assert!(TrafficModel::from_csv("0,1,1\nbozuk\n").is_err());
+        assert!(TrafficModel::from_csv("0,1,1\n").is_err());
+    }
+}
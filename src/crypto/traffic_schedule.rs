@@ -0,0 +1,324 @@
+use anyhow::{anyhow, Result};
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// Sahte isteklerin zamanlamasını belirleyen zamanlayıcı
+// Ortalama hız her çağrıda verilir, üreteç çalışırken hız değişse de zamanlayıcı yeniden kurulmaz
+pub trait TrafficScheduler: Send {
+    // Bir sonraki isteğe kadar beklenecek süre, uzun vadede ortalama `rate_per_second` istek üretir
+    fn next_delay(&mut self, rng: &mut dyn RngCore, rate_per_second: f64) -> Duration;
+
+    fn name(&self) -> &str;
+}
+
+// Tek bir beklemenin üst sınırı
+// Çok küçük hızlarda süre Duration sınırını aşıp paniğe yol açmasın, hız değişikliği de makul sürede etkili olsun
+pub const MAX_DELAY: Duration = Duration::from_secs(3600);
+
+// Saniye cinsinden beklemeyi üst sınırla Duration'a çevir
+// Sonsuz ve tanımsız süreler de üst sınıra yuvarlanır
+pub fn capped_delay(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY))
+}
+
+// (0, 1] aralığında düzgün örnek, logaritmaların sonlu kalması için
+fn unit(rng: &mut dyn RngCore) -> f64 {
+    1.0 - rng.gen::<f64>()
+}
+
+// Verilen ortalamayla üstel dağılımdan örnekle
+fn exponential(rng: &mut dyn RngCore, mean_secs: f64) -> f64 {
+    -mean_secs * unit(rng).ln()
+}
+
+// Sabit aralıklarla istek üretir
+#[derive(Debug, Clone, Default)]
+pub struct ConstantRate;
+
+impl TrafficScheduler for ConstantRate {
+    fn next_delay(&mut self, _rng: &mut dyn RngCore, rate_per_second: f64) -> Duration {
+        capped_delay(1.0 / rate_per_second)
+    }
+
+    fn name(&self) -> &str {
+        "constant"
+    }
+}
+
+// Bekleme süreleri üstel dağılır, istekler Poisson süreci oluşturur
+#[derive(Debug, Clone, Default)]
+pub struct Poisson;
+
+impl TrafficScheduler for Poisson {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, rate_per_second: f64) -> Duration {
+        capped_delay(exponential(rng, 1.0 / rate_per_second))
+    }
+
+    fn name(&self) -> &str {
+        "poisson"
+    }
+}
+
+// Açık dönemlerde yoğun Poisson trafiği, kapalı dönemlerde sessizlik
+// Dönem süreleri üsteldir; açık dönemdeki hız, ortalama hız korunacak şekilde yükseltilir
+#[derive(Debug, Clone)]
+pub struct OnOff {
+    pub mean_on: Duration,
+    pub mean_off: Duration,
+    // Açık dönemin kalan süresi (sn)
+    remaining_on: f64,
+}
+
+impl OnOff {
+    pub fn new(mean_on: Duration, mean_off: Duration) -> Self {
+        Self { mean_on, mean_off, remaining_on: 0.0 }
+    }
+}
+
+impl Default for OnOff {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5), Duration::from_secs(15))
+    }
+}
+
+impl TrafficScheduler for OnOff {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, rate_per_second: f64) -> Duration {
+        let on = self.mean_on.as_secs_f64();
+        let off = self.mean_off.as_secs_f64();
+        let burst_rate = rate_per_second * (on + off) / on;
+
+        // Üstel dağılımın belleksizliği sayesinde dönem sınırında kalan bekleme yeniden örneklenebilir
+        let mut delay = 0.0;
+        loop {
+            if self.remaining_on <= 0.0 {
+                delay += exponential(rng, off);
+                self.remaining_on = exponential(rng, on);
+            }
+
+            let gap = exponential(rng, 1.0 / burst_rate);
+            if gap < self.remaining_on {
+                self.remaining_on -= gap;
+                return capped_delay(delay + gap);
+            }
+            delay += self.remaining_on;
+            self.remaining_on = 0.0;
+
+            // Çok küçük hızlarda açık dönemlere hiç istek düşmez, döngü üst sınırda kesilir
+            if delay >= MAX_DELAY.as_secs_f64() {
+                return MAX_DELAY;
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "onoff"
+    }
+}
+
+// Web gezintisini taklit eder: her sayfa açılışında art arda gelen bir grup kaynak isteği,
+// ardından ağır kuyruklu (Pareto) bir okuma süresi
+#[derive(Debug, Clone)]
+pub struct Browsing {
+    // Sayfa başına ortalama istek sayısı, sayı geometrik dağılır
+    pub requests_per_page: f64,
+    // Aynı sayfanın istekleri arasındaki ortalama süre
+    pub intra_page_gap: Duration,
+    // Okuma süresinin Pareto şekil parametresi, küçüldükçe kuyruk ağırlaşır
+    pub think_shape: f64,
+    // Sayfanın kalan istek sayısı
+    remaining_requests: u32,
+}
+
+impl Browsing {
+    pub fn new(requests_per_page: f64, intra_page_gap: Duration, think_shape: f64) -> Self {
+        Self { requests_per_page, intra_page_gap, think_shape, remaining_requests: 0 }
+    }
+
+    // Sayfanın istek sayısı, en az 1 olan geometrik dağılım
+    fn page_size(&self, rng: &mut dyn RngCore) -> u32 {
+        let success = 1.0 / self.requests_per_page.max(1.0);
+        if success >= 1.0 {
+            return 1;
+        }
+
+        1 + (unit(rng).ln() / (1.0 - success).ln()).floor() as u32
+    }
+}
+
+impl Default for Browsing {
+    fn default() -> Self {
+        Self::new(8.0, Duration::from_millis(80), 1.5)
+    }
+}
+
+impl TrafficScheduler for Browsing {
+    fn next_delay(&mut self, rng: &mut dyn RngCore, rate_per_second: f64) -> Duration {
+        let gap = self.intra_page_gap.as_secs_f64();
+
+        if self.remaining_requests > 0 {
+            self.remaining_requests -= 1;
+            return capped_delay(exponential(rng, gap));
+        }
+
+        // Ortalama okuma süresi, sayfa başına düşen toplam süre ortalama hızı verecek şekilde seçilir
+        let page_time = self.requests_per_page.max(1.0) / rate_per_second;
+        let think_mean = (page_time - (self.requests_per_page.max(1.0) - 1.0) * gap).max(gap);
+        let shape = self.think_shape.max(1.01);
+        let scale = think_mean * (shape - 1.0) / shape;
+
+        self.remaining_requests = self.page_size(rng) - 1;
+
+        capped_delay(scale / unit(rng).powf(1.0 / shape))
+    }
+
+    fn name(&self) -> &str {
+        "browsing"
+    }
+}
+
+// Hazır zamanlama profilleri
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleProfile {
+    Constant,
+    Poisson,
+    OnOff,
+    Browsing,
+}
+
+impl ScheduleProfile {
+    pub fn scheduler(self) -> Box<dyn TrafficScheduler> {
+        match self {
+            ScheduleProfile::Constant => Box::new(ConstantRate),
+            ScheduleProfile::Poisson => Box::new(Poisson),
+            ScheduleProfile::OnOff => Box::new(OnOff::default()),
+            ScheduleProfile::Browsing => Box::new(Browsing::default()),
+        }
+    }
+}
+
+impl FromStr for ScheduleProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "constant" => Ok(ScheduleProfile::Constant),
+            "poisson" => Ok(ScheduleProfile::Poisson),
+            "onoff" => Ok(ScheduleProfile::OnOff),
+            "browsing" => Ok(ScheduleProfile::Browsing),
+            _ => Err(anyhow!("Bilinmeyen zamanlama profili: {} (constant, poisson, onoff, browsing)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ScheduleProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScheduleProfile::Constant => "constant",
+            ScheduleProfile::Poisson => "poisson",
+            ScheduleProfile::OnOff => "onoff",
+            ScheduleProfile::Browsing => "browsing",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const SAMPLES: usize = 20_000;
+
+    fn delays(scheduler: &mut dyn TrafficScheduler, rate: f64, count: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..count).map(|_| scheduler.next_delay(&mut rng, rate).as_secs_f64()).collect()
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    fn variance(values: &[f64]) -> f64 {
+        let mean = mean(values);
+        values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= expected * tolerance, "{} != {} (±%{})", actual, expected, tolerance * 100.0);
+    }
+
+    #[test]
+    fn constant_rate_is_exact() {
+        let samples = delays(&mut ConstantRate, 4.0, 100);
+        assert!(samples.iter().all(|delay| (delay - 0.25).abs() < 1e-9));
+    }
+
+    #[test]
+    fn poisson_gaps_follow_exponential_distribution() {
+        let rate = 5.0;
+        let mut samples = delays(&mut Poisson, rate, SAMPLES);
+        assert_close(mean(&samples), 1.0 / rate, 0.03);
+        assert_close(variance(&samples), 1.0 / (rate * rate), 0.06);
+
+        // Kolmogorov-Smirnov: %1 anlamlılık düzeyinde üstel dağılımdan ayırt edilemez
+        samples.sort_by(f64::total_cmp);
+        let n = samples.len() as f64;
+        let statistic = samples.iter().enumerate()
+            .map(|(index, delay)| {
+                let cdf = 1.0 - (-rate * delay).exp();
+                (cdf - index as f64 / n).abs().max(((index + 1) as f64 / n - cdf).abs())
+            })
+            .fold(0.0, f64::max);
+        assert!(statistic < 1.63 / n.sqrt(), "KS istatistiği {}", statistic);
+    }
+
+    #[test]
+    fn on_off_keeps_mean_rate_and_is_burstier_than_poisson() {
+        let rate = 2.0;
+        let samples = delays(&mut OnOff::default(), rate, SAMPLES * 5);
+        assert_close(mean(&samples), 1.0 / rate, 0.1);
+
+        // Sessiz dönemler varyasyon katsayısını Poisson'un 1 değerinin üstüne çıkarır
+        let coefficient = variance(&samples).sqrt() / mean(&samples);
+        assert!(coefficient > 1.5, "varyasyon katsayısı {}", coefficient);
+    }
+
+    #[test]
+    fn browsing_keeps_mean_rate_with_short_intra_page_gaps() {
+        let rate = 1.0;
+        let mut scheduler = Browsing::default();
+        let samples = delays(&mut scheduler, rate, SAMPLES * 5);
+        assert_close(mean(&samples), 1.0 / rate, 0.15);
+
+        // Beklemelerin çoğu aynı sayfanın kısa aralıklarıdır
+        let median = {
+            let mut sorted = samples.clone();
+            sorted.sort_by(f64::total_cmp);
+            sorted[sorted.len() / 2]
+        };
+        assert!(median < scheduler.intra_page_gap.as_secs_f64() * 2.0, "medyan {}", median);
+    }
+
+    #[test]
+    fn tiny_rates_are_capped_instead_of_panicking() {
+        let schedulers: Vec<Box<dyn TrafficScheduler>> = vec![
+            Box::new(ConstantRate),
+            Box::new(Poisson),
+            Box::new(OnOff::default()),
+            Box::new(Browsing::default()),
+        ];
+
+        for mut scheduler in schedulers {
+            for rate in [1e-20, f64::MIN_POSITIVE] {
+                let samples = delays(scheduler.as_mut(), rate, 10);
+                assert!(samples.iter().all(|delay| *delay <= MAX_DELAY.as_secs_f64()), "{}", scheduler.name());
+            }
+        }
+
+        assert_eq!(capped_delay(f64::INFINITY), MAX_DELAY);
+        assert_eq!(capped_delay(f64::NAN), MAX_DELAY);
+    }
+}
@@ -1,28 +1,65 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use anyhow::{Result, anyhow};
 use libp2p::{
     floodsub::{self, Floodsub, FloodsubEvent},
-    identity,
+    gossipsub::{error::PublishError, Gossipsub, GossipsubEvent, IdentTopic, MessageAcceptance, MessageAuthenticity},
+    identify::{Identify, IdentifyConfig, IdentifyEvent},
+    kad::{record::store::MemoryStore, Kademlia, KademliaConfig, KademliaEvent, QueryResult},
     mdns::{Mdns, MdnsConfig, MdnsEvent},
-    swarm::{SwarmBuilder, SwarmEvent, NetworkBehaviourEventProcess},
-    PeerId,
+    request_response::{OutboundFailure, RequestResponse, RequestResponseEvent, RequestResponseMessage},
+    swarm::{toggle::Toggle, SwarmBuilder, SwarmEvent, NetworkBehaviourEventProcess},
+    Multiaddr, PeerId,
 };
 use libp2p::NetworkBehaviour;
 use futures::StreamExt;
-use tokio::time::sleep;
-use crate::crypto::anon_protocol::{AnonymousProtocol, MessageType};
-use crate::crypto::chaotic_routing::ChaoticRouter;
-use crate::crypto::multi_layer::{EncryptionLayer, MultiLayerEncryption};
-use rand::{thread_rng, Rng};
+use crate::crypto::anon_protocol::{self, AnonymousProtocol, MessageType};
+use crate::crypto::chaotic_routing::{
+    ChaoticRouter, CrowdDecision, CrowdDirection, CrowdPacket, CrowdPathId, CrowdReply, CrowdRequest, CrowdReturnHop,
+    MixQueue, MAX_HOP_DELAY,
+};
+use crate::crypto::circuit::{CircuitCell, CircuitId, CircuitReport};
+use crate::crypto::circuit_manager::{CircuitManager, CircuitNotice, CircuitOutput, CircuitTimeouts};
+use crate::crypto::flow_control::Priority;
+use crate::crypto::traffic_model::TrafficModel;
+use crate::crypto::traffic_schedule::ScheduleProfile;
+use crate::crypto::stream_mux::{ConnectResult, KuantumStream, StreamCommand, StreamManager, STREAM_CHUNK_SIZE};
+use crate::crypto::bootstrap::{parse_peer_addr, BootstrapManager};
+use crate::crypto::connection_manager::{ConnectionConfig, ConnectionManager, ConnectionReport};
+use crate::crypto::cover_traffic::{self, CoverStats};
+use crate::crypto::direct::{self, DeliveryStats, DeliveryTracker, DirectCodec, DirectResponse};
+use crate::crypto::fake_traffic::{FakeHttpRequest, FakeTrafficGenerator, FakeTrafficHandle, FakeTrafficStats};
+use crate::crypto::fragment::{self, Fragment, FragmentBuffer, FRAGMENT_SIZE};
+use crate::crypto::guard_nodes::GuardManager;
+use crate::crypto::peer_store::{PeerRole, PeerStore};
+use crate::crypto::node_identity;
+use crate::crypto::pubsub::{self, PubsubMode};
+use crate::crypto::rate_limit::{LimitExceeded, Protocol, ProtocolLimits, RateLimitReport, RateLimiter, RateLimits};
+use crate::crypto::hop_limit::SeenPacketCache;
+use crate::crypto::hidden_service::{DescriptorCache, ServiceCell, ServiceDescriptor, ServiceKeyStore};
+use crate::crypto::hidden_service_manager::{HiddenServiceManager, ServiceEvent, ServiceOutput};
+use crate::crypto::multi_layer::MultiLayerEncryption;
+use crate::crypto::multipath::{self, MultipathId, Share, ShareAssembler};
+use crate::crypto::onion_routing::{self, OnionPacket, PeeledPacket, RelayKeyPair, RouteHop};
+use crate::crypto::reputation::{PeerEvent, PeerReputation, ReputationStore};
+use crate::crypto::route_health::{self, ProbeId, RouteHealth, RouteHealthMonitor, RouteId};
+use crate::crypto::reply_block::{OnionMessage, ReplyBlock, ReplyBlockStore};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::io::{self, AsyncBufReadExt};
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::net::TcpListener;
+use crate::proxy::{ExitPolicy, Isolation, StreamRequest};
 
 pub mod crypto;
+pub mod proxy;
 
 // Anonim token yapısı
+#[allow(dead_code)]
 #[derive(Clone, Debug)]
 struct Token {
     id: String,
@@ -31,173 +68,1579 @@ struct Token {
     ttl: u32,
 }
 
-// Soğan paket yapısı
-#[derive(Clone, Debug)]
-struct OnionPacket {
-    layers: Vec<Vec<u8>>,
-    route_info: Vec<PeerId>,
-    current_layer: usize,
-}
+// Cevap yolundaki aracı düğüm sayısı
+const REPLY_ROUTE_HOPS: u32 = 3;
+// Servis tanımlayıcılarının geçerlilik süresi, daha eski tanımlayıcılar reddedilir
+const DESCRIPTOR_VALIDITY: Duration = Duration::from_secs(60 * 60);
+// Çok yollu iletimde en fazla parça sayısı ve birleştirme için gereken parça sayısı
+const MULTIPATH_TOTAL_SHARES: u8 = 4;
+const MULTIPATH_REQUIRED_SHARES: u8 = 2;
+// Sürekli yoklanan aktif rota sayısı
+const ACTIVE_ROUTE_COUNT: usize = 3;
+// Yoklama aralığı ve bir yoklamanın dönmesi için beklenen süre
+// Beklenen süre atlama başına ağ payı ve rotaya atanan mixnet gecikmeleriyle uzatılır
+const ROUTE_PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const ROUTE_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+const ROUTE_PROBE_HOP_TIMEOUT: Duration = Duration::from_secs(2);
+// Bu kadar art arda başarısız yoklamadan sonra rota değiştirilir
+const ROUTE_MAX_PROBE_FAILURES: u32 = 2;
+// Kalıcı verilerin saklandığı dizin
+const DATA_DIR: &str = ".kuantum";
+// Koruma düğümü sayısı
+const GUARD_COUNT: usize = 3;
+// Koruma düğümlerinin en kısa kullanım süresi (4 hafta, üzerine rastgele pay eklenir)
+const GUARD_ROTATION_PERIOD: Duration = Duration::from_secs(4 * 7 * 24 * 60 * 60);
+// Bu süre boyunca erişilemeyen koruma düğümü değiştirilir
+const GUARD_UNREACHABLE_TIMEOUT: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+// Ayarlanırsa yönlendirme kararları bu tohumla tekrar üretilebilir olur
+const SEED_ENV_VAR: &str = "KUANTUM_SEED";
+// Oluşturulan cevap bloklarının geçerlilik süresi
+const REPLY_BLOCK_LIFETIME: Duration = Duration::from_secs(600);
+// Döngü ve tekrar tespiti için görülen paketlerin hatırlanma süresi
+// Cevap bloğu başlıkları geçerli kaldığı sürece tekrar gönderilebileceğinden cevap bloğu ömründen kısa olamaz
+const SEEN_PACKET_LIFETIME: Duration = REPLY_BLOCK_LIFETIME;
+// Devrelerdeki gönderim kuyruklarının işlenme aralığı
+const CIRCUIT_TICK: Duration = Duration::from_millis(100);
+// SENDME, sırası gelmeyen hücre ve boşta kalan devre süreleri
+const CIRCUIT_TIMEOUTS: CircuitTimeouts = CircuitTimeouts {
+    sendme: Duration::from_secs(10),
+    reorder: Duration::from_secs(15),
+    idle: Duration::from_secs(600),
+};
+// Eksik parçaları gelmeyen paketlerin unutulma süresi
+const FRAGMENT_LIFETIME: Duration = Duration::from_secs(30);
+// Soğan paketine bölünen yükün parça boyutu, parça çerçevesinin ek yüküne yer bırakır
+const ONION_FRAGMENT_SIZE: usize = onion_routing::PAYLOAD_CAPACITY - 64;
+// Yalnızca Kuantum düğümlerinden oluşan DHT için protokol adları
+const DHT_PROTOCOL: &[u8] = b"/kuantum/kad/1.0.0";
+const IDENTIFY_PROTOCOL: &str = "/kuantum/id/1.0.0";
+// DHT yönlendirme tablosunun rastgele sorgularla yenilenme aralığı
+const DHT_BOOTSTRAP_INTERVAL: Duration = Duration::from_secs(300);
+// Ayarlanırsa DHT bu adreslerden önyüklenir (virgülle ayrılmış, `/p2p/<peer-id>` ile biten multiaddr'lar)
+const BOOTSTRAP_ENV_VAR: &str = "KUANTUM_BOOTSTRAP";
+// Kopan önyükleme bağlantıları için ilk ve en uzun bekleme süresi
+const BOOTSTRAP_INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const BOOTSTRAP_MAX_BACKOFF: Duration = Duration::from_secs(300);
+// Diskte saklanan çalışan önyükleme düğümü sayısı
+const MAX_SAVED_BOOTSTRAP_PEERS: usize = 16;
+// Zamanı gelen yeniden bağlanma denemelerinin kontrol aralığı
+const REDIAL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// Doğrudan teslim edilen paketin onayı için beklenen süre
+const DIRECT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Eş puanlarının yarılanma süresi
+const REPUTATION_HALF_LIFE: Duration = Duration::from_secs(600);
+// Bu puanın altına düşen eşler yasaklanır
+const BAN_THRESHOLD: f64 = -50.0;
+const BAN_DURATION: Duration = Duration::from_secs(3600);
+// Eş başına hız sınırları ve şifreleme işleri için ortak kotalar
+const RATE_LIMITS: RateLimits = RateLimits {
+    pubsub: ProtocolLimits { messages_per_sec: 50.0, bytes_per_sec: 256.0 * 1024.0 },
+    // Devre hücreleri doğrudan teslimle taşındığından toplu aktarımlara yer bırakılır
+    direct: ProtocolLimits { messages_per_sec: 500.0, bytes_per_sec: 4.0 * 1024.0 * 1024.0 },
+    circuits_per_min: 60.0,
+    crypto_ops_per_sec: 2000.0,
+    crypto_memory_bytes: 32 * 1024 * 1024,
+};
+// Diskte saklanan en fazla düğüm ve düğüm başına adres sayısı
+const MAX_STORED_PEERS: usize = 256;
+const MAX_PEER_ADDRESSES: usize = 4;
+// Bağlı düğüm sayısı bunun altına düşünce kayıtlı düğümler yeniden aranır
+const MIN_CONNECTED_PEERS: usize = 3;
+// Bir seferde aranan en fazla kayıtlı düğüm
+const MAX_REDIAL_PEERS: usize = 8;
+// Varsayılan bağlantı sınırları, komut satırından değiştirilebilir
+const DEFAULT_CONNECTIONS: ConnectionConfig = ConnectionConfig {
+    max_inbound: 64,
+    max_outbound: 32,
+    max_pending_inbound: 16,
+    max_pending_outbound: 16,
+    max_per_peer: 2,
+    max_per_ip: 4,
+    target_peers: 48,
+    min_peers: MIN_CONNECTED_PEERS,
+    idle_timeout: Duration::from_secs(600),
+};
+// Boşta kalan ve fazla bağlantıların kontrol aralığı
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// Bu süre boyunca mesaj göndermeyen eşlerin kovaları unutulur
+const RATE_LIMIT_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+// Saniyede ortalama gönderilen örtü paketi
+const COVER_TRAFFIC_RATE: f64 = 0.2;
+
+// Yayın katmanı ya da doğrudan teslim ile taşınan ağ çerçeveleri
+#[derive(Debug, Serialize, Deserialize)]
+enum Frame {
+    // Düğümün yönlendirme anahtarını duyur
+    RelayAnnounce { relay_key: [u8; 32] },
+    // Belirli bir düğüme adreslenmiş soğan paketi
+    Onion { to: Vec<u8>, packet: OnionPacket },
+    // Gizli servis tanımlayıcısını yayınla
+    ServiceDescriptor(ServiceDescriptor),
+    // Belirli bir jondo'ya adreslenmiş Crowds paketi
+    Crowd { to: Vec<u8>, packet: CrowdPacket },
+    // Floodsub modunda 2048 byte sınırını aşan, yayın katmanıyla gönderilen adreslenmiş çerçevenin bir parçası
+    Fragment { to: Vec<u8>, fragment: Fragment },
+}
+
+// Soğan rotasının sonunda teslim edilen yük
+#[derive(Debug, Serialize, Deserialize)]
+enum OnionPayload {
+    Message(OnionMessage),
+    Service(ServiceCell),
+    // Çok yollu iletimde silme kodlamalı parça
+    Share(Share),
+    // Rotadan geçip göndereninin kendisine dönen yoklama paketi
+    Probe { probe_id: ProbeId },
+    // Akış denetimli devre hücresi
+    Circuit(CircuitCell),
+    // Gerçek paketlerden ayırt edilemeyen örtü paketi, son düğüm sessizce atar
+    // Cevap bloğu varsa son düğüm `response_size` byte'lık sahte bir cevap döndürür
+    Cover { data: Vec<u8>, response_size: u32, reply_block: Option<ReplyBlock> },
+    // Tek pakete sığmayan yükün aynı rotadan gönderilen parçası
+    Fragment(Fragment),
+}
+
+// Düğüm durum raporu
+#[derive(Debug, Serialize)]
+struct NodeStatus {
+    peer_id: String,
+    known_peers: usize,
+    stored_peers: usize,
+    dht_peers: usize,
+    // Gossipsub modunda bağlı eşlerin puanları
+    peer_scores: HashMap<String, f64>,
+    direct_delivery: DeliveryStats,
+    cover_traffic: CoverStats,
+    // Sahte trafik üreteci çalışıyorsa üretim istatistikleri
+    cover_generator: Option<FakeTrafficStats>,
+    reputation: Vec<PeerReputation>,
+    rate_limits: RateLimitReport,
+    connections: ConnectionReport,
+    relays: usize,
+    guards: Vec<String>,
+    hosted_services: Vec<String>,
+    mix_queue: usize,
+    routes: Vec<RouteHealth>,
+    circuits: Vec<CircuitReport>,
+    streams: usize,
+}
+
+// Network davranışlarını yöneten yapı
+#[derive(NetworkBehaviour)]
+struct KuantumBehaviour {
+    // Yalnızca biri etkindir, bkz. PubsubMode
+    floodsub: Toggle<Floodsub>,
+    gossipsub: Toggle<Gossipsub>,
+    mdns: Mdns,
+    kademlia: Kademlia<MemoryStore>,
+    identify: Identify,
+    // Adreslenmiş paketlerin onaylı doğrudan teslimi
+    direct: RequestResponse<DirectCodec>,
+    #[behaviour(ignore)]
+    deliveries: DeliveryTracker,
+    #[behaviour(ignore)]
+    cover_stats: CoverStats,
+    #[behaviour(ignore)]
+    reputation: ReputationStore,
+    // Yasaklanıp ana döngüde bağlantısı kesilecek eşler
+    #[behaviour(ignore)]
+    pending_bans: Vec<PeerId>,
+    #[behaviour(ignore)]
+    rate_limiter: RateLimiter,
+    #[behaviour(ignore)]
+    connections: ConnectionManager,
+    #[behaviour(ignore)]
+    anonymous_protocol: Arc<Mutex<AnonymousProtocol>>,
+    #[behaviour(ignore)]
+    chaotic_router: Arc<Mutex<ChaoticRouter>>,
+    // Kimlikler, hedef seçimleri ve soğan katmanlarının geçici anahtarları için rastgele kaynak
+    #[behaviour(ignore)]
+    rng: crypto::BoxedRng,
+    #[behaviour(ignore)]
+    multi_layer_encryption: Arc<Mutex<MultiLayerEncryption>>,
+    #[behaviour(ignore)]
+    #[allow(dead_code)]
+    response_topics: HashMap<String, String>,
+    // Şu anda erişilebilen düğümler, her düğüm bir kez bulunur
+    #[behaviour(ignore)]
+    known_peers: Vec<PeerId>,
+    #[behaviour(ignore)]
+    peer_store: PeerStore,
+    #[behaviour(ignore)]
+    topic: floodsub::Topic,
+    #[behaviour(ignore)]
+    gossip_topic: IdentTopic,
+    // Gossipsub modunda keşfedilip ana döngüde bağlanılacak eşler
+    #[behaviour(ignore)]
+    pending_dials: Vec<PeerId>,
+    // Eşlerin bu düğümü gördüğü adresler, çıkış politikasına ana döngüde eklenir
+    #[behaviour(ignore)]
+    observed_addresses: Vec<Multiaddr>,
+    #[behaviour(ignore)]
+    local_peer_id: PeerId,
+    #[behaviour(ignore)]
+    relay_keys: RelayKeyPair,
+    #[behaviour(ignore)]
+    relay_directory: HashMap<PeerId, [u8; 32]>,
+    #[behaviour(ignore)]
+    reply_blocks: Arc<Mutex<ReplyBlockStore>>,
+    #[behaviour(ignore)]
+    received_reply_blocks: HashMap<String, ReplyBlock>,
+    #[behaviour(ignore)]
+    services: HiddenServiceManager,
+    #[behaviour(ignore)]
+    mix_queue: MixQueue,
+    #[behaviour(ignore)]
+    crowd_reply_keys: HashMap<CrowdPathId, [u8; 32]>,
+    #[behaviour(ignore)]
+    crowd_pending_replies: HashMap<String, (CrowdPathId, [u8; 32], u32)>,
+    #[behaviour(ignore)]
+    guards: GuardManager,
+    #[behaviour(ignore)]
+    share_assembler: ShareAssembler,
+    #[behaviour(ignore)]
+    route_health: RouteHealthMonitor,
+    #[behaviour(ignore)]
+    seen_packets: SeenPacketCache,
+    #[behaviour(ignore)]
+    fragments: FragmentBuffer,
+    #[behaviour(ignore)]
+    circuits: CircuitManager,
+}
+
+impl NetworkBehaviourEventProcess<FloodsubEvent> for KuantumBehaviour {
+    fn inject_event(&mut self, event: FloodsubEvent) {
+        if let FloodsubEvent::Message(message) = event {
+            self.connections.on_activity(&message.source);
+            if let Err(e) = self.handle_pubsub_message(message.source, &message.data) {
+                self.on_frame_error(message.source, &e);
+            }
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<GossipsubEvent> for KuantumBehaviour {
+    fn inject_event(&mut self, event: GossipsubEvent) {
+        if let GossipsubEvent::Message { propagation_source, message_id, message } = event {
+            self.connections.on_activity(&propagation_source);
+
+            // Katı doğrulama modunda imzasız mesajlar buraya ulaşmadan atılır
+            let source = message.source.unwrap_or(propagation_source);
+
+            // Geçersiz paket gönderen eşin puanı düşer, diğer hatalı mesajlar yalnızca iletilmez
+            // Adreslenmiş paketleri yalnızca alıcısı doğrulayabilir, aradaki eşler onları dürüstçe iletir;
+            // Gossipsub cezası iletene yazıldığından yalnızca iletici mesajın sahibiyse reddedilir
+            let acceptance = match self.handle_pubsub_message(source, &message.data) {
+                Ok(()) => MessageAcceptance::Accept,
+                Err(e) => {
+                    if self.on_frame_error(source, &e) && source == propagation_source {
+                        MessageAcceptance::Reject
+                    } else {
+                        MessageAcceptance::Ignore
+                    }
+                }
+            };
+
+            if let Some(gossipsub) = self.gossipsub.as_mut() {
+                let _ = gossipsub.report_message_validation_result(&message_id, &propagation_source, acceptance);
+            }
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<MdnsEvent> for KuantumBehaviour {
+    fn inject_event(&mut self, event: MdnsEvent) {
+        match event {
+            MdnsEvent::Discovered(list) => {
+                // mDNS aynı düğümü her adresi için ayrı ve düzenli aralıklarla tekrar bildirir
+                for (peer_id, address) in list {
+                    if !self.known_peers.contains(&peer_id) {
+                        println!("mDNS yeni peer buldu: {}", peer_id);
+                    }
+                    self.peer_store.observe(peer_id, Some(&address));
+                    // Yerel ağdaki düğümler DHT için de başlangıç noktası olur
+                    self.kademlia.add_address(&peer_id, address);
+                    self.add_known_peer(peer_id);
+                }
+            }
+            MdnsEvent::Expired(list) => {
+                for (peer_id, _) in list {
+                    println!("mDNS peer süresi doldu: {}", peer_id);
+                    self.forget_peer(&peer_id);
+                }
+            }
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<KademliaEvent> for KuantumBehaviour {
+    fn inject_event(&mut self, event: KademliaEvent) {
+        match event {
+            KademliaEvent::RoutingUpdated { peer, is_new_peer: true, addresses, .. } => {
+                println!("DHT yeni peer buldu: {}", peer);
+                for address in addresses.iter() {
+                    self.peer_store.observe(peer, Some(address));
+                }
+                self.add_known_peer(peer);
+            }
+            KademliaEvent::OutboundQueryCompleted { result: QueryResult::Bootstrap(result), .. } => match result {
+                Ok(ok) if ok.num_remaining == 0 => {
+                    println!("DHT önyüklemesi tamamlandı, yönlendirme tablosunda {} peer var", self.dht_peer_count());
+                }
+                Ok(_) => {}
+                Err(e) => println!("DHT önyükleme hatası: {:?}", e),
+            },
+            _ => {}
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<IdentifyEvent> for KuantumBehaviour {
+    fn inject_event(&mut self, event: IdentifyEvent) {
+        // Gelen bağlantılarda karşı tarafın dinleme adresleri ancak kimlik bilgisiyle öğrenilir
+        if let IdentifyEvent::Received { peer_id, info } = event {
+            // NAT arkasındaki düğüm genel adresini ancak eşlerden öğrenir
+            self.observed_addresses.push(info.observed_addr);
+
+            if !info.protocols.iter().any(|protocol| protocol.as_bytes() == DHT_PROTOCOL) {
+                return;
+            }
+
+            for address in info.listen_addrs {
+                self.peer_store.observe(peer_id, Some(&address));
+                self.kademlia.add_address(&peer_id, address);
+            }
+            self.peer_store.set_protocols(&peer_id, info.protocols);
+        }
+    }
+}
+
+impl NetworkBehaviourEventProcess<RequestResponseEvent<Vec<u8>, DirectResponse>> for KuantumBehaviour {
+    fn inject_event(&mut self, event: RequestResponseEvent<Vec<u8>, DirectResponse>) {
+        match event {
+            RequestResponseEvent::Message { peer, message: RequestResponseMessage::Request { request, channel, .. } } => {
+                self.connections.on_activity(&peer);
+                let response = match self.handle_direct_request(peer, &request) {
+                    Ok(()) => DirectResponse::Ack,
+                    Err(e) => {
+                        self.on_frame_error(peer, &e);
+                        DirectResponse::Error(e.to_string())
+                    }
+                };
+
+                // Gönderen beklemekten vazgeçtiyse cevap kanalı kapanmış olabilir
+                let _ = self.direct.send_response(channel, response);
+            }
+            RequestResponseEvent::Message { peer, message: RequestResponseMessage::Response { request_id, response } } => {
+                self.connections.on_activity(&peer);
+                if self.deliveries.on_response(&request_id, &response).is_none() {
+                    return;
+                }
+
+                match response {
+                    DirectResponse::Ack => self.record_peer_event(peer, PeerEvent::Relayed),
+                    DirectResponse::Error(reason) => println!("Doğrudan teslim reddedildi, peer: {}: {}", peer, reason),
+                }
+            }
+            RequestResponseEvent::OutboundFailure { peer, request_id, error } => {
+                let delivery = match self.deliveries.on_failure(&request_id) {
+                    Some(delivery) => delivery,
+                    None => return,
+                };
+
+                match error {
+                    // Paket karşıya hiç ulaşmadı, yayın katmanıyla yeniden dene
+                    OutboundFailure::DialFailure | OutboundFailure::UnsupportedProtocols => {
+                        println!("Doğrudan teslim yapılamadı ({:?}), yayın katmanı kullanılıyor, peer: {}", error, peer);
+                        if let Err(e) = self.publish_addressed(delivery.peer, delivery.data) {
+                            println!("Paket gönderme hatası: {}", e);
+                        }
+                    }
+                    // Paket ulaşmış olabilir, tekrar göndermek tekrar saldırısı gibi görünür
+                    OutboundFailure::Timeout | OutboundFailure::ConnectionClosed => {
+                        println!("Doğrudan teslim onaylanmadı ({:?}), peer: {}", error, peer);
+                        if let OutboundFailure::Timeout = error {
+                            self.record_peer_event(peer, PeerEvent::Timeout);
+                        }
+                    }
+                }
+            }
+            RequestResponseEvent::InboundFailure { peer, error, .. } => {
+                println!("Doğrudan teslim isteği alınamadı ({:?}), peer: {}", error, peer);
+            }
+            RequestResponseEvent::ResponseSent { .. } => {}
+        }
+    }
+}
+
+impl KuantumBehaviour {
+    // Yeni bir anonim token oluştur
+    #[allow(dead_code)]
+    fn create_anonymous_token(&self, data: &[u8], ttl: u32) -> Result<Token> {
+        let mut anon_protocol = self.anonymous_protocol.lock().unwrap();
+        
+        // İletinin türünü belirle
+        let msg_type = MessageType::Binary;
+        
+        // Kimliksiz mesaj oluştur
+        let anon_message = anon_protocol.create_message(msg_type, data, 0)?;
+        
+        // Mesajı şifrele
+        let encrypted_data = anon_protocol.encrypt_message(&anon_message)?;
+        
+        // Token oluştur
+        let token = Token {
+            id: Uuid::new_v4().to_string(),
+            encrypted_data,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            ttl,
+        };
+        
+        Ok(token)
+    }
+    
+    // Soğan paket oluştur
+    fn create_onion_packet(&mut self, data: &[u8], route: &[RouteHop]) -> Result<OnionPacket> {
+        // Başlıkta her düğüme bir dilim ayrılır, yük her düğümün anahtar akışıyla sarılır;
+        // geçici anahtarlar ve nonce'lar düğümün RNG'sinden alınır, tohum verildiyse tekrar üretilebilir
+        onion_routing::build_forward_packet(route, data, &mut *self.rng)
+    }
+
+    // Rotadaki düğümlerin yönlendirme anahtarlarını bul
+    fn resolve_route(&self, route: &[PeerId]) -> Result<Vec<RouteHop>> {
+        route.iter()
+            .map(|peer_id| {
+                let relay_key = if *peer_id == self.local_peer_id {
+                    self.relay_keys.public_bytes()
+                } else {
+                    *self.relay_directory.get(peer_id)
+                        .ok_or_else(|| anyhow!("Yönlendirme anahtarı bilinmiyor: {}", peer_id))?
+                };
+
+                Ok(RouteHop::new(*peer_id, relay_key))
+            })
+            .collect()
+    }
+
+    // Verilen düğümler dışındaki bilinen düğümlerden rastgele aracı rota seç
+    fn random_relay_route(&self, exclude: &[PeerId]) -> Result<Vec<RouteHop>> {
+        let relays: Vec<PeerId> = self.relay_directory.keys()
+            .filter(|peer_id| !exclude.contains(peer_id))
+            .cloned()
+            .collect();
+
+        let route = self.chaotic_router.lock().unwrap()
+            .generate_random_route(&relays, REPLY_ROUTE_HOPS);
+
+        self.resolve_route(&route)
+    }
+
+    // Şu anda erişilebilen, yönlendirme anahtarı bilinen ve puanı rotaya yeten düğümler
+    fn available_relays(&self) -> Vec<PeerId> {
+        let router = self.chaotic_router.lock().unwrap();
+
+        self.relay_directory.keys()
+            .filter(|peer_id| self.known_peers.contains(peer_id) && router.is_eligible(peer_id))
+            .cloned()
+            .collect()
+    }
+
+    // Eşin davranışını puanla, yasaklanan eşin bağlantısı ana döngüde kesilir
+    fn record_peer_event(&mut self, peer_id: PeerId, event: PeerEvent) {
+        if peer_id == self.local_peer_id {
+            return;
+        }
+
+        if self.reputation.record(peer_id, event) {
+            println!("Peer yasaklandı ({:?}), puan: {:.1}: {}", event, self.reputation.score(&peer_id), peer_id);
+            self.pending_bans.push(peer_id);
+        }
+        self.sync_reputation();
+        self.drop_unusable_routes();
+    }
+
+    // Hata geçersiz bir paketten kaynaklandıysa göndereni cezalandır ve true döndür
+    fn record_invalid_packet(&mut self, peer_id: PeerId, error: &anyhow::Error) -> bool {
+        match pubsub::invalid_packet_event(error) {
+            Some(event) => {
+                self.record_peer_event(peer_id, event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Çerçeve işleme hatasını kaydet, geçersiz paket gönderen cezalandırılırsa true döner
+    // Hız sınırı hataları sınır devreye girdiğinde bir kez yazıldığından burada tekrar yazılmaz;
+    // eşe ait sınırı aşan eş de her sınırlama döneminde bir kez cezalandırılır, düğüm geneli kotalar kimseye yazılmaz
+    fn on_frame_error(&mut self, peer_id: PeerId, error: &anyhow::Error) -> bool {
+        if let Some(exceeded) = error.downcast_ref::<LimitExceeded>() {
+            return match exceeded.peer {
+                Some(peer) if peer == peer_id => {
+                    if exceeded.first {
+                        self.record_peer_event(peer, PeerEvent::Spam);
+                    }
+                    true
+                }
+                _ => false,
+            };
+        }
+
+        println!("Çerçeve işleme hatası, peer: {}: {}", peer_id, error);
+        self.record_invalid_packet(peer_id, error)
+    }
+
+    // Güncel puanları yönlendiriciye bildir
+    fn sync_reputation(&mut self) {
+        self.chaotic_router.lock().unwrap().set_reputation(self.reputation.route_scores());
+    }
+
+    fn take_pending_bans(&mut self) -> Vec<PeerId> {
+        std::mem::take(&mut self.pending_bans)
+    }
+
+    // Yasağı dolan eşleri döndür
+    fn expire_bans(&mut self) -> Vec<PeerId> {
+        let unbanned = self.reputation.expire();
+        self.sync_reputation();
+
+        unbanned
+    }
+
+    // Koruma düğümü kümesini güncelle ve değiştiyse kaydet
+    fn refresh_guards(&mut self) {
+        let candidates = self.available_relays();
+        if self.guards.refresh(&candidates) {
+            self.save_guards();
+            self.drop_unusable_routes();
+        }
+
+        let guards: Vec<PeerId> = self.guards.guards().iter()
+            .filter_map(|guard| guard.peer_id.parse().ok())
+            .collect();
+        self.peer_store.retain_role(PeerRole::Guard, &guards);
+    }
+
+    // Güncel puanları düğüm kaydına yaz ve kaydı diske kaydet
+    fn save_peer_store(&mut self) {
+        for (peer_id, score) in self.reputation.route_scores() {
+            if score.is_finite() {
+                self.peer_store.set_reputation(&peer_id, score);
+            }
+        }
+
+        if let Err(e) = self.peer_store.save() {
+            println!("Düğüm kaydı kaydedilemedi: {}", e);
+        }
+    }
+
+    // Boşta kalan ve hedef sayıyı aşan bağlantılardan kapatılacak düğümler
+    // Koruma ve önyükleme düğümleri korunur, düşük puanlı düğümler önce çıkarılır
+    fn connections_to_close(&mut self, bootstrap_peers: &[PeerId]) -> Vec<PeerId> {
+        let mut protected: HashSet<PeerId> = self.guards.guards().iter()
+            .filter_map(|guard| guard.peer_id.parse().ok())
+            .collect();
+        protected.extend(bootstrap_peers);
+
+        self.connections.select_for_closing(&self.reputation.route_scores(), &protected)
+    }
+
+    // Adresi kayıtlı düğümleri ana döngüde aranmak üzere sıraya koy, bağlı olunanlar atlanır
+    fn redial_known_peers(&mut self) {
+        let candidates: Vec<_> = self.peer_store.dial_candidates().into_iter()
+            .filter(|(peer_id, _)| *peer_id != self.local_peer_id && !self.reputation.is_banned(peer_id))
+            .take(MAX_REDIAL_PEERS)
+            .collect();
+
+        for (peer_id, addresses) in candidates {
+            // Arama sırasında adresler DHT tablosundan alınır
+            for address in addresses {
+                self.kademlia.add_address(&peer_id, address);
+            }
+            self.pending_dials.push(peer_id);
+        }
+    }
+
+    fn save_guards(&self) {
+        if let Err(e) = self.guards.save() {
+            println!("Koruma düğümleri kaydedilemedi: {}", e);
+        }
+    }
+
+    // Yönlendirme anahtarını ağa duyur
+    fn announce_relay_key(&mut self) {
+        self.publish_frame(&Frame::RelayAnnounce { relay_key: self.relay_keys.public_bytes() });
+    }
+
+    // Veriyi etkin yayın katmanıyla tüm eşlere gönder
+    fn publish(&mut self, data: Vec<u8>) {
+        if let Some(gossipsub) = self.gossipsub.as_mut() {
+            match gossipsub.publish(self.gossip_topic.clone(), data) {
+                // Henüz eş yoksa ya da aynı içerik az önce yayınlandıysa gönderilecek bir şey yok
+                Ok(_) | Err(PublishError::InsufficientPeers) | Err(PublishError::Duplicate) => {}
+                Err(e) => println!("Yayın hatası: {:?}", e),
+            }
+        } else if let Some(floodsub) = self.floodsub.as_mut() {
+            floodsub.publish(self.topic.clone(), data);
+        }
+    }
+
+    // Çerçeveyi tüm eşlere yayınla
+    fn publish_frame(&mut self, frame: &Frame) {
+        match bincode::serialize(frame) {
+            Ok(data) => self.publish(data),
+            Err(e) => println!("Çerçeve oluşturma hatası: {}", e),
+        }
+    }
+
+    // Yayın katmanından gelen veriyi işle
+    // Ağ çerçevesi olarak çözülemeyen veriler düz mesaj sayılır
+    fn handle_pubsub_message(&mut self, source: PeerId, data: &[u8]) -> Result<()> {
+        enforce_limit(self.rate_limiter.check_message(source, Protocol::Pubsub, data.len()))?;
+
+        if let Ok(frame) = bincode::deserialize::<Frame>(data) {
+            return self.handle_frame(source, frame);
+        }
+
+        println!("Mesaj alındı: '{}', gönderen: {}", String::from_utf8_lossy(data), source);
+
+        // Gelen mesajı işle
+        if let Err(e) = self.process_message(&source, data) {
+            println!("Mesaj işleme hatası: {}", e);
+        }
+
+        Ok(())
+    }
+
+    // Eşe yayın katmanı üzerinden bağlan
+    fn connect_peer(&mut self, peer_id: PeerId) {
+        if self.reputation.is_banned(&peer_id) {
+            return;
+        }
+
+        match self.floodsub.as_mut() {
+            Some(floodsub) => floodsub.add_node_to_partial_view(peer_id),
+            // Gossipsub kendisi bağlantı kurmaz, eş ana döngüde aranır
+            None => self.pending_dials.push(peer_id),
+        }
+    }
+
+    fn take_pending_dials(&mut self) -> Vec<PeerId> {
+        std::mem::take(&mut self.pending_dials)
+    }
+
+    fn take_observed_addresses(&mut self) -> Vec<Multiaddr> {
+        std::mem::take(&mut self.observed_addresses)
+    }
+
+    // Soğan paketini bir sonraki düğüme gönder
+    fn send_onion(&mut self, next_hop: PeerId, packet: OnionPacket) -> Result<()> {
+        let frame = Frame::Onion { to: next_hop.to_bytes(), packet };
+
+        // Kendimize ağ üzerinden gönderemeyiz
+        if next_hop == self.local_peer_id {
+            return self.handle_frame(self.local_peer_id, frame);
+        }
+
+        self.send_direct(next_hop, &frame)
+    }
+
+    // Adreslenmiş çerçeveyi yalnızca alıcısına gönder, alıcı işledikten sonra onaylar
+    // Onay ve hatalar DeliveryTracker ile izlenir
+    fn send_direct(&mut self, to: PeerId, frame: &Frame) -> Result<()> {
+        let data = bincode::serialize(frame)?;
+        let request_id = self.direct.send_request(&to, data.clone());
+        self.deliveries.insert(request_id, to, data);
+
+        Ok(())
+    }
+
+    // Doğrudan teslim edilen çerçeveyi işle
+    // Yalnızca bu düğüme adreslenmiş paketler kabul edilir
+    fn handle_direct_request(&mut self, source: PeerId, data: &[u8]) -> Result<()> {
+        enforce_limit(self.rate_limiter.check_message(source, Protocol::Direct, data.len()))?;
+
+        let frame: Frame = bincode::deserialize(data).map_err(|e| pubsub::invalid_packet(PeerEvent::ProtocolViolation, e))?;
+        let local = self.local_peer_id.to_bytes();
+
+        match frame {
+            Frame::Onion { ref to, .. } | Frame::Crowd { ref to, .. } if *to == local => self.handle_frame(source, frame),
+            _ => Err(pubsub::invalid_packet(PeerEvent::ProtocolViolation, "Doğrudan teslimde yalnızca bu düğüme adreslenmiş paketler kabul edilir")),
+        }
+    }
+
+    // Tek bir düğüme adreslenmiş serileştirilmiş çerçeveyi yayınla
+    // Floodsub 2048 byte'tan büyük mesajları sessizce attığından floodsub modunda büyük çerçeveler parçalanır
+    fn publish_addressed(&mut self, to: PeerId, data: Vec<u8>) -> Result<()> {
+        if !self.floodsub.is_enabled() || data.len() <= FRAGMENT_SIZE {
+            self.publish(data);
+            return Ok(());
+        }
+
+        for fragment in fragment::split(&data, &mut *self.rng)? {
+            let frame = Frame::Fragment { to: to.to_bytes(), fragment };
+            self.publish(bincode::serialize(&frame)?);
+        }
+
+        Ok(())
+    }
+
+    // Yükü rastgele aracılar üzerinden hedefe gönder
+    fn send_payload(&mut self, destination: RouteHop, payload: &OnionPayload) -> Result<()> {
+        // Sağlıklı bir aktif rota varsa onu kullan, yoksa yeni bir rota oluştur
+        let active_hops = self.route_health.choose_route(&destination.peer_id, &mut *self.rng)
+            .map(|route| route.hops.clone());
+        let mut route = match active_hops {
+            Some(hops) => self.resolve_route(&hops)?,
+            None => self.build_fresh_route(&destination.peer_id)?,
+        };
+        route.push(destination);
+
+        self.send_over_route(route, payload)
+    }
+
+    // Koruma düğümü ile başlayan yeni bir rastgele rota oluştur
+    fn build_fresh_route(&self, destination: &PeerId) -> Result<Vec<RouteHop>> {
+        // İlk düğüm her zaman koruma düğümlerinden biridir
+        let guard = self.guards.choose_guard(&self.available_relays())?;
+        let mut route = self.resolve_route(&[guard])?;
+        route.extend(self.random_relay_route(&[guard, *destination, self.local_peer_id])?);
+
+        Ok(route)
+    }
+
+    // Aktif rotaları yokla, başarısız olanları yenileriyle değiştir
+    fn maintain_routes(&mut self) {
+        self.route_health.expire_probes();
+        self.drop_unusable_routes();
+
+        for failed in self.route_health.remove_failed_routes() {
+            println!(
+                "Rota {} art arda {} yoklamaya cevap vermedi, yeni rotaya geçiliyor",
+                failed.id, failed.stats.consecutive_failures
+            );
+        }
+
+        while self.route_health.route_count() < ACTIVE_ROUTE_COUNT {
+            match self.build_fresh_route(&self.local_peer_id) {
+                Ok(route) => {
+                    self.route_health.add_route(route.iter().map(|hop| hop.peer_id).collect());
+                }
+                Err(_) => break,
+            }
+        }
+
+        for route_id in self.route_health.route_ids() {
+            if let Err(e) = self.send_probe(route_id) {
+                println!("Rota {} yoklanamadı: {}", route_id, e);
+                self.route_health.remove_route(route_id);
+            }
+        }
+    }
+
+    // Yasaklanan ya da rota için artık uygun olmayan düğümlerden geçen ve koruma düğümü
+    // kümesinden çıkmış bir düğümle başlayan aktif rotaları bırak
+    fn drop_unusable_routes(&mut self) {
+        let router = self.chaotic_router.lock().unwrap();
+        let removed = self.route_health.remove_routes_where(|route| {
+            route.hops.first().is_some_and(|guard| !self.guards.is_guard(guard))
+                || route.hops.iter().any(|hop| self.reputation.is_banned(hop) || !router.is_eligible(hop))
+        });
+        drop(router);
+
+        for route in removed {
+            println!("Rota {} artık kullanılamayan bir düğümden geçiyor, yeni rotaya geçiliyor", route.id);
+        }
+    }
+
+    // Rota boyunca ilerleyip bu düğüme dönen şifreli yoklama gönder
+    fn send_probe(&mut self, route_id: RouteId) -> Result<()> {
+        let hops = self.route_health.route(route_id)
+            .ok_or_else(|| anyhow!("Rota bulunamadı: {}", route_id))?
+            .hops
+            .clone();
+
+        let mut route = self.resolve_route(&hops)?;
+        route.extend(self.resolve_route(&[self.local_peer_id])?);
+        self.chaotic_router.lock().unwrap().apply_hop_delays(&mut route);
+
+        let delays: Vec<Duration> = route.iter().map(|hop| hop.delay).collect();
+        let timeout = route_health::probe_timeout(ROUTE_PROBE_TIMEOUT, ROUTE_PROBE_HOP_TIMEOUT, &delays);
+        let probe_id: ProbeId = self.rng.gen();
+        self.route_health.start_probe(route_id, probe_id, timeout);
+
+        self.send_over_delayed_route(route, &OnionPayload::Probe { probe_id })
+    }
+
+    // Sahte isteği örtü paketi olarak rastgele bir aracıya gönder
+    // Paket gerçek paketlerle aynı boyutta şifrelenir ve koruma düğümüyle başlayan bir rotadan gider
+    fn send_cover(&mut self, request: FakeHttpRequest) {
+        let relays = self.available_relays();
+        let destination = match cover_traffic::choose_destination(&relays, &self.local_peer_id, &mut *self.rng) {
+            Some(destination) => destination,
+            None => return,
+        };
+
+        let size = request.data.len();
+        // Cevap bekleyen isteklerde dönüş yolu kurulamazsa paket cevapsız gider
+        let reply_block = if request.response_size > 0 { self.create_reply_block().ok() } else { None };
+        let payload = OnionPayload::Cover {
+            response_size: request.response_size as u32,
+            reply_block,
+            data: request.data,
+        };
+
+        let result = self.resolve_route(&[destination])
+            .and_then(|mut hops| self.send_payload(hops.remove(0), &payload));
+        self.cover_stats.record_sent(size, result.is_ok());
+    }
+
+    // Keşfedilen düğümü eşler listesine ekle ve bağlan
+    // Bağlantı kurulunca düğümün yönlendirme anahtarı duyurusu yönlendiriciye ulaşır
+    fn add_known_peer(&mut self, peer_id: PeerId) {
+        if peer_id == self.local_peer_id || self.known_peers.contains(&peer_id) {
+            return;
+        }
+
+        self.connect_peer(peer_id);
+        self.known_peers.push(peer_id);
+        if self.guards.mark_reachable(&peer_id) {
+            self.save_guards();
+        }
+    }
+
+    // Ulaşılamayan düğümü rotalarda kullanılabilen eşlerden çıkar
+    // Koruma düğümüyse erişilemez olarak işaretlenir, yeniden bağlanılınca add_known_peer ile geri döner
+    fn forget_peer(&mut self, peer_id: &PeerId) {
+        self.known_peers.retain(|p| p != peer_id);
+        if self.guards.mark_unreachable(peer_id) {
+            println!("Koruma düğümüne ulaşılamıyor: {}", peer_id);
+            self.save_guards();
+        }
+    }
+
+    // DHT yönlendirme tablosunu yenile, henüz bilinen peer yoksa bir sonraki tura bırakılır
+    fn bootstrap_dht(&mut self) {
+        let _ = self.kademlia.bootstrap();
+    }
+
+    // Birleştirme ve karıştırma için bellekte bekleyen paketlerin toplam boyutu
+    fn buffered_bytes(&self) -> usize {
+        self.fragments.buffered_bytes() + self.mix_queue.buffered_bytes()
+    }
+
+    fn peer_scores(&self) -> HashMap<String, f64> {
+        let gossipsub = match self.gossipsub.as_ref() {
+            Some(gossipsub) => gossipsub,
+            None => return HashMap::new(),
+        };
+
+        self.known_peers.iter()
+            .filter_map(|peer_id| Some((peer_id.to_string(), gossipsub.peer_score(peer_id)?)))
+            .collect()
+    }
+
+    fn dht_peer_count(&mut self) -> usize {
+        self.kademlia.kbuckets().map(|bucket| bucket.num_entries()).sum()
+    }
+
+    // Düğüm durum raporu oluştur
+    fn status(&mut self) -> NodeStatus {
+        NodeStatus {
+            peer_id: self.local_peer_id.to_string(),
+            known_peers: self.known_peers.len(),
+            stored_peers: self.peer_store.len(),
+            dht_peers: self.dht_peer_count(),
+            peer_scores: self.peer_scores(),
+            direct_delivery: self.deliveries.stats(),
+            cover_traffic: self.cover_stats.clone(),
+            cover_generator: None,
+            reputation: self.reputation.report(),
+            rate_limits: self.rate_limiter.report(),
+            connections: self.connections.report(),
+            relays: self.relay_directory.len(),
+            guards: self.guards.guards().iter().map(|guard| guard.peer_id.clone()).collect(),
+            hosted_services: self.services.addresses(),
+            mix_queue: self.mix_queue.len(),
+            routes: self.route_health.report(),
+            circuits: self.circuits.reports(),
+            streams: self.circuits.stream_count(),
+        }
+    }
+
+    // Yükü verilen rota üzerinden gönder
+    // Tek pakete sığmayan yük parçalara bölünür, her parça aynı rotadan ayrı bir paketle gider
+    fn send_over_route(&mut self, mut route: Vec<RouteHop>, payload: &OnionPayload) -> Result<()> {
+        self.chaotic_router.lock().unwrap().apply_hop_delays(&mut route);
+        self.send_over_delayed_route(route, payload)
+    }
+
+    // Gecikmeleri önceden atanmış rota üzerinden gönder
+    fn send_over_delayed_route(&mut self, route: Vec<RouteHop>, payload: &OnionPayload) -> Result<()> {
+        let data = bincode::serialize(payload)?;
+        if data.len() <= onion_routing::PAYLOAD_CAPACITY {
+            let packet = self.create_onion_packet(&data, &route)?;
+            return self.send_onion(route[0].peer_id, packet);
+        }
+
+        for fragment in fragment::split_sized(&data, ONION_FRAGMENT_SIZE, &mut *self.rng)? {
+            let packet = self.create_onion_packet(&bincode::serialize(&OnionPayload::Fragment(fragment))?, &route)?;
+            self.send_onion(route[0].peer_id, packet)?;
+        }
+
+        Ok(())
+    }
+
+    // Yükü silme kodlamalı parçalara bölüp her parçayı ayrık bir rotadan gönder
+    // Her rota farklı bir koruma düğümü ile başlar; parça sayısı erişilebilir koruma düğümü sayısıyla sınırlanır
+    fn send_multipath(&mut self, destination: PeerId, payload: &OnionPayload) -> Result<()> {
+        let destination = self.resolve_route(&[destination])?.remove(0);
+
+        let guards = self.guards.usable_guards(&self.available_relays());
+        let total_shares = MULTIPATH_TOTAL_SHARES.min(guards.len() as u8);
+        if total_shares < MULTIPATH_REQUIRED_SHARES {
+            return Err(anyhow!(
+                "Çok yollu gönderim için en az {} erişilebilir koruma düğümü gerekli, {} var",
+                MULTIPATH_REQUIRED_SHARES,
+                guards.len()
+            ));
+        }
+
+        // Aracılar koruma düğümlerini ve hedefi içermez, rotalar ortak düğüm paylaşmaz
+        let mut exclude = guards.clone();
+        exclude.push(destination.peer_id);
+        let relays: Vec<PeerId> = self.relay_directory.keys()
+            .filter(|peer_id| !exclude.contains(peer_id))
+            .cloned()
+            .collect();
+        let routes = self.chaotic_router.lock().unwrap()
+            .generate_disjoint_routes(&relays, total_shares as usize, REPLY_ROUTE_HOPS)?;
+
+        let message_id: MultipathId = self.rng.gen();
+        let shares = multipath::split_into_shares(
+            message_id,
+            &bincode::serialize(payload)?,
+            MULTIPATH_REQUIRED_SHARES,
+            total_shares,
+        )?;
+
+        for ((share, guard), middle) in shares.into_iter().zip(guards).zip(routes) {
+            let mut route = self.resolve_route(&[guard])?;
+            route.extend(self.resolve_route(&middle)?);
+            route.push(destination.clone());
+
+            self.send_over_route(route, &OnionPayload::Share(share))?;
+        }
+
+        Ok(())
+    }
+
+    // Yükü cevap bloğu kullanarak bloğun sahibine gönder
+    fn send_via_reply_block(&mut self, reply_block: ReplyBlock, payload: &OnionPayload) -> Result<()> {
+        let (first_hop, packet) = reply_block.attach_reply(&bincode::serialize(payload)?, &mut *self.rng)?;
+
+        self.send_onion(first_hop, packet)
+    }
+
+    // Bu düğüme dönen yeni bir cevap bloğu oluştur
+    fn create_reply_block(&mut self) -> Result<ReplyBlock> {
+        let mut return_route = self.random_relay_route(&[self.local_peer_id])?;
+        return_route.extend(self.resolve_route(&[self.local_peer_id])?);
+        self.chaotic_router.lock().unwrap().apply_hop_delays(&mut return_route);
+
+        self.reply_blocks.lock().unwrap().create(&return_route, &mut *self.rng)
+    }
+
+    // Cevap bloğu ekleyerek anonim mesaj gönder
+    // Çok yollu modda mesaj silme kodlamalı parçalar halinde ayrık rotalardan gider
+    fn send_anonymous_message(&mut self, destination: PeerId, body: &[u8], multipath: bool) -> Result<()> {
+        // Dönüş yolunu önceden şifrele ve mesaja ekle
+        let message = OnionPayload::Message(OnionMessage {
+            body: body.to_vec(),
+            reply_block: Some(self.create_reply_block()?),
+        });
+
+        if multipath {
+            return self.send_multipath(destination, &message);
+        }
+
+        let destination = self.resolve_route(&[destination])?.remove(0);
+        self.send_payload(destination, &message)
+    }
+
+    // Alınan cevap bloğunu kullanarak cevap gönder
+    fn send_reply(&mut self, handle: &str, body: &[u8]) -> Result<()> {
+        // Blok kayıttan çıkarılır, ikinci kez kullanılamaz
+        let reply_block = self.received_reply_blocks.remove(handle)
+            .ok_or_else(|| anyhow!("Cevap bloğu bulunamadı ya da kullanılmış: {}", handle))?;
+
+        let message = OnionMessage {
+            body: body.to_vec(),
+            reply_block: None,
+        };
+
+        self.send_via_reply_block(reply_block, &OnionPayload::Message(message))
+    }
+
+    // Hedefe sabit bir rota üzerinden akış denetimli devre aç
+    fn open_circuit(&mut self, destination: PeerId, isolation: Option<String>) -> Result<CircuitId> {
+        let destination = self.resolve_route(&[destination])?.remove(0);
+        let active_hops = self.route_health.choose_route(&destination.peer_id, &mut *self.rng)
+            .map(|route| route.hops.clone());
+        let mut route = match active_hops {
+            Some(hops) => self.resolve_route(&hops)?,
+            None => self.build_fresh_route(&destination.peer_id)?,
+        };
+        route.push(destination);
+
+        let id: CircuitId = self.rng.gen();
+        self.circuits.add_circuit(id, route, isolation);
+
+        Ok(id)
+    }
+
+    // Çıkış düğümüne giden ve aynı yalıtım anahtarını taşıyan açık bir devre bul, yoksa yenisini aç
+    fn exit_circuit(&mut self, exit: PeerId, isolation: Option<String>) -> Result<CircuitId> {
+        match self.circuits.find_exit(&exit, &isolation) {
+            Some(id) => Ok(id),
+            None => self.open_circuit(exit, isolation),
+        }
+    }
+
+    // Veriyi devrenin kuyruğuna ekle, pencere izin verdikçe gönderilir
+    fn send_on_circuit(&mut self, handle: &str, msg_type: MessageType, data: &[u8]) -> Result<()> {
+        let id = self.circuits.find(handle)?;
+        self.circuits.send_message(id, Priority::from(msg_type), data)?;
+
+        self.send_circuit_outputs()
+    }
+
+    fn close_circuit(&mut self, handle: &str) -> Result<()> {
+        let id = self.circuits.find(handle)?;
+        self.circuits.close(&id);
+
+        Ok(())
+    }
+
+    // Devre üzerinde yeni bir akış aç
+    // Dönen tutamaç AsyncRead + AsyncWrite olarak doğrudan kullanılabilir
+    fn open_stream(&mut self, circuit_id: CircuitId, priority: Priority, target: Option<String>) -> Result<KuantumStream> {
+        let stream = self.circuits.open_stream(circuit_id, priority, target)?;
+        self.send_circuit_outputs()?;
+
+        Ok(stream)
+    }
+
+    // Akış tutamaçlarından gelen komutu işle
+    fn handle_stream_command(&mut self, command: StreamCommand) -> Result<()> {
+        self.circuits.handle_stream_command(command)?;
+        self.send_circuit_outputs()
+    }
+
+    // Devre yöneticisinin bıraktığı hücreleri gönder
+    // Bir hücre gönderilemese de diğer devrelerin hücreleri gider, ilk hata döner
+    fn send_circuit_outputs(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for output in self.circuits.take_outputs() {
+            result = result.and(self.send_circuit_output(output));
+        }
+
+        result
+    }
+
+    // Hedefin geri yönde kullanacağı ve SENDME için gereken cevap blokları burada oluşturulur
+    fn send_circuit_output(&mut self, output: CircuitOutput) -> Result<()> {
+        match output {
+            CircuitOutput::Forward { route, circuit_id, seq, frame, wants_sendme, reply_blocks } => {
+                let sendme_block = if wants_sendme { Some(self.create_reply_block()?) } else { None };
+                let reply_blocks = (0..reply_blocks)
+                    .map(|_| self.create_reply_block())
+                    .collect::<Result<Vec<_>>>()?;
+                let cell = CircuitCell::Data { circuit_id, seq, frame, sendme_block, reply_blocks };
+
+                self.send_over_route(route, &OnionPayload::Circuit(cell))
+            }
+            CircuitOutput::Reply { reply_block, cell } => self.send_via_reply_block(reply_block, &OnionPayload::Circuit(cell)),
+        }
+    }
+
+    // Zaman aşımına uğrayan SENDME'leri işle ve bekleyen hücreleri gönder
+    fn maintain_circuits(&mut self) {
+        for notice in self.circuits.maintain() {
+            match notice {
+                CircuitNotice::SendmesLost { circuit_id, lost } => {
+                    println!("Devre {}: {} SENDME zamanında gelmedi, pencere küçültüldü", hex::encode(&circuit_id[..4]), lost);
+                }
+                CircuitNotice::BackwardLost(id) => {
+                    println!("Devre {}: geri yön hücresi kayboldu, devre kapatılıyor", hex::encode(&id[..4]));
+                }
+                CircuitNotice::ForwardLost(id) => {
+                    println!("Devre {}: ileri yön hücresi kayboldu, devre kapatılıyor", hex::encode(&id[..4]));
+                }
+                CircuitNotice::CloseFailed { key, error } => {
+                    println!("Akış {} kapatma hatası: {}", key.handle(), error);
+                }
+            }
+        }
+
+        if let Err(e) = self.send_circuit_outputs() {
+            println!("Devre hücre gönderme hatası: {}", e);
+        }
+    }
+
+    // Devre hücresini işle
+    // `source` hücreyi getiren son aracıdır, yeni devreler onun kotasından düşülür
+    fn handle_circuit_cell(&mut self, source: PeerId, cell: CircuitCell) -> Result<()> {
+        if self.circuits.is_new_inbound(&cell) {
+            enforce_limit(self.rate_limiter.check_circuit(source))?;
+        }
+
+        let messages = self.circuits.handle_cell(cell);
+        self.send_circuit_outputs()?;
+
+        for message in messages? {
+            let handle = hex::encode(&message.circuit_id[..4]);
+            match message.priority {
+                Priority::Interactive => println!("Devre {} mesajı: '{}'", handle, String::from_utf8_lossy(&message.body)),
+                Priority::Bulk => println!("Devre {} verisi alındı: {} bayt", handle, message.body.len()),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Soğan paketindeki hatayı gönderen eşe yalnızca paketi kendisi oluşturduysa yükle
+    // Bu düğümün katmanını ve tekrarlanan başlıkları önceki düğüm doğrulayamaz; yönlendirme anahtarı
+    // duyurmamış bir eş ise rotalarda yer alamayacağından paketi ancak kendisi oluşturmuş olabilir
+    fn onion_fault(&self, source: &PeerId, event: PeerEvent, reason: String) -> anyhow::Error {
+        if self.relay_directory.contains_key(source) {
+            anyhow!(reason)
+        } else {
+            pubsub::invalid_packet(event, reason)
+        }
+    }
+
+    // Ağ çerçevesini işle
+    fn handle_frame(&mut self, source: PeerId, frame: Frame) -> Result<()> {
+        match frame {
+            Frame::RelayAnnounce { relay_key } => {
+                // Yeni bir düğüm öğrendiğimizde kendi anahtarımızı da duyur
+                self.peer_store.observe(source, None);
+                self.peer_store.add_role(&source, PeerRole::Relay);
+                if self.relay_directory.insert(source, relay_key) != Some(relay_key) {
+                    println!("Yönlendirme anahtarı alındı, peer: {}", source);
+                    self.announce_relay_key();
+                    self.refresh_guards();
+                }
+            }
+            Frame::Onion { to, packet } => {
+                // Bize adreslenmemiş paketleri yok say
+                if to != self.local_peer_id.to_bytes() {
+                    return Ok(());
+                }
+
+                // Her katman çözme denemesi ortak işlem bütçesinden düşülür
+                enforce_limit(self.rate_limiter.check_crypto())?;
+
+                // Aynı başlık ikinci kez geldiyse paket döngüde ya da tekrar gönderilmiş
+                if !self.seen_packets.insert(&packet.header) {
+                    return Err(self.onion_fault(&source, PeerEvent::Spam, "Tekrarlanan soğan paketi atıldı".to_string()));
+                }
+
+                let peeled = self.relay_keys.peel(&packet)
+                    .map_err(|e| self.onion_fault(&source, PeerEvent::DecryptionFailed, format!("Soğan paketi çözülemedi: {}", e)))?;
+                match peeled {
+                    PeeledPacket::Forward { next_hop, delay, packet } => {
+                        // Sınırı aşan gecikmeler paketi kuyrukta tutarak bellek tüketmek için kullanılabilir
+                        if delay > MAX_HOP_DELAY {
+                            return Err(anyhow!("Gecikmesi üst sınırı aşan soğan paketi atıldı: {:?}", delay));
+                        }
+
+                        // Mixnet modunda paketi gönderenin seçtiği süre kadar beklet
+                        if delay.is_zero() {
+                            self.send_onion(next_hop, packet)?;
+                        } else {
+                            let size = packet.header.len() + packet.payload.len();
+                            enforce_limit(self.rate_limiter.check_memory(self.buffered_bytes(), size))?;
+                            self.mix_queue.push(delay, next_hop, packet);
+                        }
+                    }
+                    PeeledPacket::Deliver { reply_id: None, payload } => {
+                        self.deliver_onion_payload(source, &payload, false)?;
+                    }
+                    PeeledPacket::Deliver { reply_id: Some(reply_id), payload } => {
+                        let payload = self.reply_blocks.lock().unwrap().open_reply(&reply_id, &payload)?;
+                        self.deliver_onion_payload(source, &payload, true)?;
+                    }
+                }
+            }
+            Frame::Fragment { to, fragment } => {
+                if to != self.local_peer_id.to_bytes() {
+                    return Ok(());
+                }
+
+                enforce_limit(self.rate_limiter.check_memory(self.buffered_bytes(), fragment.data.len()))?;
+
+                // Tüm parçalar geldiğinde çerçeveyi birleştir ve normal şekilde işle
+                if let Some(data) = self.fragments.insert(fragment).map_err(|e| pubsub::invalid_packet(PeerEvent::ProtocolViolation, e))? {
+                    match bincode::deserialize(&data).map_err(|e| pubsub::invalid_packet(PeerEvent::ProtocolViolation, e))? {
+                        frame @ (Frame::Onion { .. } | Frame::Crowd { .. }) => self.handle_frame(source, frame)?,
+                        _ => return Err(pubsub::invalid_packet(PeerEvent::ProtocolViolation, "Parçalanmış çerçeve adreslenmiş bir paket değil")),
+                    }
+                }
+            }
+            Frame::ServiceDescriptor(descriptor) => {
+                let address = descriptor.address();
+                if self.services.insert_descriptor(descriptor)? {
+                    println!("Gizli servis tanımlayıcısı alındı: {}", address);
+                }
+            }
+            Frame::Crowd { to, packet } => {
+                if to == self.local_peer_id.to_bytes() {
+                    self.handle_crowd_packet(source, packet)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Soğan rotasının sonunda teslim alınan yükü işle
+    fn deliver_onion_payload(&mut self, source: PeerId, payload: &[u8], is_reply: bool) -> Result<()> {
+        match bincode::deserialize::<OnionPayload>(payload)? {
+            OnionPayload::Message(message) => {
+                if is_reply {
+                    println!("Anonim cevap alındı: '{}'", String::from_utf8_lossy(&message.body));
+                } else {
+                    println!("Anonim mesaj alındı: '{}'", String::from_utf8_lossy(&message.body));
+                }
+
+                if let Some(reply_block) = message.reply_block {
+                    let handle = reply_block.id.chars().take(8).collect::<String>();
+                    println!("Cevaplamak için: reply {} <mesaj>", handle);
+                    self.received_reply_blocks.insert(handle, reply_block);
+                }
+
+                Ok(())
+            }
+            OnionPayload::Service(cell) => self.handle_service_cell(cell),
+            OnionPayload::Probe { probe_id } => {
+                self.route_health.record_probe_reply(&probe_id);
+                Ok(())
+            }
+            OnionPayload::Circuit(cell) => self.handle_circuit_cell(source, cell),
+            OnionPayload::Fragment(fragment) => {
+                enforce_limit(self.rate_limiter.check_memory(self.buffered_bytes(), fragment.data.len()))?;
+
+                // Tüm parçalar geldiğinde yükü birleştir ve işle
+                match self.fragments.insert(fragment)? {
+                    Some(payload) => self.deliver_onion_payload(source, &payload, is_reply),
+                    None => Ok(()),
+                }
+            }
+            OnionPayload::Share(share) => {
+                // Yeterli parça geldiğinde yükü birleştir ve işle
+                match self.share_assembler.accept(share)? {
+                    Some(payload) => self.deliver_onion_payload(source, &payload, is_reply),
+                    None => Ok(()),
+                }
+            }
+            OnionPayload::Cover { response_size, reply_block, .. } => {
+                self.cover_stats.record_received();
+
+                if let Some(reply_block) = reply_block {
+                    // Cevap bloğu tek paket taşır, sahte cevap da pakete sığacak kadar kısaltılır
+                    let empty = OnionPayload::Cover { data: Vec::new(), response_size: 0, reply_block: None };
+                    let room = onion_routing::PAYLOAD_CAPACITY - bincode::serialized_size(&empty)? as usize;
+                    let response = OnionPayload::Cover {
+                        data: vec![0; cover_traffic::response_body_size(response_size, room)],
+                        response_size: 0,
+                        reply_block: None,
+                    };
+                    self.send_via_reply_block(reply_block, &response)?;
+                    self.cover_stats.record_answered();
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    // Yeni bir gizli servis oluştur ve yayınla
+    fn host_hidden_service(&mut self) -> Result<String> {
+        let address = self.services.host()?;
+        self.publish_hidden_services();
+
+        Ok(address)
+    }
+
+    // Barındırılan servislerin tanıtım noktalarını yenile ve tanımlayıcılarını yayınla
+    fn publish_hidden_services(&mut self) {
+        for address in self.services.addresses() {
+            if let Err(e) = self.services.publish(&address, &self.relay_directory) {
+                println!("Gizli servis yayınlama hatası ({}): {}", address, e);
+            }
+        }
+
+        if let Err(e) = self.send_service_outputs() {
+            println!("Gizli servis yayınlama hatası: {}", e);
+        }
+    }
+
+    // Gizli servise rastgele bir rendezvous noktası üzerinden bağlan
+    fn connect_hidden_service(&mut self, address: &str) -> Result<String> {
+        let rendezvous = self.random_relay_route(&[self.local_peer_id])?
+            .pop()
+            .ok_or_else(|| anyhow!("Rendezvous noktası olabilecek düğüm yok"))?;
+
+        let session = self.services.connect(address, rendezvous)?;
+        self.send_service_outputs()?;
+
+        Ok(session)
+    }
+
+    // Kurulu oturum üzerinden karşı tarafa veri gönder
+    fn send_service_data(&mut self, handle: &str, data: &[u8]) -> Result<()> {
+        self.services.send_data(handle, data)?;
+        self.send_service_outputs()
+    }
+
+    // Gizli servis yöneticisinin bıraktığı hücreleri gönder, ilk hata döndürülür
+    fn send_service_outputs(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for output in self.services.take_outputs() {
+            result = result.and(self.send_service_output(output));
+        }
+
+        result
+    }
+
+    // Karşı tarafın bu düğüme dönmek için kullanacağı cevap blokları burada oluşturulur
+    fn send_service_output(&mut self, output: ServiceOutput) -> Result<()> {
+        match output {
+            ServiceOutput::Send { to, mut cell, reply_blocks } => {
+                let reply_blocks = (0..reply_blocks)
+                    .map(|_| self.create_reply_block())
+                    .collect::<Result<Vec<_>>>()?;
+                cell.attach_reply_blocks(reply_blocks);
+
+                self.send_payload(to, &OnionPayload::Service(cell))
+            }
+            ServiceOutput::Reply { reply_block, cell } => self.send_via_reply_block(reply_block, &OnionPayload::Service(cell)),
+            ServiceOutput::Publish(descriptor) => {
+                self.publish_frame(&Frame::ServiceDescriptor(descriptor));
+                Ok(())
+            }
+        }
+    }
+
+    // Crowds paketini bir sonraki jondo'ya gönder
+    fn send_crowd_packet(&mut self, next_hop: PeerId, packet: CrowdPacket) -> Result<()> {
+        if next_hop == self.local_peer_id {
+            return self.handle_crowd_packet(self.local_peer_id, packet);
+        }
+
+        let frame = Frame::Crowd { to: next_hop.to_bytes(), packet };
+        self.send_direct(next_hop, &frame)
+    }
+
+    // Crowds rastgele yürüyüşü ile hedefe mesaj gönder
+    fn send_crowd_message(&mut self, destination: PeerId, body: &[u8]) -> Result<()> {
+        let relay_key = self.resolve_route(&[destination])?.remove(0).relay_key;
+        let reply_key: [u8; 32] = self.rng.gen();
+        let path_id: CrowdPathId = self.rng.gen();
+        let (mut hop_token, hop_count) = self.chaotic_router.lock().unwrap().new_hop_token(0);
+
+        // İçerik ve sayaç çapası yalnızca hedef tarafından açılabilir
+        let request = CrowdRequest { body: body.to_vec(), reply_key, hop_anchor: hop_token.anchor() };
+        let sealed = onion_routing::seal_to(&relay_key, &bincode::serialize(&request)?, &mut *self.rng)?;
+
+        self.chaotic_router.lock().unwrap()
+            .record_crowd_path(path_id, CrowdReturnHop::Initiator, hop_token.anchor());
+        self.crowd_reply_keys.insert(path_id, reply_key);
+
+        // Başlatıcının ilk jondo'su koruma düğümlerinden seçilir
+        let first_hop = self.guards.choose_guard(&self.available_relays())?;
+
+        // İlk jondo'ya gönderim bir atlama sayılır, sayaç rastgele başlangıç değerinden ilerler
+        hop_token.advance();
+        let packet = CrowdPacket {
+            path_id,
+            destination: destination.to_bytes(),
+            hop_count: hop_count + 1,
+            hop_token,
+            direction: CrowdDirection::Request,
+            sealed,
+        };
+
+        // Yürüyüş başlatıcıya geri dönerse döngü olarak görülsün
+        self.seen_packets.insert(&packet.loop_key());
+        self.send_crowd_packet(first_hop, packet)
+    }
+
+    // Alınan Crowds isteğini aynı yol üzerinden cevapla
+    fn send_crowd_reply(&mut self, handle: &str, body: &[u8]) -> Result<()> {
+        let (path_id, reply_key, request_hops) = self.crowd_pending_replies.remove(handle)
+            .ok_or_else(|| anyhow!("Crowds isteği bulunamadı ya da cevaplanmış: {}", handle))?;
+
+        // Cevap isteğin yolundan döner, başlangıç değeri isteğin kullandığı atlamalardan sonra kalan bütçeyi aşmaz
+        let (hop_token, hop_count) = self.chaotic_router.lock().unwrap().new_hop_token(request_hops);
+        let reply = CrowdReply { body: body.to_vec(), hop_anchor: hop_token.anchor() };
 
-// Sahte trafik için HTTP isteği simülasyonu
-#[derive(Clone, Debug)]
-struct FakeRequest {
-    method: String,
-    url: String,
-    headers: HashMap<String, String>,
-    body: Option<Vec<u8>>,
-}
+        let packet = CrowdPacket {
+            path_id,
+            destination: Vec::new(),
+            hop_count,
+            hop_token,
+            direction: CrowdDirection::Reply,
+            sealed: onion_routing::seal(&reply_key, &bincode::serialize(&reply)?, &mut *self.rng)?,
+        };
 
-// Network davranışlarını yöneten yapı
-#[derive(NetworkBehaviour)]
-struct KuantumBehaviour {
-    floodsub: Floodsub,
-    mdns: Mdns,
-    #[behaviour(ignore)]
-    anonymous_protocol: Arc<Mutex<AnonymousProtocol>>,
-    #[behaviour(ignore)]
-    chaotic_router: Arc<Mutex<ChaoticRouter>>,
-    #[behaviour(ignore)]
-    multi_layer_encryption: Arc<Mutex<MultiLayerEncryption>>,
-    #[behaviour(ignore)]
-    response_topics: HashMap<String, String>,
-    #[behaviour(ignore)]
-    known_peers: Vec<PeerId>,
-}
+        self.route_crowd_reply(packet)
+    }
 
-impl NetworkBehaviourEventProcess<FloodsubEvent> for KuantumBehaviour {
-    fn inject_event(&mut self, event: FloodsubEvent) {
-        if let FloodsubEvent::Message(message) = event {
-            println!(
-                "Floodsub mesajı alındı: '{}', gönderen: {}",
-                String::from_utf8_lossy(&message.data),
-                message.source
-            );
-            
-            // Gelen mesajı işle
-            if let Err(e) = self.process_message(&message.source, &message.data) {
-                println!("Mesaj işleme hatası: {}", e);
+    // Crowds paketini işle
+    fn handle_crowd_packet(&mut self, source: PeerId, mut packet: CrowdPacket) -> Result<()> {
+        // Sahte ya da limiti aşmış atlama sayılarını at
+        let max_hops = self.chaotic_router.lock().unwrap().max_hops();
+        packet.hop_token.verify(packet.hop_count, max_hops).map_err(|e| pubsub::invalid_packet(PeerEvent::ProtocolViolation, e))?;
+
+        // Yol kimliği atlamalar boyunca değişmez, aynı yolun isteği ikinci kez geldiyse yürüyüş döngüye girmiştir
+        let revisited = !self.seen_packets.insert(&packet.loop_key());
+
+        if packet.direction == CrowdDirection::Reply {
+            // Tekrarı yoldaki herhangi bir düğüm üretmiş olabilir, önceki düğüm cezalandırılmaz
+            if revisited {
+                return Err(anyhow!("Tekrarlanan Crowds cevabı atıldı"));
+            }
+            return self.route_crowd_reply(packet);
+        }
+
+        if revisited {
+            // Sayacı yolda yeniden kuran düğüm çapayı değiştirmek zorundadır
+            let known_anchor = self.chaotic_router.lock().unwrap().crowd_path_anchor(&packet.path_id);
+            if known_anchor.is_some_and(|anchor| anchor != packet.hop_token.anchor()) {
+                return Err(anyhow!("Atlama sayacı yolda yeniden kurulmuş Crowds paketi atıldı"));
+            }
+            if packet.destination == self.local_peer_id.to_bytes() {
+                return Err(anyhow!("Tekrarlanan Crowds isteği atıldı"));
             }
+
+            // Döngü burada kesilir: yol kaydı değiştirilmez, paket doğrudan hedefe teslim edilir
+            let destination = PeerId::from_bytes(&packet.destination)
+                .map_err(|_| anyhow!("Geçersiz Crowds hedefi"))?;
+            packet.hop_count += 1;
+            packet.hop_token.advance();
+            return self.send_crowd_packet(destination, packet);
+        }
+
+        // Cevaplar için önceki düğümü hatırla
+        self.chaotic_router.lock().unwrap()
+            .record_crowd_path(packet.path_id, CrowdReturnHop::Predecessor(source), packet.hop_token.anchor());
+
+        if packet.destination == self.local_peer_id.to_bytes() {
+            return self.deliver_crowd_request(packet);
         }
+
+        // p olasılıkla rastgele bir jondo'ya ilet, aksi halde hedefe teslim et
+        let jondos: Vec<PeerId> = self.relay_directory.keys().cloned().collect();
+        let decision = self.chaotic_router.lock().unwrap().next_crowd_hop(packet.hop_count, &jondos);
+        let next_hop = match decision {
+            CrowdDecision::Forward(peer_id) => peer_id,
+            CrowdDecision::Deliver => PeerId::from_bytes(&packet.destination)
+                .map_err(|_| anyhow!("Geçersiz Crowds hedefi"))?,
+        };
+
+        packet.hop_count += 1;
+        packet.hop_token.advance();
+        self.send_crowd_packet(next_hop, packet)
     }
-}
 
-impl NetworkBehaviourEventProcess<MdnsEvent> for KuantumBehaviour {
-    fn inject_event(&mut self, event: MdnsEvent) {
-        match event {
-            MdnsEvent::Discovered(list) => {
-                for (peer_id, _) in list {
-                    println!("mDNS yeni peer buldu: {}", peer_id);
-                    self.floodsub.add_node_to_partial_view(peer_id);
-                    self.known_peers.push(peer_id);
-                }
+    // Cevabı isteğin geldiği yol boyunca geri ilet
+    fn route_crowd_reply(&mut self, mut packet: CrowdPacket) -> Result<()> {
+        let return_hop = self.chaotic_router.lock().unwrap()
+            .crowd_return_hop(&packet.path_id)
+            .ok_or_else(|| anyhow!("Bilinmeyen Crowds yolu"))?;
+
+        match return_hop {
+            CrowdReturnHop::Predecessor(peer_id) => {
+                packet.hop_count += 1;
+                packet.hop_token.advance();
+                self.send_crowd_packet(peer_id, packet)
             }
-            MdnsEvent::Expired(list) => {
-                for (peer_id, _) in list {
-                    println!("mDNS peer süresi doldu: {}", peer_id);
-                    self.known_peers.retain(|p| p != &peer_id);
+            CrowdReturnHop::Initiator => {
+                let reply_key = self.crowd_reply_keys.remove(&packet.path_id)
+                    .ok_or_else(|| anyhow!("Crowds cevabı zaten alınmış"))?;
+                let reply: CrowdReply = bincode::deserialize(&onion_routing::open(&reply_key, &packet.sealed)?)?;
+
+                // Hedefin mühürlediği çapa paketteki sayaçla aynı değilse sayaç yolda yeniden kurulmuştur
+                if reply.hop_anchor != packet.hop_token.anchor() {
+                    return Err(anyhow!("Atlama sayacı yolda yeniden kurulmuş Crowds cevabı atıldı"));
                 }
+
+                println!("Crowds cevabı alındı: '{}'", String::from_utf8_lossy(&reply.body));
+                Ok(())
             }
         }
     }
-}
 
-impl KuantumBehaviour {
-    // Yeni bir anonim token oluştur
-    fn create_anonymous_token(&self, data: &[u8], ttl: u32) -> Result<Token> {
-        let mut anon_protocol = self.anonymous_protocol.lock().unwrap();
-        
-        // İletinin türünü belirle
-        let msg_type = MessageType::Binary;
-        
-        // Kimliksiz mesaj oluştur
-        let anon_message = anon_protocol.create_message(msg_type, data, 0)?;
-        
-        // Mesajı şifrele
-        let encrypted_data = anon_protocol.encrypt_message(&anon_message)?;
-        
-        // Token oluştur
-        let token = Token {
-            id: Uuid::new_v4().to_string(),
-            encrypted_data,
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
-            ttl,
-        };
-        
-        Ok(token)
+    // Bu düğüme teslim edilen Crowds isteğini aç
+    fn deliver_crowd_request(&mut self, packet: CrowdPacket) -> Result<()> {
+        let request: CrowdRequest = bincode::deserialize(&self.relay_keys.open_sealed(&packet.sealed)?)?;
+
+        // Başlatıcının mühürlediği çapa paketteki sayaçla aynı değilse sayaç yolda yeniden kurulmuştur
+        if request.hop_anchor != packet.hop_token.anchor() {
+            return Err(anyhow!("Atlama sayacı yolda yeniden kurulmuş Crowds isteği atıldı"));
+        }
+
+        let handle = hex::encode(&packet.path_id[..4]);
+
+        println!("Crowds mesajı alındı: '{}'", String::from_utf8_lossy(&request.body));
+        println!("Cevaplamak için: crowd-reply {} <mesaj>", handle);
+
+        self.crowd_pending_replies.insert(handle, (packet.path_id, request.reply_key, packet.hop_count));
+
+        Ok(())
     }
-    
-    // Soğan paket oluştur
-    fn create_onion_packet(&self, data: &[u8], route: Vec<PeerId>) -> Result<OnionPacket> {
-        let mut multi_layer = self.multi_layer_encryption.lock().unwrap();
-        
-        // Çok katmanlı şifreleme yap
-        let mut layers = Vec::new();
-        let mut current_data = data.to_vec();
-        
-        // Her düğüm için bir şifreleme katmanı ekle
-        for _ in &route {
-            let layer = EncryptionLayer::new();
-            let encrypted = layer.encrypt(&current_data)?;
-            current_data = encrypted.clone();
-            layers.push(encrypted);
+
+    // Bekleme süresi dolan mixnet paketlerini ilet
+    fn flush_mix_queue(&mut self) {
+        for (next_hop, packet) in self.mix_queue.pop_due() {
+            if let Err(e) = self.send_onion(next_hop, packet) {
+                println!("Mixnet paketi iletme hatası: {}", e);
+            }
         }
-        
-        // Katmanları ters çevir (en dıştaki önce)
-        layers.reverse();
-        
-        Ok(OnionPacket {
-            layers,
-            route_info: route,
-            current_layer: 0,
-        })
     }
     
-    // Sahte HTTP isteği oluştur
-    fn generate_fake_request(&self) -> FakeRequest {
-        let mut rng = thread_rng();
-        
-        // Rastgele metot seç
-        let methods = ["GET", "POST", "PUT", "DELETE"];
-        let method = methods[rng.gen_range(0..methods.len())].to_string();
-        
-        // Rastgele URL oluştur
-        let domains = ["example.com", "test.org", "dummy.net", "fakesite.io"];
-        let paths = ["api", "users", "data", "posts", "images"];
-        let domain = domains[rng.gen_range(0..domains.len())];
-        let path = paths[rng.gen_range(0..paths.len())];
-        let url = format!("https://{}/{}/{}", domain, path, rng.gen_range(1..1000));
-        
-        // Sahte HTTP headers
-        let mut headers = HashMap::new();
-        headers.insert("User-Agent".to_string(), "KuantumNetwork/1.0".to_string());
-        headers.insert("Content-Type".to_string(), "application/json".to_string());
-        headers.insert("X-Request-ID".to_string(), Uuid::new_v4().to_string());
-        
-        // POST için sahte body oluştur
-        let body = if method == "POST" || method == "PUT" {
-            let mut fake_data = Vec::new();
-            for _ in 0..rng.gen_range(10..100) {
-                fake_data.push(rng.gen::<u8>());
+    // Gizli servis protokolü mesajlarını işle
+    fn handle_service_cell(&mut self, cell: ServiceCell) -> Result<()> {
+        let events = self.services.handle_cell(cell);
+        let sent = self.send_service_outputs();
+
+        for event in events? {
+            match event {
+                ServiceEvent::Connected { address, session } => {
+                    println!("Gizli servise yeni bağlantı: {}, oturum: {}", address, session);
+                }
+                ServiceEvent::Established { address, session } => {
+                    println!("Gizli servis bağlantısı kuruldu: {}, oturum: {}", address, session);
+                }
+                ServiceEvent::Message { session, data } => {
+                    println!("Gizli servis mesajı ({}): '{}'", session, String::from_utf8_lossy(&data));
+                }
             }
-            Some(fake_data)
-        } else {
-            None
-        };
-        
-        FakeRequest {
-            method,
-            url,
-            headers,
-            body,
         }
+
+        sent
     }
-    
+
     // Gelen mesajları çöz ve işle
-    fn process_message(&self, peer_id: &PeerId, data: &[u8]) -> Result<()> {
+    fn process_message(&mut self, peer_id: &PeerId, data: &[u8]) -> Result<()> {
+        enforce_limit(self.rate_limiter.check_crypto())?;
+
         // Çok katmanlı şifrelemeyi açmayı dene
         let multi_layer = self.multi_layer_encryption.lock().unwrap();
         if let Ok(decrypted) = multi_layer.decrypt(data) {
@@ -205,31 +1648,162 @@ impl KuantumBehaviour {
             return Ok(());
         }
         
+        drop(multi_layer);
+
         // Anonim protokol mesajını çözmeyi dene
-        let anon_protocol = self.anonymous_protocol.lock().unwrap();
-        if let Ok(anon_message) = anon_protocol.decrypt_message(data) {
-            if let Some(msg_type) = anon_message.get_message_type() {
-                println!("Anonim mesaj alındı, tür: {}, gönderen: {}", 
-                    msg_type, anon_message.temp_id);
-                return Ok(());
-            }
+        // Crowds iletimi ayrı çerçevelerle yapılır, burada yalnızca düz mesajlar kalır
+        let anon_protocol = self.anonymous_protocol.clone();
+        let anon_protocol = anon_protocol.lock().unwrap();
+        let anon_message = anon_protocol.decrypt_message(data)
+            .map_err(|_| anyhow!("Mesaj işlenemedi, gönderen: {}", peer_id))?;
+
+        // Sayaç şifreli paketin ek verisiyle doğrulandı, limiti aşan mesajlar atılır
+        let max_hops = self.chaotic_router.lock().unwrap().max_hops();
+        if anon_message.hop_count > max_hops {
+            return Err(anyhow!("Atlama limiti aşan anonim mesaj atıldı: {} > {}", anon_message.hop_count, max_hops));
         }
-        
-        // Kaotik yönlendirici ile işlemeyi dene
-        let chaotic_router = self.chaotic_router.lock().unwrap();
-        if chaotic_router.should_forward() {
-            println!("Mesaj kaotik yönlendirici tarafından yönlendirilecek, peer: {}", peer_id);
-            return Ok(());
+
+        let msg_type = anon_message.get_message_type()
+            .ok_or_else(|| anyhow!("Bilinmeyen anonim mesaj türü: {}", anon_message.msg_type))?;
+        println!("Anonim mesaj alındı, tür: {}, gönderen: {}, atlama: {}",
+            msg_type, anon_message.temp_id, anon_message.hop_count);
+
+        // Artırılmış sayaçla yeniden yayınla
+        if self.chaotic_router.lock().unwrap().should_forward() {
+            let forwarded = anon_protocol.forward_message(&anon_message)?;
+            self.publish(forwarded);
         }
-        
-        Err(anyhow!("Mesaj işlenemedi"))
+
+        Ok(())
+    }
+}
+
+// Hız sınırı sonucunu hataya çevir, sınır yeni devreye girdiyse bir kez yaz
+fn enforce_limit(result: std::result::Result<(), LimitExceeded>) -> Result<()> {
+    result.map_err(|e| {
+        if e.first {
+            println!("{}", e);
+        }
+        anyhow::Error::new(e)
+    })
+}
+
+// Komut satırı seçenekleri
+struct CliArgs {
+    // Önyükleme düğümleri
+    peers: Vec<(PeerId, Multiaddr)>,
+    pubsub: PubsubMode,
+    connections: ConnectionConfig,
+    // Örtü trafiğinin örnekleneceği HAR ya da CSV kaydı
+    traffic_model: Option<String>,
+}
+
+// Komut satırı argümanlarını ayrıştır
+// --peer <multiaddr>/p2p/<peer-id> (birden fazla verilebilir), --floodsub,
+// --max-inbound <n>, --max-outbound <n>, --max-per-ip <n>, --idle-timeout <sn>, --traffic-model <dosya>
+fn parse_args() -> Result<CliArgs> {
+    let mut cli = CliArgs {
+        peers: Vec::new(),
+        pubsub: PubsubMode::Gossipsub,
+        connections: DEFAULT_CONNECTIONS,
+        traffic_model: None,
+    };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--peer" => {
+                let value = args.next().ok_or_else(|| anyhow!("--peer için adres gerekli"))?;
+                cli.peers.push(parse_peer_addr(&value)?);
+            }
+            "--floodsub" => cli.pubsub = PubsubMode::Floodsub,
+            "--max-inbound" => cli.connections.max_inbound = parse_number(&arg, args.next())?,
+            "--max-outbound" => cli.connections.max_outbound = parse_number(&arg, args.next())?,
+            "--max-per-ip" => cli.connections.max_per_ip = parse_number(&arg, args.next())?,
+            "--idle-timeout" => cli.connections.idle_timeout = Duration::from_secs(parse_number(&arg, args.next())?),
+            "--traffic-model" => {
+                cli.traffic_model = Some(args.next().ok_or_else(|| anyhow!("--traffic-model için dosya gerekli"))?);
+            }
+            _ => return Err(anyhow!(
+                "Bilinmeyen argüman: {} (kullanım: [--peer <multiaddr>/p2p/<peer-id>]... [--floodsub] \
+                 [--max-inbound <n>] [--max-outbound <n>] [--max-per-ip <n>] [--idle-timeout <sn>] [--traffic-model <dosya>])", arg
+            )),
+        }
+    }
+
+    // Çıkarma hedefi gelen ve giden sınırların toplamını aşmasın
+    let total = (cli.connections.max_inbound + cli.connections.max_outbound) as usize;
+    cli.connections.target_peers = cli.connections.target_peers.min(total * 3 / 4);
+
+    Ok(cli)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T> {
+    let value = value.ok_or_else(|| anyhow!("{} için değer gerekli", flag))?;
+    value.parse().map_err(|_| anyhow!("{} için geçersiz sayı: {}", flag, value))
+}
+
+// Sahte trafik üretecini başlat, üretilen istekler ana döngüye örtü paketi olarak gönderilmek üzere iletilir
+fn start_cover_traffic(generator: &mut FakeTrafficGenerator, sender: &mpsc::UnboundedSender<FakeHttpRequest>) -> Result<FakeTrafficHandle> {
+    let sender = sender.clone();
+
+    generator.start(move |request| {
+        let _ = sender.send(request);
+    })
+}
+
+// Proxy komutunun argümanlarını ayrıştır: <port> <çıkış-peer-id> [shared|host|connection]
+fn parse_proxy_args(args: &str) -> Option<(u16, PeerId, Isolation)> {
+    let mut parts = args.split_whitespace();
+    let port = parts.next()?.parse().ok()?;
+    let exit = parts.next()?.parse().ok()?;
+    let isolation = match parts.next() {
+        Some(value) => Isolation::parse(value)?,
+        None => Isolation::Shared,
+    };
+
+    if parts.next().is_some() {
+        return None;
     }
+
+    Some((port, exit, isolation))
+}
+
+// Akışı konsola bağla: gelen veri yazdırılır, dönen kanala gönderilen veri akışa yazılır
+fn attach_console_stream(stream: KuantumStream) -> mpsc::UnboundedSender<Vec<u8>> {
+    let handle = stream.handle();
+    let (mut reader, mut writer) = io::split(stream);
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+
+    tokio::spawn(async move {
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(count) => println!("Akış {} verisi: '{}'", handle, String::from_utf8_lossy(&buffer[..count])),
+            }
+        }
+        println!("Akış {} kapandı", handle);
+    });
+
+    tokio::spawn(async move {
+        while let Some(data) = receiver.recv().await {
+            if writer.write_all(&data).await.is_err() {
+                return;
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+
+    sender
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Local PeerID oluştur
-    let local_key = identity::Keypair::generate_ed25519();
+    let cli = parse_args()?;
+    
+    // Kayıtlı düğüm kimliğini yükle, ilk açılışta oluşturulur
+    let local_key = node_identity::load_or_generate(&Path::new(DATA_DIR).join("identity.json"))?;
     let local_peer_id = PeerId::from(local_key.public());
     println!("Yerel peer ID: {}", local_peer_id);
     
@@ -237,16 +1811,28 @@ async fn main() -> Result<()> {
     let transport = libp2p::development_transport(local_key.clone()).await?;
     
     // Kuantum ağ davranışları oluştur
-    let topic = floodsub::Topic::new("kuantum-network");
+    let topic = floodsub::Topic::new(pubsub::TOPIC);
+    let gossip_topic = IdentTopic::new(pubsub::TOPIC);
     
     // Anonim protokol oluştur
     let anonymous_protocol = Arc::new(Mutex::new(
-        AnonymousProtocol::new(Duration::from_secs(300))
+        AnonymousProtocol::new(Duration::from_secs(300), anon_protocol::topic_key(pubsub::TOPIC))
     ));
     
+    // Tohum verilmişse tüm rastgele kararlar deterministik RNG'den türetilir
+    let mut root_rng = match std::env::var(SEED_ENV_VAR) {
+        Ok(seed) => {
+            let seed: u64 = seed.parse()
+                .map_err(|_| anyhow!("{} bir sayı olmalı: {}", SEED_ENV_VAR, seed))?;
+            println!("Yönlendirme tohumu: {}", seed);
+            crypto::seeded_rng(seed)
+        }
+        Err(_) => crypto::os_rng(),
+    };
+
     // Kaotik yönlendirici oluştur
     let chaotic_router = Arc::new(Mutex::new(
-        ChaoticRouter::new(0.3, 5)
+        ChaoticRouter::with_rng(0.3, 5, None, crypto::derive_rng(&mut root_rng))
     ));
     
     // Çok katmanlı şifreleme oluştur
@@ -254,67 +1840,271 @@ async fn main() -> Result<()> {
         MultiLayerEncryption::new(3)
     ));
     
+    // Daha önce görülen düğümleri yükle, kayıtlı puanlar puan kaydına aktarılır
+    let peer_store = PeerStore::load(Path::new(DATA_DIR).join("peers.json"), MAX_STORED_PEERS, MAX_PEER_ADDRESSES)?;
+    let mut reputation = ReputationStore::new(REPUTATION_HALF_LIFE, BAN_THRESHOLD, BAN_DURATION);
+    for (peer_id, score) in peer_store.reputations() {
+        reputation.restore(peer_id, score);
+    }
+    println!("Kayıtlı düğüm sayısı: {}", peer_store.len());
+    
+    // Kayıtlı koruma düğümlerini yükle
+    let guards = GuardManager::load(
+        Path::new(DATA_DIR).join("guards.json"),
+        GUARD_COUNT,
+        GUARD_ROTATION_PERIOD,
+        GUARD_UNREACHABLE_TIMEOUT,
+    )?;
+    
+    // Kayıtlı gizli servisleri yükle, tanımlayıcıları ilk duyuruda yayınlanır
+    let services = HiddenServiceManager::load(
+        local_peer_id,
+        ServiceKeyStore::load(Path::new(DATA_DIR).join("services.json"))?,
+        DescriptorCache::new(DESCRIPTOR_VALIDITY),
+        crypto::derive_rng(&mut root_rng),
+    )?;
+    for address in services.addresses() {
+        println!("Kayıtlı gizli servis yüklendi: {}", address);
+    }
+
+    // Cevap bloklarını takip et
+    let reply_blocks = Arc::new(Mutex::new(
+        ReplyBlockStore::new(REPLY_BLOCK_LIFETIME)
+    ));
+    
+    // Devre akışlarını yönet, tutamaç komutları ve gelen akışlar ana döngüde işlenir
+    let (streams, mut stream_commands, mut accepted_streams) = StreamManager::new();
+    
+    // Proxy dinleyicilerinden gelen akış istekleri
+    let (proxy_requester, mut proxy_requests) = mpsc::unbounded_channel::<StreamRequest>();
+    
+    // Bu düğüm çıkış düğümü olarak dış TCP bağlantıları açar mı
+    let mut exit_enabled = false;
+    // Çıkış bağlantılarının hangi adreslere açılabileceği
+    let mut exit_policy = ExitPolicy::new();
+    
+    // Konsoldan yazılan akışlar
+    let mut console_streams: HashMap<String, mpsc::UnboundedSender<Vec<u8>>> = HashMap::new();
+    
     // mDNS yapılandır
     let mdns = Mdns::new(MdnsConfig::default()).await?;
     
-    // Floodsub yapılandır
-    let mut floodsub = Floodsub::new(local_peer_id);
-    floodsub.subscribe(topic.clone());
+    // Kademlia DHT yapılandır, yerel ağ dışındaki düğümler bu tablo üzerinden bulunur
+    let mut kademlia_config = KademliaConfig::default();
+    kademlia_config.set_protocol_name(DHT_PROTOCOL);
+    let kademlia = Kademlia::with_config(local_peer_id, MemoryStore::new(local_peer_id), kademlia_config);
+    
+    // Karşı tarafın dinleme adreslerini öğrenmek için kimlik protokolü
+    let identify = Identify::new(IdentifyConfig::new(IDENTIFY_PROTOCOL.to_string(), local_key.public()));
+    
+    // Adreslenmiş paketler tüm ağa yayılmak yerine doğrudan alıcıya gönderilir
+    let direct = direct::behaviour(DIRECT_REQUEST_TIMEOUT);
+    
+    // Önyükleme düğümleri: daha önce çalışanlar, ortam değişkeni ve komut satırı
+    let mut bootstrap = BootstrapManager::load(
+        Path::new(DATA_DIR).join("bootstrap.json"),
+        BOOTSTRAP_INITIAL_BACKOFF,
+        BOOTSTRAP_MAX_BACKOFF,
+        MAX_SAVED_BOOTSTRAP_PEERS,
+    )?;
+    let env_peers = match std::env::var(BOOTSTRAP_ENV_VAR) {
+        Ok(value) => value.split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(parse_peer_addr)
+            .collect::<Result<Vec<_>>>()?,
+        Err(_) => Vec::new(),
+    };
+    for (peer_id, address) in env_peers.into_iter().chain(cli.peers) {
+        bootstrap.add(peer_id, address);
+    }
+    
+    // Yayın katmanını yapılandır
+    // Gossipsub imzalı mesajlar ve eş puanlaması kullanır, floodsub eski düğümlerle uyumluluk içindir
+    let (floodsub, gossipsub) = match cli.pubsub {
+        PubsubMode::Gossipsub => {
+            let mut gossipsub = Gossipsub::new(MessageAuthenticity::Signed(local_key.clone()), pubsub::gossipsub_config()?)
+                .map_err(|e| anyhow!("Gossipsub oluşturulamadı: {}", e))?;
+            let (score_params, score_thresholds) = pubsub::peer_score_params(&gossip_topic);
+            gossipsub.with_peer_score(score_params, score_thresholds)
+                .map_err(|e| anyhow!("Eş puanlaması ayarlanamadı: {}", e))?;
+            gossipsub.subscribe(&gossip_topic)
+                .map_err(|e| anyhow!("Konuya abone olunamadı: {:?}", e))?;
+            (None, Some(gossipsub))
+        }
+        PubsubMode::Floodsub => {
+            let mut floodsub = Floodsub::new(local_peer_id);
+            floodsub.subscribe(topic.clone());
+            (Some(floodsub), None)
+        }
+    };
+    println!("Yayın katmanı: {:?}", cli.pubsub);
     
     // Ağ davranışlarını yapılandır
     let mut swarm = SwarmBuilder::new(
         transport,
         KuantumBehaviour {
-            floodsub,
+            floodsub: floodsub.into(),
+            gossipsub: gossipsub.into(),
             mdns,
+            kademlia,
+            identify,
+            direct,
+            deliveries: DeliveryTracker::new(),
+            cover_stats: CoverStats::default(),
+            reputation,
+            pending_bans: Vec::new(),
+            rate_limiter: RateLimiter::new(RATE_LIMITS),
+            connections: ConnectionManager::new(cli.connections),
             anonymous_protocol: anonymous_protocol.clone(),
             chaotic_router: chaotic_router.clone(),
+            rng: crypto::derive_rng(&mut root_rng),
             multi_layer_encryption: multi_layer_encryption.clone(),
             response_topics: HashMap::new(),
             known_peers: Vec::new(),
+            peer_store,
+            topic: topic.clone(),
+            gossip_topic,
+            pending_dials: Vec::new(),
+            observed_addresses: Vec::new(),
+            local_peer_id,
+            relay_keys: RelayKeyPair::generate(&mut *root_rng),
+            relay_directory: HashMap::new(),
+            reply_blocks: reply_blocks.clone(),
+            received_reply_blocks: HashMap::new(),
+            services,
+            mix_queue: MixQueue::new(),
+            crowd_reply_keys: HashMap::new(),
+            crowd_pending_replies: HashMap::new(),
+            guards,
+            share_assembler: ShareAssembler::new(Duration::from_secs(300)),
+            route_health: RouteHealthMonitor::new(ROUTE_MAX_PROBE_FAILURES),
+            seen_packets: SeenPacketCache::new(SEEN_PACKET_LIFETIME),
+            fragments: FragmentBuffer::new(FRAGMENT_LIFETIME),
+            circuits: CircuitManager::new(streams, CIRCUIT_TIMEOUTS),
         },
         local_peer_id
     )
+    .connection_limits(cli.connections.swarm_limits())
     .build();
     
     // Yerel adresi dinle
     swarm.listen_on("/ip4/0.0.0.0/tcp/0".parse()?)?;
+    
+    // Önyükleme düğümlerini DHT tablosuna ekle, bağlantılar ana döngüde kurulur
+    for (peer_id, address) in bootstrap.peers() {
+        println!("Önyükleme düğümü: {} ({})", peer_id, address);
+        let behaviour = swarm.behaviour_mut();
+        behaviour.peer_store.observe(peer_id, Some(&address));
+        behaviour.peer_store.add_role(&peer_id, PeerRole::Bootstrap);
+        behaviour.kademlia.add_address(&peer_id, address);
+    }
+    swarm.behaviour_mut().sync_reputation();
+    swarm.behaviour_mut().redial_known_peers();
     println!("Ağı dinlemeye başladı. Herhangi bir terminalde aşağıdaki komutu çalıştırarak bu düğüme bağlanabilirsiniz:");
-    println!("cargo run -- --peer <peer-id>");
+    println!("cargo run -- --peer <dinleme-adresi>/p2p/{}", local_peer_id);
     println!("\nDiğer komutlar:");
     println!("  send <mesaj>  - Bağlı tüm eşlere mesaj gönderir");
+    println!("  anon <peer-id> <mesaj> - Soğan rotası üzerinden cevaplanabilir anonim mesaj gönderir");
+    println!("  multi <peer-id> <mesaj> - Mesajı silme kodlamalı parçalar halinde ayrık rotalardan gönderir");
+    println!("  reply <no> <mesaj>     - Alınan anonim mesajı tek kullanımlık cevap bloğu ile cevaplar");
+    println!("  crowd <peer-id> <mesaj> - Crowds rastgele yürüyüşü ile mesaj gönderir");
+    println!("  crowd-reply <no> <mesaj> - Crowds mesajını aynı yol üzerinden cevaplar");
+    println!("  status                 - Düğüm durumunu ve rota sağlığını JSON olarak gösterir");
+    println!("  guards [reset]         - Koruma düğümlerini listeler ya da sıfırlar");
+    println!("  mixnet <ms>|off        - Ortalama düğüm gecikmesi ile mixnet modunu açar ya da kapatır");
+    println!("  cover start|stop|pause|resume|rate <n> - Örtü trafiği üretecini denetler, argümansız istatistikleri gösterir");
+    println!("  cover profile <profil> - Zamanlama profilini seçer: constant, poisson (varsayılan), onoff, browsing");
+    println!("  cover model <dosya>    - İstek boyutlarını, cevap boyutlarını ve aralıkları HAR ya da CSV kaydından öğrenir");
+    println!("  service-host           - Konumu gizli yeni bir servis yayınlar");
+    println!("  service-connect <adres> - Gizli servise rendezvous noktası üzerinden bağlanır");
+    println!("  service-send <oturum> <mesaj> - Gizli servis oturumuna mesaj gönderir");
+    println!("  circuit <peer-id>      - Hedefe akış denetimli bir devre açar");
+    println!("  circuit-send <no> <mesaj> - Devre üzerinden etkileşimli mesaj gönderir");
+    println!("  circuit-file <no> <dosya> - Dosyayı devre üzerinden toplu veri olarak gönderir");
+    println!("  circuit-close <no>     - Devreyi kapatır");
+    println!("  stream-open <no>       - Devre üzerinde yeni bir akış açar");
+    println!("  stream-send <akış> <mesaj> - Akışa veri yazar");
+    println!("  stream-close <akış>    - Akışı kapatır");
+    println!("  socks <port> <çıkış-peer-id> [yalıtım] - Yerel SOCKS5 proxy başlatır, bağlantılar çıkış düğümüne tünellenir");
+    println!("  http-proxy <port> <çıkış-peer-id> [yalıtım] - Yerel HTTP proxy başlatır (CONNECT ve mutlak URI istekleri)");
+    println!("                         yalıtım: shared (varsayılan), host (hedef başına devre), connection (bağlantı başına devre)");
+    println!("  exit-relay on|off      - Diğer düğümler için çıkış düğümü olmayı açar ya da kapatır");
+    println!("  exit-allow <ip>        - Çıkış politikasının varsayılan olarak reddettiği yerel bir adrese izin verir");
     println!("  exit          - Programdan çıkar");
     println!("\nBu uygulamayı eşler arasında mesajlaşmak için kullanıyorsunuz. Mesajlar şifreli ve anonim olarak iletilecektir.");
     
-    // Sahte trafik üretmek için periyodik görev başlat
-    tokio::spawn(async {
-        loop {
-            // 2-10 saniye arası bekle
-            let wait_time = Duration::from_secs({
-                let mut rng = thread_rng();
-                rng.gen_range(2..10)
-            });
-            sleep(wait_time).await;
-            
-            // Rastgele peer ID oluştur
-            let random_bytes: [u8; 32] = {
-                let mut rng = thread_rng();
-                rng.gen()
-            };
-            
-            if let Ok(random_peer) = PeerId::from_bytes(&random_bytes) {
-                println!("Sahte trafik oluşturuluyor, peer: {}", random_peer);
+    // Sahte istekleri ana döngüye örtü paketi olarak gönderilmek üzere ilet
+    let (cover_sender, mut cover_requests) = mpsc::unbounded_channel::<FakeHttpRequest>();
+    let mut fake_traffic = FakeTrafficGenerator::with_rng(COVER_TRAFFIC_RATE, crypto::derive_rng(&mut root_rng));
+    if let Some(path) = &cli.traffic_model {
+        fake_traffic.set_model(TrafficModel::load(path)?);
+        println!("Örtü trafiği {} kaydından örnekleniyor, saniyede {:.2} istek", path, fake_traffic.rate_per_second);
+    }
+    let mut cover_generator = Some(start_cover_traffic(&mut fake_traffic, &cover_sender)?);
+
+    // Kullanıcı girdilerini ayrı bir iş parçacığında oku
+    // tokio'nun stdin okuması bloklayan bir görevdir ve kapanışta çalışma zamanını bekletir
+    let (line_sender, mut console_lines) = mpsc::unbounded_channel::<std::io::Result<String>>();
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        for line in std::io::stdin().lock().lines() {
+            if line_sender.send(line).is_err() {
+                break;
             }
         }
     });
-
-    // Kullanıcı girdilerini işle
-    let mut stdin = io::BufReader::new(io::stdin()).lines();
+    
+    // Yönlendirme anahtarını periyodik olarak duyur
+    let mut announce_interval = tokio::time::interval(Duration::from_secs(30));
+    
+    // Aktif rotaları düzenli olarak yokla
+    let mut probe_interval = tokio::time::interval(ROUTE_PROBE_INTERVAL);
+    
+    // Mixnet kuyruğunu düzenli aralıklarla boşalt
+    let mut mix_interval = tokio::time::interval(Duration::from_millis(20));
+    
+    // Devre kuyruklarını ve SENDME zaman aşımlarını işle
+    let mut circuit_interval = tokio::time::interval(CIRCUIT_TICK);
+    
+    // DHT yönlendirme tablosunu düzenli olarak yenile
+    let mut dht_interval = tokio::time::interval(DHT_BOOTSTRAP_INTERVAL);
+    
+    // Önyükleme düğümlerine bağlan ve kopanları yeniden dene
+    let mut redial_interval = tokio::time::interval(REDIAL_CHECK_INTERVAL);
+    
+    // Boşta kalan ve fazla bağlantıları kapat
+    let mut connection_interval = tokio::time::interval(CONNECTION_CHECK_INTERVAL);
+    
+    // Ctrl-C de "exit" komutuyla aynı kapanış yolundan geçer
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut ctrl_c_available = true;
     
     loop {
         tokio::select! {
-            line = stdin.next_line() => {
-                let line = line?.expect("stdin kapandı");
+            result = &mut ctrl_c, if ctrl_c_available => {
+                match result {
+                    Ok(()) => {
+                        println!("Kapatma sinyali alındı");
+                        break;
+                    }
+                    Err(e) => {
+                        println!("Ctrl-C dinlenemiyor: {}", e);
+                        ctrl_c_available = false;
+                    }
+                }
+            }
+            line = console_lines.recv() => {
+                // stdin kapandıysa (EOF) ya da okunamıyorsa düğüm "exit" yazılmış gibi kapanır
+                let line = match line {
+                    Some(Ok(line)) => line,
+                    None => break,
+                    Some(Err(e)) => {
+                        println!("Konsol okunamadı: {}", e);
+                        break;
+                    }
+                };
                 if line.is_empty() {
                     continue;
                 }
@@ -323,18 +2113,459 @@ async fn main() -> Result<()> {
                     break;
                 }
                 
-                // Mesajı belirtilen konuya gönder
-                swarm.behaviour_mut().floodsub.publish(topic.clone(), line.as_bytes());
+                let anon_command = line.strip_prefix("anon ").map(|rest| (rest, false))
+                    .or_else(|| line.strip_prefix("multi ").map(|rest| (rest, true)));
+                
+                if let Some((rest, multipath)) = anon_command {
+                    // Soğan rotası üzerinden anonim mesaj gönder
+                    let result = match rest.split_once(' ') {
+                        Some((peer, message)) => match peer.parse::<PeerId>() {
+                            Ok(peer_id) => swarm.behaviour_mut().send_anonymous_message(peer_id, message.as_bytes(), multipath),
+                            Err(_) => Err(anyhow!("Geçersiz peer ID: {}", peer)),
+                        },
+                        None => Err(anyhow!("Kullanım: anon|multi <peer-id> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Anonim mesaj gönderme hatası: {}", e);
+                    }
+                } else if let Some(rest) = line.strip_prefix("reply ") {
+                    // Tek kullanımlık cevap bloğu ile cevap gönder
+                    let result = match rest.split_once(' ') {
+                        Some((handle, message)) => swarm.behaviour_mut().send_reply(handle, message.as_bytes()),
+                        None => Err(anyhow!("Kullanım: reply <no> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Cevap gönderme hatası: {}", e);
+                    }
+                } else if let Some(rest) = line.strip_prefix("crowd ") {
+                    // Crowds modu ile mesaj gönder
+                    let result = match rest.split_once(' ') {
+                        Some((peer, message)) => match peer.parse::<PeerId>() {
+                            Ok(peer_id) => swarm.behaviour_mut().send_crowd_message(peer_id, message.as_bytes()),
+                            Err(_) => Err(anyhow!("Geçersiz peer ID: {}", peer)),
+                        },
+                        None => Err(anyhow!("Kullanım: crowd <peer-id> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Crowds mesajı gönderme hatası: {}", e);
+                    }
+                } else if let Some(rest) = line.strip_prefix("crowd-reply ") {
+                    let result = match rest.split_once(' ') {
+                        Some((handle, message)) => swarm.behaviour_mut().send_crowd_reply(handle, message.as_bytes()),
+                        None => Err(anyhow!("Kullanım: crowd-reply <no> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Crowds cevabı gönderme hatası: {}", e);
+                    }
+                } else if line == "status" {
+                    let mut status = swarm.behaviour_mut().status();
+                    status.cover_generator = cover_generator.as_ref().map(FakeTrafficHandle::stats);
+                    match serde_json::to_string_pretty(&status) {
+                        Ok(status) => println!("{}", status),
+                        Err(e) => println!("Durum raporu oluşturulamadı: {}", e),
+                    }
+                } else if let Some(setting) = line.strip_prefix("guards") {
+                    // Koruma düğümlerini göster ya da sıfırla
+                    let behaviour = swarm.behaviour_mut();
+                    if setting.trim() == "reset" {
+                        behaviour.guards.reset();
+                        behaviour.refresh_guards();
+                        behaviour.save_guards();
+                    }
+                    
+                    for guard in behaviour.guards.guards() {
+                        let status = if guard.unreachable_since.is_some() { "erişilemiyor" } else { "erişilebilir" };
+                        println!("Koruma düğümü: {} ({}), döndürme zamanı: {}", guard.peer_id, status, guard.expires_at);
+                    }
+                } else if let Some(setting) = line.strip_prefix("cover") {
+                    // Sahte trafik üretecini denetle
+                    match (setting.trim(), cover_generator.as_ref()) {
+                        ("start", None) => match start_cover_traffic(&mut fake_traffic, &cover_sender) {
+                            Ok(handle) => {
+                                cover_generator = Some(handle);
+                                println!("Örtü trafiği başlatıldı, saniyede {} paket", fake_traffic.rate_per_second);
+                            }
+                            Err(e) => println!("Örtü trafiği başlatılamadı: {}", e),
+                        },
+                        ("stop", Some(_)) => {
+                            if let Some(handle) = cover_generator.take() {
+                                match handle.stop().await {
+                                    Ok(()) => println!("Örtü trafiği durduruldu"),
+                                    Err(e) => println!("Örtü trafiği durdurulurken hata: {}", e),
+                                }
+                            }
+                        }
+                        ("pause", Some(handle)) => {
+                            handle.pause();
+                            println!("Örtü trafiği duraklatıldı");
+                        }
+                        ("resume", Some(handle)) => {
+                            handle.resume();
+                            println!("Örtü trafiği devam ediyor");
+                        }
+                        (setting, handle) if setting.starts_with("model ") => match TrafficModel::load(setting["model ".len()..].trim()) {
+                            Ok(model) => {
+                                let summary = model.summary();
+                                fake_traffic.rate_per_second = model.mean_rate();
+                                match handle {
+                                    Some(handle) => handle.set_model(model),
+                                    None => fake_traffic.set_model(model),
+                                }
+                                println!(
+                                    "Trafik modeli yüklendi: {} istek, ortalama istek {:.0} byte, cevap {:.0} byte, saniyede {:.2} istek",
+                                    summary.requests, summary.mean_request_bytes, summary.mean_response_bytes, summary.mean_rate_per_second
+                                );
+                            }
+                            Err(e) => println!("Trafik modeli yüklenemedi: {}", e),
+                        },
+                        (setting, handle) if setting.starts_with("profile ") => match setting["profile ".len()..].trim().parse::<ScheduleProfile>() {
+                            Ok(profile) => {
+                                match handle {
+                                    Some(handle) => handle.set_scheduler(profile.scheduler()),
+                                    None => fake_traffic.set_scheduler(profile.scheduler()),
+                                }
+                                println!("Örtü trafiği zamanlama profili: {}", profile);
+                            }
+                            Err(e) => println!("{}", e),
+                        },
+                        (setting, handle) => match setting.strip_prefix("rate ").map(|rate| rate.trim().parse::<f64>()) {
+                            Some(Ok(rate)) if rate.is_finite() && rate >= 0.0 => {
+                                // Durdurulmuş üreteç yeniden başlatıldığında da bu hız kullanılır
+                                fake_traffic.rate_per_second = rate;
+                                if let Some(handle) = handle {
+                                    handle.set_rate(rate);
+                                }
+                                println!("Örtü trafiği hızı: saniyede {} paket", rate);
+                            }
+                            _ => match handle {
+                                Some(handle) => println!("Örtü trafiği: {}", serde_json::to_string(&handle.stats()).unwrap_or_default()),
+                                None => println!("Örtü trafiği durdurulmuş (kullanım: cover start|stop|pause|resume|rate <paket/sn>|profile <profil>|model <dosya>)"),
+                            },
+                        },
+                    }
+                } else if let Some(setting) = line.strip_prefix("mixnet") {
+                    // Mixnet modunu yapılandır
+                    match setting.trim() {
+                        "" => {
+                            let mean_delay = chaotic_router.lock().unwrap().mix_mean_delay();
+                            println!("Mixnet ortalama gecikmesi: {:?}, bekleyen paket: {}", mean_delay, swarm.behaviour().mix_queue.len());
+                        }
+                        "off" => {
+                            chaotic_router.lock().unwrap().set_mix_mean_delay(None);
+                            println!("Mixnet modu kapatıldı");
+                        }
+                        millis => match millis.parse::<u64>() {
+                            Ok(millis) if Duration::from_millis(millis) > MAX_HOP_DELAY => {
+                                println!("Ortalama gecikme en fazla {} ms olabilir", MAX_HOP_DELAY.as_millis());
+                            }
+                            Ok(millis) => {
+                                chaotic_router.lock().unwrap().set_mix_mean_delay(Some(Duration::from_millis(millis)));
+                                println!("Mixnet modu açıldı, ortalama düğüm gecikmesi: {} ms", millis);
+                            }
+                            Err(_) => println!("Kullanım: mixnet <ms>|off"),
+                        },
+                    }
+                } else if line == "service-host" {
+                    match swarm.behaviour_mut().host_hidden_service() {
+                        Ok(address) => println!("Gizli servis yayınlandı: {}", address),
+                        Err(e) => println!("Gizli servis oluşturma hatası: {}", e),
+                    }
+                } else if let Some(address) = line.strip_prefix("service-connect ") {
+                    match swarm.behaviour_mut().connect_hidden_service(address.trim()) {
+                        Ok(session) => println!("Gizli servise bağlanılıyor, oturum: {}", session),
+                        Err(e) => println!("Gizli servis bağlantı hatası: {}", e),
+                    }
+                } else if let Some(rest) = line.strip_prefix("service-send ") {
+                    let result = match rest.split_once(' ') {
+                        Some((session, message)) => swarm.behaviour_mut().send_service_data(session, message.as_bytes()),
+                        None => Err(anyhow!("Kullanım: service-send <oturum> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Gizli servis mesajı gönderme hatası: {}", e);
+                    }
+                } else if let Some(peer) = line.strip_prefix("circuit ") {
+                    let result = match peer.trim().parse::<PeerId>() {
+                        Ok(peer_id) => swarm.behaviour_mut().open_circuit(peer_id, None),
+                        Err(_) => Err(anyhow!("Geçersiz peer ID: {}", peer)),
+                    };
+                    
+                    match result {
+                        Ok(id) => println!("Devre açıldı: {}", hex::encode(&id[..4])),
+                        Err(e) => println!("Devre açma hatası: {}", e),
+                    }
+                } else if let Some(rest) = line.strip_prefix("circuit-send ") {
+                    let result = match rest.split_once(' ') {
+                        Some((handle, message)) => swarm.behaviour_mut().send_on_circuit(handle, MessageType::Text, message.as_bytes()),
+                        None => Err(anyhow!("Kullanım: circuit-send <no> <mesaj>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Devre mesajı gönderme hatası: {}", e);
+                    }
+                } else if let Some(rest) = line.strip_prefix("circuit-file ") {
+                    let result = match rest.split_once(' ') {
+                        Some((handle, path)) => std::fs::read(path.trim())
+                            .map_err(|e| anyhow!("Dosya okunamadı ({}): {}", path.trim(), e))
+                            .and_then(|data| swarm.behaviour_mut().send_on_circuit(handle, MessageType::Binary, &data)),
+                        None => Err(anyhow!("Kullanım: circuit-file <no> <dosya>")),
+                    };
+                    
+                    if let Err(e) = result {
+                        println!("Devre dosya gönderme hatası: {}", e);
+                    }
+                } else if let Some(handle) = line.strip_prefix("circuit-close ") {
+                    if let Err(e) = swarm.behaviour_mut().close_circuit(handle.trim()) {
+                        println!("Devre kapatma hatası: {}", e);
+                    }
+                } else if let Some(handle) = line.strip_prefix("stream-open ") {
+                    let behaviour = swarm.behaviour_mut();
+                    let result = behaviour.circuits.find(handle.trim())
+                        .and_then(|circuit_id| behaviour.open_stream(circuit_id, Priority::Interactive, None));
+                    
+                    match result {
+                        Ok(stream) => {
+                            println!("Akış açıldı: {}", stream.handle());
+                            console_streams.insert(stream.handle(), attach_console_stream(stream));
+                        }
+                        Err(e) => println!("Akış açma hatası: {}", e),
+                    }
+                } else if let Some(rest) = line.strip_prefix("stream-send ") {
+                    let sent = rest.split_once(' ')
+                        .and_then(|(handle, message)| console_streams.get(handle).map(|writer| writer.send(message.as_bytes().to_vec())));
+                    
+                    match sent {
+                        Some(Ok(())) => {}
+                        Some(Err(_)) => println!("Akış kapanmış"),
+                        None => println!("Kullanım: stream-send <akış> <mesaj> (açık akış gerekli)"),
+                    }
+                } else if let Some(handle) = line.strip_prefix("stream-close ") {
+                    // Yazıcı bırakılınca akış kapatılır
+                    if console_streams.remove(handle.trim()).is_none() {
+                        println!("Akış bulunamadı: {}", handle.trim());
+                    }
+                } else if let Some(rest) = line.strip_prefix("socks ") {
+                    // Yerel SOCKS5 dinleyicisi başlat
+                    match parse_proxy_args(rest) {
+                        Some((port, exit, isolation)) => match TcpListener::bind(("127.0.0.1", port)).await {
+                            Ok(listener) => {
+                                println!("SOCKS5 proxy dinleniyor: 127.0.0.1:{}, çıkış: {}, yalıtım: {:?}", port, exit, isolation);
+                                exit_policy.add_own_port(port);
+                                tokio::spawn(proxy::socks5::serve(listener, exit, isolation, proxy_requester.clone()));
+                            }
+                            Err(e) => println!("SOCKS5 proxy başlatılamadı: {}", e),
+                        },
+                        None => println!("Kullanım: socks <port> <çıkış-peer-id> [shared|host|connection]"),
+                    }
+                } else if let Some(rest) = line.strip_prefix("http-proxy ") {
+                    // Yerel HTTP proxy dinleyicisi başlat
+                    match parse_proxy_args(rest) {
+                        Some((port, exit, isolation)) => match TcpListener::bind(("127.0.0.1", port)).await {
+                            Ok(listener) => {
+                                println!("HTTP proxy dinleniyor: 127.0.0.1:{}, çıkış: {}, yalıtım: {:?}", port, exit, isolation);
+                                exit_policy.add_own_port(port);
+                                tokio::spawn(proxy::http::serve(listener, exit, isolation, proxy_requester.clone()));
+                            }
+                            Err(e) => println!("HTTP proxy başlatılamadı: {}", e),
+                        },
+                        None => println!("Kullanım: http-proxy <port> <çıkış-peer-id> [shared|host|connection]"),
+                    }
+                } else if let Some(setting) = line.strip_prefix("exit-relay ") {
+                    match setting.trim() {
+                        "on" => exit_enabled = true,
+                        "off" => exit_enabled = false,
+                        _ => println!("Kullanım: exit-relay on|off"),
+                    }
+                    println!("Çıkış düğümü: {}", if exit_enabled { "açık" } else { "kapalı" });
+                } else if let Some(address) = line.strip_prefix("exit-allow ") {
+                    // Yerel ağdaki bir adrese çıkış bağlantısı açılmasına izin ver
+                    match address.trim().parse() {
+                        Ok(address) => {
+                            exit_policy.allow(address);
+                            println!("Çıkış politikası {} adresine izin veriyor", address);
+                        }
+                        Err(_) => println!("Kullanım: exit-allow <ip>"),
+                    }
+                } else {
+                    // Mesajı belirtilen konuya gönder
+                    swarm.behaviour_mut().publish(line.as_bytes().to_vec());
+                }
+            }
+            _ = announce_interval.tick() => {
+                for peer_id in swarm.behaviour_mut().expire_bans() {
+                    println!("Peer yasağı kalktı: {}", peer_id);
+                    swarm.unban_peer_id(peer_id);
+                }
+                swarm.behaviour_mut().announce_relay_key();
+                swarm.behaviour_mut().publish_hidden_services();
+                swarm.behaviour_mut().refresh_guards();
+                reply_blocks.lock().unwrap().prune_expired();
+                chaotic_router.lock().unwrap().prune_crowd_paths();
+                swarm.behaviour_mut().share_assembler.prune_expired();
+                swarm.behaviour_mut().seen_packets.prune_expired();
+                swarm.behaviour_mut().services.prune_expired();
+                swarm.behaviour_mut().fragments.prune_expired();
+                swarm.behaviour_mut().rate_limiter.prune_idle(RATE_LIMIT_IDLE_TIMEOUT);
+                if swarm.network_info().num_peers() < MIN_CONNECTED_PEERS {
+                    swarm.behaviour_mut().redial_known_peers();
+                }
+                swarm.behaviour_mut().save_peer_store();
+            }
+            _ = probe_interval.tick() => {
+                swarm.behaviour_mut().maintain_routes();
+            }
+            _ = redial_interval.tick() => {
+                for address in bootstrap.due_dials() {
+                    if let Err(e) = swarm.dial_addr(address.clone()) {
+                        println!("Önyükleme düğümüne bağlanılamadı ({}): {}", address, e);
+                    }
+                }
+            }
+            _ = connection_interval.tick() => {
+                let bootstrap_peers: Vec<PeerId> = bootstrap.peers().into_iter().map(|(peer_id, _)| peer_id).collect();
+                for peer_id in swarm.behaviour_mut().connections_to_close(&bootstrap_peers) {
+                    println!("Bağlantı kapatılıyor: {}", peer_id);
+                    let _ = swarm.disconnect_peer_id(peer_id);
+                }
+            }
+            _ = dht_interval.tick() => {
+                swarm.behaviour_mut().bootstrap_dht();
+            }
+            _ = mix_interval.tick() => {
+                swarm.behaviour_mut().flush_mix_queue();
+            }
+            _ = circuit_interval.tick() => {
+                swarm.behaviour_mut().maintain_circuits();
+                console_streams.retain(|_, writer| !writer.is_closed());
+            }
+            Some(command) = stream_commands.next() => {
+                if let Err(e) = swarm.behaviour_mut().handle_stream_command(command) {
+                    println!("Akış hatası: {}", e);
+                }
+            }
+            Some(mut stream) = accepted_streams.next() => {
+                if let Some(target) = stream.target() {
+                    // Hedefli akışlar yalnızca çıkış düğümü açıksa dışarı bağlanır
+                    if !exit_enabled {
+                        println!("Çıkış düğümü kapalı, akış reddedildi: {} -> {}", stream.handle(), target);
+                        tokio::spawn(async move {
+                            let _ = stream.report_connect(ConnectResult::NotAllowed).await;
+                        });
+                        continue;
+                    }
+                    
+                    println!("Çıkış bağlantısı: {} -> {}", stream.handle(), target);
+                    let policy = exit_policy.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = proxy::run_exit(stream, &policy).await {
+                            println!("Çıkış bağlantısı hatası: {}", e);
+                        }
+                    });
+                } else {
+                    println!("Yeni akış: {}", stream.handle());
+                    console_streams.insert(stream.handle(), attach_console_stream(stream));
+                }
+            }
+            Some(request) = cover_requests.recv() => {
+                swarm.behaviour_mut().send_cover(request);
+            }
+            Some(request) = proxy_requests.recv() => {
+                let behaviour = swarm.behaviour_mut();
+                let result = behaviour.exit_circuit(request.exit, request.isolation)
+                    .and_then(|circuit_id| behaviour.open_stream(circuit_id, Priority::Interactive, Some(request.target)));
+                
+                // İstek sahibi vazgeçtiyse akış bırakılır ve kapanır
+                let _ = request.respond.send(result);
             }
             event = swarm.next() => {
-                if let Some(event) = event {
-                    if let SwarmEvent::NewListenAddr { address, .. } = event {
+                let failed_peer = match event {
+                    Some(SwarmEvent::NewListenAddr { address, .. }) => {
                         println!("Dinleme adresi: {}", address);
+                        // Çıkış bağlantıları düğümün kendi dinleyicisine yönlendirilemez
+                        exit_policy.add_own_multiaddr(&address);
+                        for protocol in address.iter() {
+                            if let libp2p::multiaddr::Protocol::Tcp(port) = protocol {
+                                exit_policy.add_own_port(port);
+                            }
+                        }
+                        None
+                    }
+                    Some(SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. }) => {
+                        swarm.behaviour_mut().peer_store.observe(peer_id, None);
+                        // Bağlantı koptuğunda unutulan eş ve koruma düğümü yeniden kullanılabilir olur
+                        swarm.behaviour_mut().add_known_peer(peer_id);
+                        if !swarm.behaviour_mut().connections.on_established(peer_id, &endpoint) {
+                            println!("Aynı IP adresinden çok fazla düğüm bağlı, bağlantı kapatılıyor: {}", peer_id);
+                            let _ = swarm.disconnect_peer_id(peer_id);
+                        }
+                        if bootstrap.on_connected(&peer_id) {
+                            println!("Önyükleme düğümüne bağlanıldı: {}", peer_id);
+                            if let Err(e) = bootstrap.save() {
+                                println!("Önyükleme düğümleri kaydedilemedi: {}", e);
+                            }
+                        }
+                        None
                     }
+                    Some(SwarmEvent::ConnectionClosed { peer_id, num_established, .. }) => {
+                        swarm.behaviour_mut().connections.on_closed(&peer_id, num_established);
+                        if num_established > 0 {
+                            continue;
+                        }
+                        swarm.behaviour_mut().forget_peer(&peer_id);
+                        if let Some(backoff) = bootstrap.on_disconnected(&peer_id) {
+                            println!("Önyükleme düğümü bağlantısı koptu: {}, {:.1} sn sonra yeniden denenecek", peer_id, backoff.as_secs_f64());
+                        }
+                        None
+                    }
+                    Some(SwarmEvent::UnreachableAddr { peer_id, attempts_remaining: 0, .. }) => Some(peer_id),
+                    Some(SwarmEvent::UnknownPeerUnreachableAddr { address, .. }) => {
+                        parse_peer_addr(&address.to_string()).ok().map(|(peer_id, _)| peer_id)
+                    }
+                    _ => None,
+                };
+                
+                if let Some(peer_id) = failed_peer {
+                    if !swarm.is_connected(&peer_id) {
+                        swarm.behaviour_mut().forget_peer(&peer_id);
+                    }
+                    if let Some(backoff) = bootstrap.on_dial_failure(&peer_id) {
+                        println!("Önyükleme düğümüne ulaşılamadı: {}, {:.1} sn sonra yeniden denenecek", peer_id, backoff.as_secs_f64());
+                    }
+                }
+            }
+        }
+        
+        // Yasaklanan eşlerin bağlantılarını kes, yasak süresince yeni bağlantı kabul edilmez
+        for peer_id in swarm.behaviour_mut().take_pending_bans() {
+            swarm.ban_peer_id(peer_id);
+        }
+        
+        // Eşlerin gözlemlediği genel adresler de düğümün kendi adresidir
+        for address in swarm.behaviour_mut().take_observed_addresses() {
+            exit_policy.add_own_multiaddr(&address);
+        }
+        
+        // Gossipsub modunda keşfedilen ama bağlı olunmayan eşleri ara
+        for peer_id in swarm.behaviour_mut().take_pending_dials() {
+            if !swarm.is_connected(&peer_id) {
+                if let Err(e) = swarm.dial(&peer_id) {
+                    println!("Eşe bağlanılamadı: {}, {}", peer_id, e);
                 }
             }
         }
     }
     
+    // Örtü trafiği görevini kapat, hata olsa da düğüm kaydı saklanır
+    if let Some(handle) = cover_generator.take() {
+        if let Err(e) = handle.stop().await {
+            println!("Örtü trafiği durdurulamadı: {}", e);
+        }
+    }
+    swarm.behaviour_mut().save_peer_store();
+    
     Ok(())
 }
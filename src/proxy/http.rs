@@ -0,0 +1,416 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use tokio::io::{copy, copy_bidirectional, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{request_stream, Isolation, StreamRequester};
+use crate::crypto::stream_mux::ConnectResult;
+
+// İstek başlığının en fazla boyutu
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+const DEFAULT_HTTP_PORT: u16 = 80;
+const DEFAULT_CONNECT_PORT: u16 = 443;
+
+// Proxy'ye özel, hedef sunucuya iletilmeyen başlıklar (RFC 7230 6.1)
+// Connection başlığında adı geçen başlıklar da bu bağlantıya özeldir ve iletilmez
+const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "proxy-connection",
+    "proxy-authorization",
+    "proxy-authenticate",
+    "connection",
+    "keep-alive",
+    "te",
+    "trailer",
+    "upgrade",
+];
+
+// Yerel HTTP proxy dinleyicisi
+// `CONNECT host:port` istekleri tünellenir, mutlak URI'li düz istekler hedef sunucuya yeniden yazılarak iletilir
+// Düz isteklerde bağlantı başına tek istek iletilir, cevaptan sonra istemci bağlantısı kapatılır
+pub async fn serve(listener: TcpListener, exit: PeerId, isolation: Isolation, requests: StreamRequester) {
+    loop {
+        let (client, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!("HTTP proxy bağlantı kabul hatası: {}", e);
+                continue;
+            }
+        };
+
+        let requests = requests.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(client, exit, isolation, requests).await {
+                println!("HTTP proxy bağlantı hatası: {}", e);
+            }
+        });
+    }
+}
+
+// Ayrıştırılmış istek başlığı
+struct RequestHead {
+    method: String,
+    uri: String,
+    version: String,
+    headers: Vec<(String, String)>,
+}
+
+async fn handle_client(mut client: TcpStream, exit: PeerId, isolation: Isolation, requests: StreamRequester) -> Result<()> {
+    let (head, body) = read_head(&mut client).await?;
+
+    let head = match parse_head(&head) {
+        Ok(head) => head,
+        Err(e) => {
+            send_status(&mut client, "400 Bad Request").await?;
+            return Err(e);
+        }
+    };
+
+    let tunnel = head.method.eq_ignore_ascii_case("CONNECT");
+    let (target, forwarded) = if tunnel {
+        (with_default_port(&head.uri, DEFAULT_CONNECT_PORT), None)
+    } else {
+        match rewrite_request(&head).and_then(|(target, request)| Ok((target, request, body_length(&head)?))) {
+            Ok((target, request, length)) => (target, Some((request, length))),
+            Err(e) => {
+                send_status(&mut client, "501 Not Implemented").await?;
+                return Err(e);
+            }
+        }
+    };
+
+    let mut stream = match request_stream(&requests, exit, target.clone(), isolation).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            send_status(&mut client, "502 Bad Gateway").await?;
+            return Err(e);
+        }
+    };
+
+    // İstek ve tünel ancak çıkış düğümü hedefe bağlandıktan sonra başlar
+    let result = stream.connected().await;
+    if result != ConnectResult::Succeeded {
+        let status = match result {
+            ConnectResult::NotAllowed => "403 Forbidden",
+            _ => "502 Bad Gateway",
+        };
+        send_status(&mut client, status).await?;
+        return Err(anyhow!("Çıkış düğümü hedefe bağlanamadı ({}): {:?}", target, result));
+    }
+
+    let (request, length) = match forwarded {
+        // Tünel açıldı, bundan sonraki veriler olduğu gibi aktarılır
+        None => {
+            send_status(&mut client, "200 Connection Established").await?;
+            println!("HTTP CONNECT tünellendi: {} -> {}", stream.handle(), target);
+            if !body.is_empty() {
+                stream.write_all(&body).await?;
+            }
+            copy_bidirectional(&mut client, &mut stream).await?;
+
+            return Ok(());
+        }
+        Some(forwarded) => forwarded,
+    };
+
+    // Yeniden yazılmış isteği ve yalnızca bu isteğin gövdesini gönder
+    // Ardışık gönderilmiş sonraki istekler başka bir sunucuya ait olabilir, hedefe iletilmez
+    stream.write_all(&request).await?;
+    let included = body.len().min(length as usize);
+    stream.write_all(&body[..included]).await?;
+    copy(&mut (&mut client).take(length - included as u64), &mut stream).await?;
+    println!("HTTP isteği iletildi: {} {} -> {}", head.method, stream.handle(), target);
+
+    // Sunucu cevabı gönderip bağlantıyı kapatınca istemci bağlantısı da kapanır
+    copy(&mut stream, &mut client).await?;
+    client.shutdown().await?;
+
+    Ok(())
+}
+
+// İstek gövdesinin uzunluğu, gövdesiz isteklerde sıfır
+// Parçalı gövdelerin sonu ayrıştırılmadan bulunamaz, bu yüzden desteklenmez
+fn body_length(head: &RequestHead) -> Result<u64> {
+    if head.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("transfer-encoding")) {
+        return Err(anyhow!("Parçalı istek gövdesi desteklenmiyor"));
+    }
+
+    let mut lengths = head.headers.iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.parse::<u64>().map_err(|_| anyhow!("Geçersiz Content-Length: {}", value)));
+
+    let length = lengths.next().transpose()?.unwrap_or(0);
+    // Çelişkili uzunluklar istek kaçırmaya yol açar
+    if lengths.any(|other| other.ok() != Some(length)) {
+        return Err(anyhow!("Çelişkili Content-Length başlıkları"));
+    }
+
+    Ok(length)
+}
+
+// Boş satıra kadar istek başlığını oku
+// Başlıkla aynı okumada gelen fazla baytlar ayrıca döndürülür
+async fn read_head(client: &mut TcpStream) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            let body = buffer.split_off(end + 4);
+            return Ok((buffer, body));
+        }
+        if buffer.len() > MAX_HEAD_SIZE {
+            return Err(anyhow!("İstek başlığı çok büyük"));
+        }
+
+        let count = client.read(&mut chunk).await?;
+        if count == 0 {
+            return Err(anyhow!("İstemci başlık tamamlanmadan bağlantıyı kapattı"));
+        }
+        buffer.extend_from_slice(&chunk[..count]);
+    }
+}
+
+fn parse_head(head: &[u8]) -> Result<RequestHead> {
+    let text = std::str::from_utf8(head).map_err(|_| anyhow!("İstek başlığı UTF-8 değil"))?;
+    let mut lines = text.split("\r\n");
+
+    let request_line = lines.next().ok_or_else(|| anyhow!("İstek satırı yok"))?;
+    let mut parts = request_line.split_whitespace();
+    let (method, uri, version) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(uri), Some(version), None) => (method, uri, version),
+        _ => return Err(anyhow!("Geçersiz istek satırı: {}", request_line)),
+    };
+
+    let headers = lines
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_once(':')
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| anyhow!("Geçersiz başlık satırı: {}", line))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(RequestHead {
+        method: method.to_string(),
+        uri: uri.to_string(),
+        version: version.to_string(),
+        headers,
+    })
+}
+
+// Mutlak URI'li isteği hedef sunucunun beklediği biçime çevir
+// Bağlantı başına tek istek iletilir, bu yüzden sunucudan bağlantıyı kapatması istenir.
+// Host başlığı isteğin yönlendirildiği sunucudan yazılır; istemcinin farklı bir Host
+// göndermesi aynı sunucudaki başka bir siteye istek kaçırmaya yol açar
+fn rewrite_request(head: &RequestHead) -> Result<(String, Vec<u8>)> {
+    let rest = head.uri.strip_prefix("http://")
+        .ok_or_else(|| anyhow!("Yalnızca http:// adresleri iletilebilir: {}", head.uri))?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(anyhow!("Adreste sunucu yok: {}", head.uri));
+    }
+    // Kullanıcı bilgisi hedefe iletilmez
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+
+    let connection_headers: Vec<String> = head.headers.iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("connection") || name.eq_ignore_ascii_case("proxy-connection"))
+        .flat_map(|(_, value)| value.split(','))
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect();
+
+    let mut request = format!("{} {} {}\r\nHost: {}\r\n", head.method, path, head.version, authority);
+    for (name, value) in &head.headers {
+        let name_lower = name.to_ascii_lowercase();
+        if name_lower != "host"
+            && !HOP_BY_HOP_HEADERS.contains(&name_lower.as_str())
+            && !connection_headers.contains(&name_lower)
+        {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    request.push_str("Connection: close\r\n\r\n");
+
+    Ok((with_default_port(authority, DEFAULT_HTTP_PORT), request.into_bytes()))
+}
+
+// Port belirtilmemişse varsayılan portu ekle
+fn with_default_port(authority: &str, default_port: u16) -> String {
+    let has_port = match authority.rsplit_once(':') {
+        // IPv6 adreslerinde port köşeli parantezden sonra gelir
+        Some((host, port)) => port.parse::<u16>().is_ok() && (!host.starts_with('[') || host.ends_with(']')),
+        None => false,
+    };
+
+    if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:{}", authority, default_port)
+    }
+}
+
+async fn send_status(client: &mut TcpStream, status: &str) -> Result<()> {
+    client.write_all(format!("HTTP/1.1 {}\r\n\r\n", status).as_bytes()).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::tests::run_circuit;
+    use crate::proxy::ExitPolicy;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
+
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn head(request: &str) -> RequestHead {
+        parse_head(request.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parses_request_head() {
+        let head = head("GET http://example.com/a HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n");
+        assert_eq!(head.method, "GET");
+        assert_eq!(head.uri, "http://example.com/a");
+        assert_eq!(head.version, "HTTP/1.1");
+        assert_eq!(head.headers.len(), 2);
+
+        assert!(parse_head(b"GET /\r\n\r\n").is_err());
+        assert!(parse_head(b"GET / HTTP/1.1\r\nbozuk\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn rewrites_absolute_uri_and_drops_hop_by_hop_headers() {
+        let (target, request) = rewrite_request(&head(
+            "GET http://user@example.com:8080/a?b HTTP/1.1\r\nProxy-Connection: keep-alive\r\nAccept: */*\r\n\r\n",
+        )).unwrap();
+
+        assert_eq!(target, "example.com:8080");
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET /a?b HTTP/1.1\r\nHost: example.com:8080\r\nAccept: */*\r\nConnection: close\r\n\r\n"
+        );
+        assert!(rewrite_request(&head("GET https://example.com/ HTTP/1.1\r\n\r\n")).is_err());
+    }
+
+    #[test]
+    fn host_header_follows_routed_authority() {
+        let (target, request) = rewrite_request(&head(
+            "GET http://example.com/ HTTP/1.1\r\nHost: internal.example\r\nAccept: */*\r\n\r\n",
+        )).unwrap();
+
+        assert_eq!(target, "example.com:80");
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET / HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\nConnection: close\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn drops_connection_scoped_headers() {
+        let (_, request) = rewrite_request(&head(concat!(
+            "GET http://example.com/ HTTP/1.1\r\n",
+            "Connection: Upgrade, X-Secret\r\n",
+            "Upgrade: websocket\r\n",
+            "TE: trailers\r\n",
+            "Trailer: Expires\r\n",
+            "x-secret: 1\r\n",
+            "Accept: */*\r\n\r\n",
+        ))).unwrap();
+
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET / HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\nConnection: close\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn adds_default_port() {
+        assert_eq!(with_default_port("example.com", 80), "example.com:80");
+        assert_eq!(with_default_port("example.com:8080", 80), "example.com:8080");
+        assert_eq!(with_default_port("[::1]", 443), "[::1]:443");
+        assert_eq!(with_default_port("[::1]:8443", 443), "[::1]:8443");
+    }
+
+    #[test]
+    fn body_length_rejects_chunked_and_conflicting_lengths() {
+        assert_eq!(body_length(&head("GET http://a/ HTTP/1.1\r\n\r\n")).unwrap(), 0);
+        assert_eq!(body_length(&head("POST http://a/ HTTP/1.1\r\nContent-Length: 5\r\n\r\n")).unwrap(), 5);
+        assert!(body_length(&head("POST http://a/ HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n")).is_err());
+        assert!(body_length(&head("POST http://a/ HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 6\r\n\r\n")).is_err());
+    }
+
+    #[tokio::test]
+    async fn pipelined_request_is_not_forwarded_to_first_host() {
+        // Her bağlantıda gelen baytları kaydedip tek cevap veren sunucu
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_address = server.local_addr().unwrap();
+        let (received_sender, mut received) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = server.accept().await {
+                let (head, body) = read_head(&mut socket).await.unwrap();
+                socket.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").await.unwrap();
+                socket.shutdown().await.unwrap();
+                let mut rest = body;
+                socket.read_to_end(&mut rest).await.unwrap();
+                let _ = received_sender.send((head, rest));
+            }
+        });
+
+        let mut policy = ExitPolicy::new();
+        policy.allow(server_address.ip());
+        let (requester, requests) = mpsc::unbounded_channel();
+        tokio::spawn(run_circuit(requests, policy));
+
+        let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = proxy.local_addr().unwrap();
+        tokio::spawn(serve(proxy, PeerId::random(), Isolation::Shared, requester));
+
+        let pipelined = format!(
+            "POST http://{0}/a HTTP/1.1\r\nContent-Length: 3\r\n\r\nabcGET http://other.example/b HTTP/1.1\r\n\r\n",
+            server_address,
+        );
+        let mut client = TcpStream::connect(proxy_address).await.unwrap();
+        client.write_all(pipelined.as_bytes()).await.unwrap();
+
+        let mut response = Vec::new();
+        timeout(TEST_TIMEOUT, client.read_to_end(&mut response)).await.unwrap().unwrap();
+        assert!(response.ends_with(b"\r\n\r\nok"));
+
+        let (head, rest) = timeout(TEST_TIMEOUT, received.recv()).await.unwrap().unwrap();
+        assert!(String::from_utf8(head).unwrap().starts_with("POST /a HTTP/1.1\r\n"));
+        assert_eq!(rest, b"abc");
+    }
+
+    #[tokio::test]
+    async fn connect_failure_maps_to_status() {
+        let mut policy = ExitPolicy::new();
+        policy.allow(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let (requester, requests) = mpsc::unbounded_channel();
+        tokio::spawn(run_circuit(requests, policy));
+
+        let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = proxy.local_addr().unwrap();
+        tokio::spawn(serve(proxy, PeerId::random(), Isolation::Shared, requester));
+
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let cases = [
+            (format!("CONNECT {} HTTP/1.1\r\n\r\n", closed), "HTTP/1.1 502 Bad Gateway"),
+            ("CONNECT 192.168.1.1:443 HTTP/1.1\r\n\r\n".to_string(), "HTTP/1.1 403 Forbidden"),
+        ];
+        for (request, status) in cases {
+            let mut client = TcpStream::connect(proxy_address).await.unwrap();
+            client.write_all(request.as_bytes()).await.unwrap();
+            let mut response = Vec::new();
+            timeout(TEST_TIMEOUT, client.read_to_end(&mut response)).await.unwrap().unwrap();
+            assert!(String::from_utf8(response).unwrap().starts_with(status));
+        }
+    }
+}
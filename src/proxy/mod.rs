@@ -0,0 +1,405 @@
+use anyhow::{anyhow, Result};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tokio::io::copy_bidirectional;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use crate::crypto::stream_mux::{ConnectResult, KuantumStream};
+
+pub mod http;
+pub mod socks5;
+
+// Proxy akışlarının devrelere dağıtılma biçimi
+// Aynı devreyi paylaşan akışlar çıkış düğümünde birbiriyle ilişkilendirilebilir
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    // Aynı çıkış düğümüne giden tüm akışlar tek devreyi paylaşır
+    Shared,
+    // Her hedef sunucu için ayrı devre
+    PerHost,
+    // Her bağlantı için ayrı devre
+    PerConnection,
+}
+
+impl Isolation {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "shared" => Some(Isolation::Shared),
+            "host" => Some(Isolation::PerHost),
+            "connection" => Some(Isolation::PerConnection),
+            _ => None,
+        }
+    }
+
+    // Akışın kullanacağı devreyi belirleyen anahtar, aynı anahtarlı akışlar devreyi paylaşır
+    pub fn key(&self, target: &str) -> Option<String> {
+        match self {
+            Isolation::Shared => None,
+            Isolation::PerHost => {
+                let host = target.rsplit_once(':').map_or(target, |(host, _)| host);
+                Some(format!("host:{}", host.to_ascii_lowercase()))
+            }
+            Isolation::PerConnection => Some(format!("connection:{}", Uuid::new_v4())),
+        }
+    }
+}
+
+// Proxy bağlantısı için çıkış düğümüne açılacak akış isteği
+// İstekler ana döngüde KuantumBehaviour tarafından karşılanır
+pub struct StreamRequest {
+    pub exit: PeerId,
+    // Bağlanılacak hedef ("host:port")
+    pub target: String,
+    // Ayrı devre gerektiren akışların yalıtım anahtarı
+    pub isolation: Option<String>,
+    pub respond: oneshot::Sender<Result<KuantumStream>>,
+}
+
+pub type StreamRequester = mpsc::UnboundedSender<StreamRequest>;
+
+// Çıkış düğümüne giden yeni bir akış iste
+pub async fn request_stream(
+    requests: &StreamRequester,
+    exit: PeerId,
+    target: String,
+    isolation: Isolation,
+) -> Result<KuantumStream> {
+    let isolation = isolation.key(&target);
+    let (respond, response) = oneshot::channel();
+    requests.send(StreamRequest { exit, target, isolation, respond })
+        .map_err(|_| anyhow!("Düğüm kapandı"))?;
+
+    response.await.map_err(|_| anyhow!("Düğüm kapandı"))?
+}
+
+// Çıkış düğümünün bağlanabileceği hedefleri belirleyen politika
+// Varsayılan olarak loopback, özel ağ (RFC1918), paylaşılan adres alanı, link-local, çoklu yayın ve
+// belirtilmemiş adresler ile düğümün kendi adresleri reddedilir; yerel adreslere izin yalnızca
+// açıkça verilir, düğümün kendi dinlediği portlara hiçbir zaman bağlanılmaz
+#[derive(Debug, Clone, Default)]
+pub struct ExitPolicy {
+    // Yerel olmasına rağmen bağlanılmasına izin verilen adresler
+    allowed: HashSet<IpAddr>,
+    // Düğümün P2P ve proxy dinleyicilerinin portları
+    own_ports: HashSet<u16>,
+    // Düğümün dinlediği ve eşlerin gözlemlediği genel adresler
+    own_addresses: HashSet<IpAddr>,
+}
+
+impl ExitPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(&mut self, address: IpAddr) {
+        self.allowed.insert(address);
+    }
+
+    pub fn add_own_port(&mut self, port: u16) {
+        self.own_ports.insert(port);
+    }
+
+    // Düğümün genel adresini kaydet, bu adrese yerel adres gibi davranılır
+    // Genel adres üzerinden düğümün kendi dinleyicilerine dönülemez
+    pub fn add_own_address(&mut self, address: IpAddr) {
+        let address = canonical(address);
+        if !is_local(&address) {
+            self.own_addresses.insert(address);
+        }
+    }
+
+    // Dinleme ya da gözlemlenen adresteki IP adresini kaydet
+    pub fn add_own_multiaddr(&mut self, address: &Multiaddr) {
+        for protocol in address.iter() {
+            match protocol {
+                Protocol::Ip4(ip) => self.add_own_address(ip.into()),
+                Protocol::Ip6(ip) => self.add_own_address(ip.into()),
+                _ => {}
+            }
+        }
+    }
+
+    // Çözümlenmiş hedef adrese bağlanılabilir mi
+    pub fn permits(&self, target: &SocketAddr) -> bool {
+        let address = canonical(target.ip());
+        if !is_local(&address) && !self.own_addresses.contains(&address) {
+            return true;
+        }
+
+        // İzin listesi düğümün kendi dinleyicilerine yol açmaz
+        self.allowed.contains(&address) && !self.own_ports.contains(&target.port())
+    }
+}
+
+// IPv4 adresi gömülü IPv6 adreslerini IPv4 olarak değerlendir
+// IPv4 eşlemeli (::ffff:a.b.c.d), IPv4 uyumlu (::a.b.c.d) ve NAT64 (64:ff9b::/96) biçimleri
+fn canonical(address: IpAddr) -> IpAddr {
+    let v6 = match address {
+        IpAddr::V6(v6) => v6,
+        IpAddr::V4(_) => return address,
+    };
+
+    let segments = v6.segments();
+    let embedded = Ipv4Addr::from(((segments[6] as u32) << 16) | segments[7] as u32);
+    match segments[..6] {
+        [0, 0, 0, 0, 0, 0xffff] | [0, 0, 0, 0, 0, 0] | [0x64, 0xff9b, 0, 0, 0, 0] => IpAddr::V4(embedded),
+        _ => address,
+    }
+}
+
+// Çıkış düğümünün kendi makinesine, yerel ağına ya da operatör ağına işaret eden adresler
+fn is_local(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(v4) => {
+            let octets = v4.octets();
+            v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_broadcast() || v4.is_multicast()
+                // 0.0.0.0/8 "bu ağ"
+                || octets[0] == 0
+                // 100.64.0.0/10 taşıyıcı NAT paylaşılan adres alanı
+                || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            let segment = v6.segments()[0];
+            // fc00::/7 benzersiz yerel, fe80::/10 link-local, fec0::/10 eski site-local
+            v6.is_loopback() || v6.is_unspecified() || v6.is_multicast()
+                || (segment & 0xfe00) == 0xfc00 || (segment & 0xffc0) == 0xfe80 || (segment & 0xffc0) == 0xfec0
+        }
+    }
+}
+
+// Çıkış düğümünde: akışı hedef adrese bağla ve iki yönde veri aktar
+// Hedef çözümlenir, yalnızca politikanın izin verdiği adreslere bağlanılır
+// Bağlanma sonucu akışı açan tarafa bildirilir, hedefe bağlanılamazsa akış ardından kapatılır
+pub async fn run_exit(mut stream: KuantumStream, policy: &ExitPolicy) -> Result<()> {
+    let mut connection = match connect_target(stream.target(), policy).await {
+        Ok(connection) => connection,
+        Err((result, e)) => {
+            // Bildirim gönderilemese de istemci akışın kapandığını görür
+            let _ = stream.report_connect(result).await;
+            return Err(e);
+        }
+    };
+    stream.report_connect(ConnectResult::Succeeded).await?;
+
+    copy_bidirectional(&mut stream, &mut connection).await?;
+
+    Ok(())
+}
+
+// Hedefi çözümle ve politikanın izin verdiği adreslere bağlan
+// Hata durumunda akışı açan tarafa bildirilecek sonuç da döner
+async fn connect_target(target: Option<&str>, policy: &ExitPolicy) -> std::result::Result<TcpStream, (ConnectResult, anyhow::Error)> {
+    let target = target.ok_or_else(|| (ConnectResult::Failed, anyhow!("Akışın hedefi yok")))?;
+
+    let addresses: Vec<SocketAddr> = lookup_host(target).await
+        .map_err(|e| (ConnectResult::HostUnreachable, anyhow!("Hedef çözümlenemedi ({}): {}", target, e)))?
+        .filter(|address| policy.permits(address))
+        .collect();
+    if addresses.is_empty() {
+        return Err((ConnectResult::NotAllowed, anyhow!("Çıkış politikası hedefi reddetti: {}", target)));
+    }
+
+    // Yeniden çözümleme yapılmaz, denetlenen adreslerin kendisine bağlanılır
+    TcpStream::connect(&addresses[..]).await.map_err(|e| {
+        let result = match e.kind() {
+            ErrorKind::ConnectionRefused => ConnectResult::ConnectionRefused,
+            _ => ConnectResult::HostUnreachable,
+        };
+        (result, anyhow!("Hedefe bağlanılamadı ({}): {}", target, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::circuit::CircuitId;
+    use crate::crypto::circuit_manager::tests::TestLink;
+    use crate::crypto::stream_mux::{StreamManager, STREAM_CHUNK_SIZE, STREAM_WINDOW};
+    use futures::StreamExt;
+    use std::net::Ipv6Addr;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::time::timeout;
+
+    const CIRCUIT: CircuitId = [1; 16];
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn socket(address: IpAddr, port: u16) -> SocketAddr {
+        SocketAddr::new(address, port)
+    }
+
+    #[test]
+    fn default_policy_rejects_local_addresses() {
+        let policy = ExitPolicy::new();
+        let local = [
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)),
+            IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
+            IpAddr::V4(Ipv4Addr::new(169, 254, 1, 1)),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+            IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+            IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1)),
+            IpAddr::V6(Ipv4Addr::LOCALHOST.to_ipv6_mapped()),
+            IpAddr::V4(Ipv4Addr::new(0, 1, 2, 3)),
+            IpAddr::V4(Ipv4Addr::new(100, 64, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(100, 127, 255, 254)),
+            IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)),
+            IpAddr::V4(Ipv4Addr::BROADCAST),
+            IpAddr::V6(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1)),
+            IpAddr::V6(Ipv6Addr::new(0xfec0, 0, 0, 0, 0, 0, 0, 1)),
+            // 64:ff9b::192.168.1.1
+            IpAddr::V6(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0xc0a8, 0x0101)),
+            // ::127.0.0.1
+            IpAddr::V6(Ipv4Addr::LOCALHOST.to_ipv6_compatible()),
+            IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped()),
+        ];
+        for address in local {
+            assert!(!policy.permits(&socket(address, 80)), "{}", address);
+        }
+
+        assert!(policy.permits(&socket(IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34)), 80)));
+        assert!(policy.permits(&socket(IpAddr::V4(Ipv4Addr::new(100, 128, 0, 1)), 80)));
+        assert!(policy.permits(&socket(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)), 443)));
+        // 64:ff9b::93.184.216.34
+        assert!(policy.permits(&socket(IpAddr::V6(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0x5db8, 0xd822)), 443)));
+    }
+
+    #[test]
+    fn own_public_address_is_treated_as_local() {
+        let public = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+        let mut policy = ExitPolicy::new();
+        policy.add_own_port(4001);
+        policy.add_own_multiaddr(&"/ip4/203.0.113.7/tcp/4001".parse().unwrap());
+
+        assert!(!policy.permits(&socket(public, 4001)));
+        assert!(!policy.permits(&socket(public, 80)));
+        assert!(!policy.permits(&socket(IpAddr::V6(Ipv4Addr::new(203, 0, 113, 7).to_ipv6_mapped()), 80)));
+        assert!(policy.permits(&socket(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 8)), 4001)));
+
+        // Açık izin yalnızca dinleyici olmayan portlara geçerlidir
+        policy.allow(public);
+        assert!(policy.permits(&socket(public, 80)));
+        assert!(!policy.permits(&socket(public, 4001)));
+    }
+
+    #[test]
+    fn allow_list_admits_local_address_except_own_ports() {
+        let mut policy = ExitPolicy::new();
+        policy.allow(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        policy.add_own_port(9050);
+
+        assert!(policy.permits(&socket(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080)));
+        assert!(!policy.permits(&socket(IpAddr::V4(Ipv4Addr::LOCALHOST), 9050)));
+        assert!(!policy.permits(&socket(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)), 8080)));
+    }
+
+    #[tokio::test]
+    async fn exit_refuses_resolved_loopback_target() {
+        let (mut manager, _commands, mut accepted) = StreamManager::new();
+        manager.accept(CIRCUIT, 1, Some("localhost:80".to_string())).unwrap();
+        let stream = accepted.next().await.unwrap();
+
+        let error = run_exit(stream, &ExitPolicy::new()).await.unwrap_err();
+        assert!(error.to_string().contains("politikası"));
+    }
+
+    // İstemci ve çıkış düğümünün devre yöneticileri arasında akış isteklerini karşıla
+    // Çıkış düğümünün kabul ettiği akışlar run_exit ile hedefe bağlanır
+    pub(super) async fn run_circuit(requests: mpsc::UnboundedReceiver<StreamRequest>, policy: ExitPolicy) {
+        let (link, mut accepted) = TestLink::new();
+        tokio::spawn(async move {
+            while let Some(stream) = accepted.next().await {
+                let policy = policy.clone();
+                tokio::spawn(async move { run_exit(stream, &policy).await });
+            }
+        });
+
+        link.run(requests).await;
+    }
+
+    // Çıkış düğümüne devre yöneticileri üzerinden bağlı bir SOCKS5 dinleyicisi başlat
+    async fn start_socks5(policy: ExitPolicy) -> SocketAddr {
+        let (requester, requests) = mpsc::unbounded_channel();
+        tokio::spawn(run_circuit(requests, policy));
+
+        let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = proxy.local_addr().unwrap();
+        tokio::spawn(socks5::serve(proxy, PeerId::random(), Isolation::Shared, requester));
+
+        proxy_address
+    }
+
+    // Yöntem seçiminden sonra CONNECT isteğini gönder ve cevap kodunu döndür
+    async fn socks5_connect(proxy_address: SocketAddr, connect: &[u8]) -> (TcpStream, u8) {
+        let mut client = TcpStream::connect(proxy_address).await.unwrap();
+        client.write_all(&[0x05, 0x01, 0x00]).await.unwrap();
+        let mut method = [0u8; 2];
+        client.read_exact(&mut method).await.unwrap();
+        assert_eq!(method, [0x05, 0x00]);
+
+        client.write_all(connect).await.unwrap();
+        let mut reply = [0u8; 10];
+        timeout(TEST_TIMEOUT, client.read_exact(&mut reply)).await.unwrap().unwrap();
+
+        (client, reply[1])
+    }
+
+    #[tokio::test]
+    async fn socks5_reply_follows_exit_connect_result() {
+        let mut policy = ExitPolicy::new();
+        policy.allow(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let proxy_address = start_socks5(policy).await;
+
+        // Dinleyicisi kapatılmış port
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let mut refused = vec![0x05, 0x01, 0x00, 0x01, 127, 0, 0, 1];
+        refused.extend_from_slice(&closed.port().to_be_bytes());
+        assert_eq!(socks5_connect(proxy_address, &refused).await.1, 0x05);
+
+        // Politikanın izin vermediği özel ağ adresi
+        let private = [0x05, 0x01, 0x00, 0x01, 192, 168, 1, 1, 0, 80];
+        assert_eq!(socks5_connect(proxy_address, &private).await.1, 0x02);
+
+        let mut unresolvable = vec![0x05, 0x01, 0x00, 0x03, 19];
+        unresolvable.extend_from_slice(b"nonexistent.invalid");
+        unresolvable.extend_from_slice(&80u16.to_be_bytes());
+        assert_eq!(socks5_connect(proxy_address, &unresolvable).await.1, 0x04);
+    }
+
+    #[tokio::test]
+    async fn socks5_client_reaches_echo_server_through_exit_stream() {
+        let echo = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let echo_address = echo.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = echo.accept().await.unwrap();
+            let (mut reader, mut writer) = socket.split();
+            tokio::io::copy(&mut reader, &mut writer).await
+        });
+
+        let mut policy = ExitPolicy::new();
+        policy.allow(echo_address.ip());
+
+        let proxy_address = start_socks5(policy).await;
+
+        let mut connect = vec![0x05, 0x01, 0x00, 0x01, 127, 0, 0, 1];
+        connect.extend_from_slice(&echo_address.port().to_be_bytes());
+        let (mut client, reply) = socks5_connect(proxy_address, &connect).await;
+        assert_eq!(reply, 0x00);
+
+        // Akış penceresini birkaç kez dolduracak kadar veri
+        let payload: Vec<u8> = (0..STREAM_CHUNK_SIZE * STREAM_WINDOW as usize * 2).map(|i| i as u8).collect();
+        let mut echoed = vec![0u8; payload.len()];
+        let (mut reader, mut writer) = client.split();
+        let transfer = async { tokio::try_join!(writer.write_all(&payload), reader.read_exact(&mut echoed)) };
+        timeout(TEST_TIMEOUT, transfer).await.unwrap().unwrap();
+
+        assert_eq!(echoed, payload);
+    }
+}
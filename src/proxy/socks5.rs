@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use libp2p::PeerId;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use tokio::io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{request_stream, Isolation, StreamRequester};
+use crate::crypto::stream_mux::ConnectResult;
+
+const SOCKS_VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const COMMAND_CONNECT: u8 = 0x01;
+const ADDRESS_IPV4: u8 = 0x01;
+const ADDRESS_DOMAIN: u8 = 0x03;
+const ADDRESS_IPV6: u8 = 0x04;
+
+// SOCKS5 cevap kodları
+const REPLY_SUCCEEDED: u8 = 0x00;
+const REPLY_GENERAL_FAILURE: u8 = 0x01;
+const REPLY_NOT_ALLOWED: u8 = 0x02;
+const REPLY_HOST_UNREACHABLE: u8 = 0x04;
+const REPLY_CONNECTION_REFUSED: u8 = 0x05;
+const REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const REPLY_ADDRESS_NOT_SUPPORTED: u8 = 0x08;
+
+// Yerel SOCKS5 dinleyicisi
+// Her CONNECT isteği çıkış düğümüne giden devre üzerinde ayrı bir akış olarak tünellenir
+pub async fn serve(listener: TcpListener, exit: PeerId, isolation: Isolation, requests: StreamRequester) {
+    loop {
+        let (client, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!("SOCKS5 bağlantı kabul hatası: {}", e);
+                continue;
+            }
+        };
+
+        let requests = requests.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(client, exit, isolation, requests).await {
+                println!("SOCKS5 bağlantı hatası: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_client(mut client: TcpStream, exit: PeerId, isolation: Isolation, requests: StreamRequester) -> Result<()> {
+    negotiate_method(&mut client).await?;
+
+    let target = match read_connect_request(&mut client).await {
+        Ok(target) => target,
+        Err((reply, e)) => {
+            send_reply(&mut client, reply).await?;
+            return Err(e);
+        }
+    };
+
+    let mut stream = match request_stream(&requests, exit, target.clone(), isolation).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            send_reply(&mut client, REPLY_GENERAL_FAILURE).await?;
+            return Err(e);
+        }
+    };
+
+    // Başarı cevabı ancak çıkış düğümü hedefe bağlandıktan sonra gönderilir
+    let result = stream.connected().await;
+    send_reply(&mut client, connect_reply(result)).await?;
+    if result != ConnectResult::Succeeded {
+        return Err(anyhow!("Çıkış düğümü hedefe bağlanamadı ({}): {:?}", target, result));
+    }
+    println!("SOCKS5 bağlantısı tünellendi: {} -> {}", stream.handle(), target);
+
+    copy_bidirectional(&mut client, &mut stream).await?;
+
+    Ok(())
+}
+
+// Kimlik doğrulamasız yöntemi seç
+async fn negotiate_method(client: &mut TcpStream) -> Result<()> {
+    let mut header = [0u8; 2];
+    client.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(anyhow!("Desteklenmeyen SOCKS sürümü: {}", header[0]));
+    }
+
+    let mut methods = vec![0u8; header[1] as usize];
+    client.read_exact(&mut methods).await?;
+
+    if !methods.contains(&NO_AUTHENTICATION) {
+        client.write_all(&[SOCKS_VERSION, NO_ACCEPTABLE_METHODS]).await?;
+        return Err(anyhow!("İstemci kimlik doğrulamasız bağlantıyı desteklemiyor"));
+    }
+
+    client.write_all(&[SOCKS_VERSION, NO_AUTHENTICATION]).await?;
+
+    Ok(())
+}
+
+// CONNECT isteğini oku ve hedefi "host:port" olarak döndür
+// Hata durumunda istemciye gönderilecek cevap kodu da döner
+async fn read_connect_request(client: &mut TcpStream) -> std::result::Result<String, (u8, anyhow::Error)> {
+    let failure = |e: std::io::Error| (REPLY_GENERAL_FAILURE, anyhow!(e));
+
+    let mut header = [0u8; 4];
+    client.read_exact(&mut header).await.map_err(failure)?;
+    if header[0] != SOCKS_VERSION {
+        return Err((REPLY_GENERAL_FAILURE, anyhow!("Desteklenmeyen SOCKS sürümü: {}", header[0])));
+    }
+    if header[1] != COMMAND_CONNECT {
+        return Err((REPLY_COMMAND_NOT_SUPPORTED, anyhow!("Yalnızca CONNECT destekleniyor")));
+    }
+
+    let host = match header[3] {
+        ADDRESS_IPV4 => {
+            let mut address = [0u8; 4];
+            client.read_exact(&mut address).await.map_err(failure)?;
+            Ipv4Addr::from(address).to_string()
+        }
+        ADDRESS_IPV6 => {
+            let mut address = [0u8; 16];
+            client.read_exact(&mut address).await.map_err(failure)?;
+            format!("[{}]", Ipv6Addr::from(address))
+        }
+        ADDRESS_DOMAIN => {
+            // Alan adı çıkış düğümünde çözülür, yerelde DNS sorgusu yapılmaz
+            let length = client.read_u8().await.map_err(failure)?;
+            let mut domain = vec![0u8; length as usize];
+            client.read_exact(&mut domain).await.map_err(failure)?;
+            String::from_utf8(domain)
+                .map_err(|_| (REPLY_ADDRESS_NOT_SUPPORTED, anyhow!("Geçersiz alan adı")))?
+        }
+        other => return Err((REPLY_ADDRESS_NOT_SUPPORTED, anyhow!("Desteklenmeyen adres türü: {}", other))),
+    };
+
+    let port = client.read_u16().await.map_err(failure)?;
+
+    Ok(format!("{}:{}", host, port))
+}
+
+// Çıkış düğümünün bağlanma sonucuna karşılık gelen SOCKS5 cevap kodu
+fn connect_reply(result: ConnectResult) -> u8 {
+    match result {
+        ConnectResult::Succeeded => REPLY_SUCCEEDED,
+        ConnectResult::NotAllowed => REPLY_NOT_ALLOWED,
+        ConnectResult::HostUnreachable => REPLY_HOST_UNREACHABLE,
+        ConnectResult::ConnectionRefused => REPLY_CONNECTION_REFUSED,
+        ConnectResult::Failed => REPLY_GENERAL_FAILURE,
+    }
+}
+
+async fn send_reply(client: &mut TcpStream, reply: u8) -> Result<()> {
+    // Bağlı adres bilgisi anonimlik için boş bırakılır
+    client.write_all(&[SOCKS_VERSION, reply, 0x00, ADDRESS_IPV4, 0, 0, 0, 0, 0, 0]).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Birbirine bağlı iki yerel soket
+    async fn pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (client, server)
+    }
+
+    async fn connect_request(request: &[u8]) -> std::result::Result<String, (u8, anyhow::Error)> {
+        let (mut client, mut server) = pair().await;
+        client.write_all(request).await.unwrap();
+        read_connect_request(&mut server).await
+    }
+
+    #[tokio::test]
+    async fn parses_connect_targets() {
+        assert_eq!(connect_request(&[0x05, 0x01, 0x00, 0x01, 10, 0, 0, 1, 0x01, 0xbb]).await.unwrap(), "10.0.0.1:443");
+
+        let mut domain = vec![0x05, 0x01, 0x00, 0x03, 11];
+        domain.extend_from_slice(b"example.com");
+        domain.extend_from_slice(&80u16.to_be_bytes());
+        assert_eq!(connect_request(&domain).await.unwrap(), "example.com:80");
+
+        let mut ipv6 = vec![0x05, 0x01, 0x00, 0x04];
+        ipv6.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        ipv6.extend_from_slice(&8080u16.to_be_bytes());
+        assert_eq!(connect_request(&ipv6).await.unwrap(), "[::1]:8080");
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_requests_with_reply_code() {
+        // BIND
+        let (reply, _) = connect_request(&[0x05, 0x02, 0x00, 0x01, 10, 0, 0, 1, 0, 80]).await.unwrap_err();
+        assert_eq!(reply, REPLY_COMMAND_NOT_SUPPORTED);
+
+        let (reply, _) = connect_request(&[0x05, 0x01, 0x00, 0x09]).await.unwrap_err();
+        assert_eq!(reply, REPLY_ADDRESS_NOT_SUPPORTED);
+
+        let (reply, _) = connect_request(&[0x05, 0x01, 0x00, 0x03, 2, 0xff, 0xfe, 0, 80]).await.unwrap_err();
+        assert_eq!(reply, REPLY_ADDRESS_NOT_SUPPORTED);
+
+        let (reply, _) = connect_request(&[0x04, 0x01, 0x00, 0x01]).await.unwrap_err();
+        assert_eq!(reply, REPLY_GENERAL_FAILURE);
+    }
+
+    #[tokio::test]
+    async fn negotiation_requires_no_authentication_method() {
+        let (mut client, mut server) = pair().await;
+        client.write_all(&[0x05, 0x02, 0x02, 0x00]).await.unwrap();
+        negotiate_method(&mut server).await.unwrap();
+        let mut answer = [0u8; 2];
+        client.read_exact(&mut answer).await.unwrap();
+        assert_eq!(answer, [SOCKS_VERSION, NO_AUTHENTICATION]);
+
+        let (mut client, mut server) = pair().await;
+        client.write_all(&[0x05, 0x01, 0x02]).await.unwrap();
+        assert!(negotiate_method(&mut server).await.is_err());
+        client.read_exact(&mut answer).await.unwrap();
+        assert_eq!(answer, [SOCKS_VERSION, NO_ACCEPTABLE_METHODS]);
+    }
+}